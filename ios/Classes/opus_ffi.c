@@ -5,16 +5,16 @@
 #include "../../src/opus_ffi.h"
 
 static const void *const kOpusFfiSymbols[] __attribute__((used)) = {
-    (const void *)&new_decoder,
-    (const void *)&decode,
-    (const void *)&decode_float,
-    (const void *)&free_decoder,
-    (const void *)&new_encoder,
-    (const void *)&encode,
-    (const void *)&encode_float,
-    (const void *)&free_encoder,
-    (const void *)&free_c_string,
-    (const void *)&free_opus_error,
+    (const void *)&opus_ffi_new_decoder,
+    (const void *)&opus_ffi_decode,
+    (const void *)&opus_ffi_decode_float,
+    (const void *)&opus_ffi_free_decoder,
+    (const void *)&opus_ffi_new_encoder,
+    (const void *)&opus_ffi_encode,
+    (const void *)&opus_ffi_encode_float,
+    (const void *)&opus_ffi_free_encoder,
+    (const void *)&opus_ffi_free_c_string,
+    (const void *)&opus_ffi_free_opus_error,
 };
 
 void opus_ffi_force_link(void) {
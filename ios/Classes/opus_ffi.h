@@ -8,15 +8,239 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * 比特率特殊值：由编码器根据采样率和声道数自动选择比特率（对应 `OPUS_AUTO`）
+ */
+#define BITRATE_AUTO -1000
+
+/**
+ * 比特率特殊值：使用编码器允许的最大比特率（对应 `OPUS_BITRATE_MAX`）
+ */
+#define BITRATE_MAX -1
+
+/**
+ * 信号类型：由编码器自动判断（对应 `OPUS_AUTO`）
+ */
+#define SIGNAL_AUTO -1000
+
+/**
+ * 信号类型：语音（对应 `OPUS_SIGNAL_VOICE`）
+ */
+#define SIGNAL_VOICE 3001
+
+/**
+ * 信号类型：音乐（对应 `OPUS_SIGNAL_MUSIC`）
+ */
+#define SIGNAL_MUSIC 3002
+
+/**
+ * 带宽特殊值：由编码器根据比特率自动选择带宽（对应 `OPUS_AUTO`）
+ */
+#define BANDWIDTH_AUTO -1000
+
+/**
+ * 强制声道数特殊值：不强制，由编码器自动选择（对应 `OPUS_AUTO`）
+ */
+#define FORCE_CHANNELS_AUTO -1000
+
+/**
+ * 映射族 1 支持的最大声道数（7.1 环绕声）
+ */
+#define MAX_SURROUND_CHANNELS 8
+
+/**
+ * 支持浮点 PCM 接口（`opus_ffi_encode_float`、`opus_ffi_decode_float` 等）
+ */
+#define OPUS_FFI_CAP_FLOAT_API (1 << 0)
+
+/**
+ * 支持多流（环绕声）编解码
+ */
+#define OPUS_FFI_CAP_MULTISTREAM (1 << 1)
+
+/**
+ * 支持 Ogg Opus 容器的读写
+ */
+#define OPUS_FFI_CAP_OGG (1 << 2)
+
+/**
+ * 支持数据包重组
+ */
+#define OPUS_FFI_CAP_REPACKETIZER (1 << 3)
+
+/**
+ * 启用了 `legacy-symbols` feature，同时导出不带 `opus_ffi_` 前缀的旧符号名
+ */
+#define OPUS_FFI_CAP_LEGACY_SYMBOLS (1 << 4)
+
+/**
+ * 音频带宽，取值与 libopus 的 `OPUS_BANDWIDTH_*` 常量相同
+ */
+typedef enum OpusBandwidth {
+  /**
+   * 窄带，4kHz
+   */
+  Narrowband = 1101,
+  /**
+   * 中带，6kHz
+   */
+  Mediumband = 1102,
+  /**
+   * 宽带，8kHz
+   */
+  Wideband = 1103,
+  /**
+   * 超宽带，12kHz
+   */
+  Superwideband = 1104,
+  /**
+   * 全带，20kHz
+   */
+  Fullband = 1105,
+} OpusBandwidth;
+
+/**
+ * 数据包的编码模式
+ */
+typedef enum OpusMode {
+  /**
+   * 仅 SILK（语音，最高宽带）
+   */
+  SilkOnly = 1000,
+  /**
+   * SILK + CELT 混合
+   */
+  Hybrid = 1001,
+  /**
+   * 仅 CELT（音乐或低延迟）
+   */
+  CeltOnly = 1002,
+} OpusMode;
+
+/**
+ * 本库返回的全部错误代码
+ *
+ * `-1` ~ `-8` 与 libopus 的 `OPUS_*` 错误代码相同，`-9` 及以下是本库自己的错误。
+ * 可以通过 `opus_ffi_error_name` 获取错误代码的名称。
+ *
+ */
+typedef enum OpusErrorCode {
+  /**
+   * 成功
+   */
+  OPUS_ERROR_CODE_OK = 0,
+  /**
+   * 参数无效（`OPUS_BAD_ARG`）
+   */
+  OPUS_ERROR_CODE_BAD_ARG = -1,
+  /**
+   * 输出缓冲区太小（`OPUS_BUFFER_TOO_SMALL`）
+   */
+  OPUS_ERROR_CODE_BUFFER_TOO_SMALL = -2,
+  /**
+   * libopus 内部错误（`OPUS_INTERNAL_ERROR`）
+   */
+  OPUS_ERROR_CODE_INTERNAL_ERROR = -3,
+  /**
+   * 数据包损坏或不合法（`OPUS_INVALID_PACKET`）
+   */
+  OPUS_ERROR_CODE_INVALID_PACKET = -4,
+  /**
+   * 请求的功能未实现（`OPUS_UNIMPLEMENTED`）
+   */
+  OPUS_ERROR_CODE_UNIMPLEMENTED = -5,
+  /**
+   * 编解码器状态无效，或对象已结束使用（`OPUS_INVALID_STATE`）
+   */
+  OPUS_ERROR_CODE_INVALID_STATE = -6,
+  /**
+   * 内存分配失败（`OPUS_ALLOC_FAIL`）
+   */
+  OPUS_ERROR_CODE_ALLOC_FAIL = -7,
+  /**
+   * 未知的 libopus 错误
+   */
+  OPUS_ERROR_CODE_UNKNOWN = -8,
+  /**
+   * 传入了空指针或不是 UTF-8 的字符串
+   */
+  OPUS_ERROR_CODE_INVALID_INPUT = -9,
+  /**
+   * Rust 代码发生 panic，错误描述中包含 panic 信息和位置
+   */
+  OPUS_ERROR_CODE_PANIC = -10,
+  /**
+   * 文件读写错误
+   */
+  OPUS_ERROR_CODE_IO = -11,
+  /**
+   * 容器格式错误（不是 Ogg Opus 流、头部损坏等）
+   */
+  OPUS_ERROR_CODE_FORMAT = -12,
+  /**
+   * 不支持的声道数或应用模式
+   */
+  OPUS_ERROR_CODE_UNSUPPORTED = -13,
+  /**
+   * 句柄已被释放、从未创建或类型不符
+   */
+  OPUS_ERROR_CODE_INVALID_HANDLE = -14,
+} OpusErrorCode;
+
 /**
  * Opus 解码器不透明指针类型
  */
 typedef struct Decoder Decoder;
+
 /**
  * Opus 编码器不透明指针类型
+ *
+ * 直接持有 libopus 的编码器状态，编码参数通过 `opus_encoder_ctl` 读写。
  */
 typedef struct Encoder Encoder;
 
+/**
+ * 多流解码器不透明指针类型
+ */
+typedef struct MultistreamDecoder MultistreamDecoder;
+
+/**
+ * 多流编码器不透明指针类型
+ */
+typedef struct MultistreamEncoder MultistreamEncoder;
+
+/**
+ * Ogg Opus 读取器不透明指针类型
+ */
+typedef struct OggOpusReader OggOpusReader;
+
+/**
+ * Ogg Opus 写入器不透明指针类型
+ */
+typedef struct OggOpusWriter OggOpusWriter;
+
+/**
+ * 数据包流读取器不透明指针类型
+ */
+typedef struct PacketStreamReader PacketStreamReader;
+
+/**
+ * 数据包流写入器不透明指针类型
+ */
+typedef struct PacketStreamWriter PacketStreamWriter;
+
+/**
+ * Opus 重组器不透明指针类型
+ *
+ * 保存已加入数据包的副本，调用者传入的缓冲区在 `opus_ffi_repacketizer_cat` 返回后即可释放。
+ */
+typedef struct Repacketizer Repacketizer;
+
+/**
+ * 流式编码器不透明指针类型
+ */
+typedef struct StreamEncoder StreamEncoder;
+
 /**
  * Opus 错误结构体，用于在 C 和 Rust 之间传递错误信息
  *
@@ -25,16 +249,16 @@ typedef struct Encoder Encoder;
  * **重要**：`message` 字段是由 Rust 分配的 C 字符串，需要手动释放。
  *
  * - **在 Rust 代码中**：如果 `OpusError` 在 Rust 栈上分配，`Drop` trait 会自动释放 `message`
- * - **在 C 代码中**：如果 `OpusError` 在 C 栈上分配，必须手动调用 `free_c_string(&error.message)` 来释放 `message`
+ * - **在 C 代码中**：如果 `OpusError` 在 C 栈上分配，必须手动调用 `opus_ffi_free_c_string(&error.message)` 来释放 `message`
  *
  * # 示例（C 代码）
  *
  * ```c
  * OpusError error = {0, NULL};
- * int res = new_decoder(1, 16000, &decoder, &error);
+ * int res = opus_ffi_new_decoder(1, 16000, &decoder, &error);
  * if (res < 0 && error.message != NULL) {
  *     printf("Error: %s\n", error.message);
- *     free_c_string(&error.message);  // 必须手动释放
+ *     opus_ffi_free_c_string(&error.message);  // 必须手动释放
  * }
  * ```
  */
@@ -43,12 +267,183 @@ typedef struct OpusError {
   char *message;
 } OpusError;
 
+/**
+ * 编码结果
+ */
+typedef struct EncodeResult {
+  /**
+   * 数据包的字节数
+   */
+  uintptr_t size;
+  /**
+   * 是否为 DTX 数据包（静音期间不含音频数据的数据包），为 `true` 时不需要发送
+   */
+  bool dtx;
+} EncodeResult;
+
+/**
+ * 多流的流布局，与 Ogg Opus 头部中的声道映射表含义相同
+ */
+typedef struct MultistreamLayout {
+  /**
+   * 声道数（1~8）
+   */
+  uint32_t channels;
+  /**
+   * 流的总数
+   */
+  uint32_t streams;
+  /**
+   * 其中立体声（耦合）流的数量
+   */
+  uint32_t coupled_streams;
+  /**
+   * 每个输出声道对应的解码声道序号，只有前 `channels` 项有效
+   */
+  uint8_t mapping[8];
+} MultistreamLayout;
+
+/**
+ * OpusHead 标识头中的流参数
+ */
+typedef struct OggOpusHead {
+  /**
+   * 声道数（1 或 2）
+   */
+  uint32_t channels;
+  /**
+   * 解码时需要丢弃的起始样本数（48kHz）
+   */
+  uint32_t pre_skip;
+  /**
+   * 编码前原始音频的采样率（Hz），仅供参考，0 表示未知
+   */
+  uint32_t input_sample_rate;
+  /**
+   * 输出增益（Q7.8 格式的 dB 值），读取时已自动应用
+   */
+  int32_t output_gain;
+} OggOpusHead;
+
+/**
+ * 从 TOC 字节和帧数编码中解析出的数据包信息
+ */
+typedef struct PacketInfo {
+  /**
+   * 编码模式
+   */
+  enum OpusMode mode;
+  /**
+   * 音频带宽
+   */
+  enum OpusBandwidth bandwidth;
+  /**
+   * 每帧时长（微秒）：2500, 5000, 10000, 20000, 40000 或 60000
+   */
+  uint32_t frame_duration_us;
+  /**
+   * 数据包中的帧数（1~48）
+   */
+  uint32_t frame_count;
+  /**
+   * 是否为立体声数据包
+   */
+  bool stereo;
+  /**
+   * 按请求的采样率解码整个数据包得到的每声道样本数
+   */
+  uint32_t samples_per_packet;
+} PacketInfo;
+
+/**
+ * 旧版固定包长录音的分析结果
+ */
+typedef struct LegacyStreamInfo {
+  /**
+   * 推断出的数据包长度（字节数）
+   */
+  uint32_t packet_size;
+  /**
+   * 由编码带宽推断出的采样率（Hz）：窄带 8000，中带 12000，宽带 16000，超宽带 24000，全带 48000
+   */
+  uint32_t sample_rate;
+  /**
+   * 声道数（1 或 2）
+   */
+  uint32_t channels;
+  /**
+   * 每个数据包的时长（微秒）
+   */
+  uint32_t frame_duration_us;
+  /**
+   * 置信度，范围 [0, 1]。为 0 时表示无法识别，其他字段无意义
+   */
+  float confidence;
+} LegacyStreamInfo;
+
+/**
+ * 数据包流的头部信息
+ */
+typedef struct PacketStreamHeader {
+  /**
+   * 编码时使用的采样率（Hz）
+   */
+  uint32_t sample_rate;
+  /**
+   * 声道数
+   */
+  uint32_t channels;
+  /**
+   * 每帧时长（微秒），0 表示帧长不固定或未知
+   */
+  uint32_t frame_duration_us;
+} PacketStreamHeader;
+
+/**
+ * 库的能力描述
+ */
+typedef struct OpusFfiCapabilities {
+  /**
+   * 本库的主版本号
+   */
+  uint32_t version_major;
+  /**
+   * 本库的次版本号
+   */
+  uint32_t version_minor;
+  /**
+   * 本库的修订号
+   */
+  uint32_t version_patch;
+  /**
+   * 支持的采样率（Hz），只有前 `sample_rate_count` 项有效
+   */
+  uint32_t sample_rates[8];
+  /**
+   * `sample_rates` 中有效项的数量
+   */
+  uint32_t sample_rate_count;
+  /**
+   * 普通编解码器支持的最大声道数
+   */
+  uint32_t max_channels;
+  /**
+   * 多流编解码器支持的最大声道数
+   */
+  uint32_t max_surround_channels;
+  /**
+   * `OPUS_FFI_CAP_*` 标志的组合
+   */
+  uint32_t flags;
+} OpusFfiCapabilities;
+
 /**
  * 创建新的 Opus 解码器
  *
  * # 参数
  *
- * * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo），其他值默认为单声道
+ * * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+ *   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
  * * `sample_rate` - 采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
  * * `result` - 输出参数，用于接收创建的解码器指针。如果函数成功返回，此指针将被设置为有效的解码器实例
  * * `error` - 可选的错误输出参数。如果函数失败，错误信息将被填充到此结构中
@@ -57,34 +452,35 @@ typedef struct OpusError {
  *
  * * `0` - 成功
  * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *   声道数不受支持时返回 `OPUS_ERROR_CODE_UNSUPPORTED`
  *
  * # 安全性
  *
- * 调用者负责在不再需要时调用 `free_decoder` 释放返回的解码器。
+ * 调用者负责在不再需要时调用 `opus_ffi_free_decoder` 释放返回的解码器。
  *
  * # 示例
  *
  * ```c
  * Decoder *decoder = NULL;
  * OpusError error = {0, NULL};
- * int res = new_decoder(1, 16000, &decoder, &error);
+ * int res = opus_ffi_new_decoder(1, 16000, &decoder, &error);
  * if (res == 0) {
  *     // 使用解码器...
- *     free_decoder(decoder);
+ *     opus_ffi_free_decoder(decoder);
  * }
  * ```
  */
-int new_decoder(uint32_t channels,
-                uint32_t sample_rate,
-                Decoder **result,
-                struct OpusError *error);
+int opus_ffi_new_decoder(uint32_t channels,
+                         uint32_t sample_rate,
+                         struct Decoder **result,
+                         struct OpusError *error);
 
 /**
  * 解码 Opus 音频数据包为 PCM 样本（16 位整数）
  *
  * # 参数
  *
- * * `decoder` - 之前通过 `new_decoder` 创建的解码器实例
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
  * * `input` - 指向 Opus 编码数据包的指针
  * * `input_size` - 输入数据包的大小（字节数）
  * * `output` - 输出缓冲区，用于存储解码后的 PCM 样本
@@ -112,27 +508,27 @@ int new_decoder(uint32_t channels,
  * size_t decoded_samples = 0;
  * OpusError error = {0, NULL};
  *
- * int res = decode(decoder, opus_packet, packet_size, pcm_buffer, 320, false, &decoded_samples, &error);
+ * int res = opus_ffi_decode(decoder, opus_packet, packet_size, pcm_buffer, 320, false, &decoded_samples, &error);
  * if (res == 0) {
  *     // 使用解码后的 PCM 数据...
  * }
  * ```
  */
-int decode(Decoder *decoder,
-           const uint8_t *input,
-           uint32_t input_size,
-           int16_t *output,
-           uint32_t output_size,
-           bool fec,
-           uintptr_t *decoded_size,
-           struct OpusError *error);
+int opus_ffi_decode(struct Decoder *decoder,
+                    const uint8_t *input,
+                    uint32_t input_size,
+                    int16_t *output,
+                    uint32_t output_size,
+                    bool fec,
+                    uintptr_t *decoded_size,
+                    struct OpusError *error);
 
 /**
  * 解码 Opus 音频数据包为 PCM 样本（32 位浮点数）
  *
  * # 参数
  *
- * * `decoder` - 之前通过 `new_decoder` 创建的解码器实例
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
  * * `input` - 指向 Opus 编码数据包的指针
  * * `input_size` - 输入数据包的大小（字节数）
  * * `output` - 输出缓冲区，用于存储解码后的浮点 PCM 样本
@@ -149,7 +545,7 @@ int decode(Decoder *decoder,
  * # 注意事项
  *
  * * 浮点样本的范围通常在 [-1.0, 1.0] 之间
- * * 此函数与 `decode` 功能相同，但输出格式为浮点数，适合需要高精度处理的场景
+ * * 此函数与 `opus_ffi_decode` 功能相同，但输出格式为浮点数，适合需要高精度处理的场景
  *
  * # 示例
  *
@@ -159,55 +555,77 @@ int decode(Decoder *decoder,
  * size_t decoded_samples = 0;
  * OpusError error = {0, NULL};
  *
- * int res = decode_float(decoder, opus_packet, packet_size, pcm_buffer, 320, false, &decoded_samples, &error);
+ * int res = opus_ffi_decode_float(decoder, opus_packet, packet_size, pcm_buffer, 320, false, &decoded_samples, &error);
  * ```
  */
-int decode_float(Decoder *decoder,
-                 const uint8_t *input,
-                 uint32_t input_size,
-                 float *output,
-                 uint32_t output_size,
-                 bool fec,
-                 uintptr_t *result,
-                 struct OpusError *error);
+int opus_ffi_decode_float(struct Decoder *decoder,
+                          const uint8_t *input,
+                          uint32_t input_size,
+                          float *output,
+                          uint32_t output_size,
+                          bool fec,
+                          uintptr_t *result,
+                          struct OpusError *error);
 
 /**
- * 释放 Opus 解码器实例
+ * 一次解码多个 Opus 数据包（16 位整数输出）
  *
  * # 参数
  *
- * * `decoder` - 通过 `new_decoder` 创建的解码器指针
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `input` - 指向首尾相接的多个数据包的指针，例如 `opus_ffi_encode_batch` 的输出
+ * * `input_size` - 输入数据的大小（字节数）
+ * * `lengths` - 每个数据包的长度（字节数），长度之和不能超过 `input_size`。长度为 0 的项表示丢失的数据包
+ * * `packet_count` - 数据包数量，即 `lengths` 的元素个数
+ * * `output` - 输出缓冲区，解码后的交错 PCM 样本连续存放
+ * * `output_size` - 输出缓冲区的容量（样本数，不是字节数）
+ * * `decoded_size` - 输出参数，所有数据包解码后的每声道样本数之和
+ * * `error` - 可选的错误输出参数
  *
- * # 安全性
+ * # 返回值
  *
- * * 如果 `decoder` 为 `NULL`，函数不会执行任何操作
- * * 释放后，`decoder` 指针将不再有效，不应再次使用
- * * 每个通过 `new_decoder` 创建的解码器必须且只能调用一次此函数
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。输出缓冲区容量不足时返回 `OPUS_BUFFER_TOO_SMALL`
+ *
+ * # 注意事项
+ *
+ * * 结果与逐个调用 `opus_ffi_decode` 完全相同（包括 DTX 数据包），但只需跨越一次 FFI 边界
+ * * 出错时 `decoded_size` 为已经解码的每声道样本数，`output` 中这部分数据有效
+ * * 丢失的数据包按上一个数据包的时长生成丢包隐藏音频，与调用 `opus_ffi_decode_plc` 的结果相同；
+ *   第一项（或 `opus_ffi_reset_decoder` 之后的第一项）长度为 0 时无法确定时长，返回 `OPUS_BAD_ARG`
+ * * 不支持前向纠错，丢包需要恢复时逐个调用 `opus_ffi_decode_with_frame_size`
  *
  * # 示例
  *
  * ```c
- * Decoder *decoder = NULL;
- * // ... 创建和使用解码器 ...
- * free_decoder(decoder);
- * decoder = NULL; // 防止重复释放
+ * int16_t pcm[320 * 50];
+ * size_t samples = 0;
+ * int res = opus_ffi_decode_batch(decoder, packets, packets_size, lengths, 50, pcm, 320 * 50, &samples, &error);
  * ```
  */
-void free_decoder(Decoder *decoder);
+int opus_ffi_decode_batch(struct Decoder *decoder,
+                          const uint8_t *input,
+                          uintptr_t input_size,
+                          const uint32_t *lengths,
+                          uint32_t packet_count,
+                          int16_t *output,
+                          uintptr_t output_size,
+                          uintptr_t *decoded_size,
+                          struct OpusError *error);
 
 /**
- * 创建新的 Opus 编码器
+ * 一次解码多个 Opus 数据包（32 位浮点数输出）
  *
  * # 参数
  *
- * * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo），其他值默认为单声道
- * * `sample_rate` - 采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
- * * `application` - 应用模式：
- *   - `1` = Voip（语音通话，低延迟优化）
- *   - `2` = Audio（音频流，高质量优化）
- *   - `3` = LowDelay（低延迟模式）
- *   - 其他值默认为 Voip
- * * `result` - 输出参数，用于接收创建的编码器指针
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `input` - 指向首尾相接的多个数据包的指针
+ * * `input_size` - 输入数据的大小（字节数）
+ * * `lengths` - 每个数据包的长度（字节数），长度之和不能超过 `input_size`。长度为 0 的项表示丢失的数据包
+ * * `packet_count` - 数据包数量，即 `lengths` 的元素个数
+ * * `output` - 输出缓冲区，解码后的交错浮点 PCM 样本连续存放
+ * * `output_size` - 输出缓冲区的容量（样本数，不是字节数）
+ * * `result` - 输出参数，所有数据包解码后的每声道样本数之和
  * * `error` - 可选的错误输出参数
  *
  * # 返回值
@@ -215,86 +633,123 @@ void free_decoder(Decoder *decoder);
  * * `0` - 成功
  * * 负数 - 错误代码（错误详情在 `error` 参数中）
  *
- * # 安全性
+ * # 注意事项
+ *
+ * * 此函数与 `opus_ffi_decode_batch` 功能相同（包括对丢失数据包的处理），但输出格式为浮点数
+ */
+int opus_ffi_decode_float_batch(struct Decoder *decoder,
+                                const uint8_t *input,
+                                uintptr_t input_size,
+                                const uint32_t *lengths,
+                                uint32_t packet_count,
+                                float *output,
+                                uintptr_t output_size,
+                                uintptr_t *result,
+                                struct OpusError *error);
+
+/**
+ * 按指定帧大小解码 Opus 音频数据包为 PCM 样本（16 位整数）
+ *
+ * # 参数
  *
- * 调用者负责在不再需要时调用 `free_encoder` 释放返回的编码器。
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `input` - 指向 Opus 编码数据包的指针
+ * * `input_size` - 输入数据包的大小（字节数）
+ * * `output` - 输出缓冲区，用于存储解码后的 PCM 样本
+ * * `output_size` - 输出缓冲区的容量（样本数，不是字节数）
+ * * `frame_size` - 期望解码的每声道样本数。使用 FEC 恢复丢失的数据包时，必须与丢失数据包的帧大小一致
+ * * `fec` - 前向纠错（Forward Error Correction）标志
+ * * `decoded_size` - 输出参数，解码后实际产生的每声道样本数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。如果 `output_size` 小于 `frame_size × 声道数`，
+ *   返回 `OPUS_BUFFER_TOO_SMALL`
+ *
+ * # 注意事项
+ *
+ * * 与 `opus_ffi_decode` 不同，此函数只向解码器提供 `frame_size × 声道数` 个样本的空间，而不是整个输出缓冲区
  *
  * # 示例
  *
  * ```c
- * Encoder *encoder = NULL;
+ * int16_t pcm_buffer[960];
+ * size_t decoded_samples = 0;
  * OpusError error = {0, NULL};
- * int res = new_encoder(1, 16000, 1, &encoder, &error);
- * if (res == 0) {
- *     // 使用编码器...
- *     free_encoder(encoder);
- * }
+ *
+ * // 使用下一个数据包的 FEC 数据恢复丢失的 20ms（16kHz 单声道 = 320 个样本）
+ * int res = opus_ffi_decode_with_frame_size(decoder, next_packet, next_size, pcm_buffer, 960, 320, true, &decoded_samples, &error);
  * ```
  */
-int new_encoder(uint32_t channels,
-                uint32_t sample_rate,
-                uint32_t application,
-                Encoder **result,
-                struct OpusError *error);
+int opus_ffi_decode_with_frame_size(struct Decoder *decoder,
+                                    const uint8_t *input,
+                                    uint32_t input_size,
+                                    int16_t *output,
+                                    uint32_t output_size,
+                                    uint32_t frame_size,
+                                    bool fec,
+                                    uintptr_t *decoded_size,
+                                    struct OpusError *error);
 
 /**
- * 将 PCM 样本编码为 Opus 数据包（16 位整数输入）
+ * 按指定帧大小解码 Opus 音频数据包为 PCM 样本（32 位浮点数）
  *
  * # 参数
  *
- * * `encoder` - 之前通过 `new_encoder` 创建的编码器实例
- * * `input` - 指向 PCM 样本数据的指针（16 位有符号整数）
- * * `input_size` - 输入样本的数量（不是字节数）。对于单声道，这是样本数；对于立体声，这是样本对的数量
- * * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
- * * `output_size` - 输出缓冲区的容量（字节数）。建议至少 4000 字节
- * * `encoded_size` - 输出参数，编码后实际产生的字节数
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `input` - 指向 Opus 编码数据包的指针
+ * * `input_size` - 输入数据包的大小（字节数）
+ * * `output` - 输出缓冲区，用于存储解码后的浮点 PCM 样本
+ * * `output_size` - 输出缓冲区的容量（样本数，不是字节数）
+ * * `frame_size` - 期望解码的每声道样本数
+ * * `fec` - 前向纠错（Forward Error Correction）标志
+ * * `result` - 输出参数，解码后实际产生的每声道样本数
  * * `error` - 可选的错误输出参数
  *
  * # 返回值
  *
  * * `0` - 成功
- * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。如果 `output_size` 小于 `frame_size × 声道数`，
+ *   返回 `OPUS_BUFFER_TOO_SMALL`
  *
  * # 注意事项
  *
- * * `input_size` 是样本数，不是字节数。对于 16 位 PCM，每个样本占 2 字节
- * * 输入样本格式为 16 位有符号整数（i16），小端序
- * * 典型的帧大小：20ms 在 16kHz 采样率下 = 320 个样本
- * * 输出缓冲区应该足够大，Opus 数据包最大约为 4000 字节
+ * * 此函数与 `opus_ffi_decode_with_frame_size` 功能相同，但输出格式为浮点数
  *
  * # 示例
  *
  * ```c
- * int16_t pcm_samples[320]; // 20ms @ 16kHz, 单声道
- * uint8_t opus_packet[4000];
- * size_t encoded_size = 0;
+ * float pcm_buffer[960];
+ * size_t decoded_samples = 0;
  * OpusError error = {0, NULL};
  *
- * int res = encode(encoder, pcm_samples, 320, opus_packet, 4000, &encoded_size, &error);
- * if (res == 0) {
- *     // 使用编码后的数据包...
- * }
+ * int res = opus_ffi_decode_float_with_frame_size(decoder, packet, packet_size, pcm_buffer, 960, 320, false, &decoded_samples, &error);
  * ```
  */
-int encode(Encoder *encoder,
-           const int16_t *input,
-           uint32_t input_size,
-           uint8_t *output,
-           uint32_t output_size,
-           uintptr_t *encoded_size,
-           struct OpusError *error);
+int opus_ffi_decode_float_with_frame_size(struct Decoder *decoder,
+                                          const uint8_t *input,
+                                          uint32_t input_size,
+                                          float *output,
+                                          uint32_t output_size,
+                                          uint32_t frame_size,
+                                          bool fec,
+                                          uintptr_t *result,
+                                          struct OpusError *error);
 
 /**
- * 将 PCM 样本编码为 Opus 数据包（32 位浮点数输入）
+ * 为丢失的数据包生成丢包隐藏（PLC）音频（16 位整数）
  *
  * # 参数
  *
- * * `encoder` - 之前通过 `new_encoder` 创建的编码器实例
- * * `input` - 指向 PCM 样本数据的指针（32 位浮点数）
- * * `input_size` - 输入样本的数量（不是字节数）
- * * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
- * * `output_size` - 输出缓冲区的容量（字节数）
- * * `result` - 输出参数，编码后实际产生的字节数
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `duration_us` - 需要隐藏的时长（微秒），必须是 2500（2.5ms）的整数倍且不超过 120000（120ms）。
+ *   通常等于丢失数据包的帧时长，例如 20ms 对应 `20000`
+ * * `output` - 输出缓冲区，用于存储生成的 PCM 样本
+ * * `output_size` - 输出缓冲区的容量（样本数，不是字节数），至少为 `采样率 × 时长 × 声道数`，
+ *   否则返回 `OPUS_BUFFER_TOO_SMALL`
+ * * `decoded_size` - 输出参数，实际产生的每声道样本数
  * * `error` - 可选的错误输出参数
  *
  * # 返回值
@@ -304,104 +759,2877 @@ int encode(Encoder *encoder,
  *
  * # 注意事项
  *
- * * 浮点样本的范围通常在 [-1.0, 1.0] 之间
- * * 此函数与 `encode` 功能相同，但输入格式为浮点数，适合需要高精度处理的场景
- * * `input_size` 是样本数，不是字节数
+ * * 解码器会根据之前解码的音频外推出平滑的填充数据，避免直接补零造成的爆音
+ * * 连续调用时输出会逐渐衰减为静音
  *
  * # 示例
  *
  * ```c
- * float pcm_samples[320]; // 20ms @ 16kHz, 单声道
- * uint8_t opus_packet[4000];
- * size_t encoded_size = 0;
+ * int16_t pcm_buffer[320]; // 20ms @ 16kHz, 单声道
+ * size_t decoded_samples = 0;
  * OpusError error = {0, NULL};
  *
- * int res = encode_float(encoder, pcm_samples, 320, opus_packet, 4000, &encoded_size, &error);
+ * int res = opus_ffi_decode_plc(decoder, 20000, pcm_buffer, 320, &decoded_samples, &error);
  * ```
  */
-int encode_float(Encoder *encoder,
-                 const float *input,
-                 uint32_t input_size,
-                 uint8_t *output,
-                 uint32_t output_size,
-                 uintptr_t *result,
-                 struct OpusError *error);
+int opus_ffi_decode_plc(struct Decoder *decoder,
+                        uint32_t duration_us,
+                        int16_t *output,
+                        uint32_t output_size,
+                        uintptr_t *decoded_size,
+                        struct OpusError *error);
 
 /**
- * 释放 Opus 编码器实例
+ * 为丢失的数据包生成丢包隐藏（PLC）音频（32 位浮点数）
  *
  * # 参数
  *
- * * `encoder` - 通过 `new_encoder` 创建的编码器指针
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `duration_us` - 需要隐藏的时长（微秒），必须是 2500（2.5ms）的整数倍且不超过 120000（120ms）
+ * * `output` - 输出缓冲区，用于存储生成的浮点 PCM 样本
+ * * `output_size` - 输出缓冲区的容量（样本数，不是字节数），至少为 `采样率 × 时长 × 声道数`，
+ *   否则返回 `OPUS_BUFFER_TOO_SMALL`
+ * * `result` - 输出参数，实际产生的每声道样本数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
  *
- * # 安全性
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
  *
- * * 如果 `encoder` 为 `NULL`，函数不会执行任何操作
- * * 释放后，`encoder` 指针将不再有效，不应再次使用
- * * 每个通过 `new_encoder` 创建的编码器必须且只能调用一次此函数
+ * # 注意事项
+ *
+ * * 此函数与 `opus_ffi_decode_plc` 功能相同，但输出格式为浮点数
  *
  * # 示例
  *
  * ```c
- * Encoder *encoder = NULL;
- * // ... 创建和使用编码器 ...
- * free_encoder(encoder);
- * encoder = NULL; // 防止重复释放
+ * float pcm_buffer[320];
+ * size_t decoded_samples = 0;
+ * OpusError error = {0, NULL};
+ *
+ * int res = opus_ffi_decode_plc_float(decoder, 20000, pcm_buffer, 320, &decoded_samples, &error);
  * ```
  */
-void free_encoder(Encoder *encoder);
+int opus_ffi_decode_plc_float(struct Decoder *decoder,
+                              uint32_t duration_us,
+                              float *output,
+                              uint32_t output_size,
+                              uintptr_t *result,
+                              struct OpusError *error);
 
 /**
- * 释放由 Rust 分配的 C 字符串
+ * 设置解码器的输出增益
  *
  * # 参数
  *
- * * `p` - 指向 C 字符串指针的指针（`char**`）。函数会释放字符串并将指针设置为 `NULL`
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `gain` - 增益，Q8 格式的分贝值（dB × 256），范围 -32768 ~ 32767，默认为 0。
+ *   例如 256 表示放大 1dB，-1536 表示衰减 6dB
+ * * `error` - 可选的错误输出参数
  *
- * # 安全性
+ * # 返回值
  *
- * * 如果 `p` 为 `NULL` 或 `*p` 为 `NULL`，函数不会执行任何操作
- * * 只能释放由 Rust 代码分配的 C 字符串（通过 `CString::into_raw()` 创建）
- * * 释放后，字符串指针将被设置为 `NULL`，防止重复释放
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。超出范围时返回 `OPUS_BAD_ARG`
+ *
+ * # 注意事项
+ *
+ * * Ogg Opus 头部中的 `output_gain` 使用相同的格式，`opus_ffi_ogg_reader_open_*` 已经自动应用该增益。
+ *   在此基础上叠加用户音量时，应传入两者之和
  *
  * # 示例
  *
  * ```c
- * char *message = NULL;
- * // ... 从 Rust 函数获取字符串 ...
- * free_c_string(&message);
- * // message 现在为 NULL
+ * // 音量减半（约 -6dB）
+ * opus_ffi_set_decoder_gain(decoder, -1541, &error);
  * ```
  */
-void free_c_string(char **p);
+int opus_ffi_set_decoder_gain(struct Decoder *decoder,
+                              int32_t gain,
+                              struct OpusError *error);
 
 /**
- * 释放堆上分配的 OpusError 结构
+ * 获取解码器的输出增益
  *
  * # 参数
  *
- * * `e` - 指向堆上分配的 `OpusError` 结构的指针
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `result` - 输出参数，Q8 格式的分贝值（dB × 256）
+ * * `error` - 可选的错误输出参数
  *
- * # 安全性
+ * # 返回值
  *
- * * 如果 `e` 为 `NULL`，函数不会执行任何操作
- * * 只能释放通过 `Box` 在堆上分配的 `OpusError`
- * * 栈上分配的 `OpusError` 不需要调用此函数，它们会自动释放
- * * 释放后，指针将不再有效，不应再次使用
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_decoder_gain(struct Decoder *decoder, int32_t *result, struct OpusError *error);
+
+/**
+ * 获取最近一次解码（包括丢包隐藏）产生的每声道样本数
+ *
+ * # 参数
+ *
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `result` - 输出参数，每声道样本数，按解码器的采样率计算
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
  *
  * # 注意事项
  *
- * 大多数情况下，`OpusError` 是在栈上分配的，不需要调用此函数。
- * 只有在特殊情况下（如在堆上分配）才需要调用此函数。
+ * * 数据包丢失时，可以按上一个数据包的时长调用 `opus_ffi_decode_plc`
+ */
+int opus_ffi_get_decoder_last_packet_duration(struct Decoder *decoder,
+                                              uint32_t *result,
+                                              struct OpusError *error);
+
+/**
+ * 获取最近一次解码的帧的基音周期
  *
- * # 示例
+ * # 参数
  *
- * ```c
- * OpusError *error = malloc(sizeof(OpusError));
- * // ... 使用 error ...
- * free_opus_error(error);
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `result` - 输出参数，基音周期（48kHz 下的样本数），没有检测到基音时为 0
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 只有 SILK 模式和开启了基音预滤波的 CELT 帧才有基音信息，可用于简单的语音检测
+ */
+int opus_ffi_get_decoder_pitch(struct Decoder *decoder,
+                               int32_t *result,
+                               struct OpusError *error);
+
+/**
+ * 获取解码器最近一帧的熵编码器最终状态
+ *
+ * # 参数
+ *
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `result` - 输出参数，最终状态
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 解码某个数据包后的值与编码该数据包后 `opus_ffi_get_encoder_final_range` 的值相同，
+ *   可以在测试中用来确认编解码结果逐位一致
+ */
+int opus_ffi_get_decoder_final_range(struct Decoder *decoder,
+                                     uint32_t *result,
+                                     struct OpusError *error);
+
+/**
+ * 重置解码器状态（对应 `OPUS_RESET_STATE`）
+ *
+ * # 参数
+ *
+ * * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 清除之前解码留下的内部状态（包括丢包隐藏使用的历史数据），之后的输出与新创建的解码器相同，
+ *   适合在切换到新的通话或音频流时复用解码器
+ *
+ * # 示例
+ *
+ * ```c
+ * // 开始解码新的音频流
+ * opus_ffi_reset_decoder(decoder, &error);
+ * ```
+ */
+int opus_ffi_reset_decoder(struct Decoder *decoder,
+                           struct OpusError *error);
+
+/**
+ * 释放 Opus 解码器实例
+ *
+ * # 参数
+ *
+ * * `decoder` - 通过 `opus_ffi_new_decoder` 创建的解码器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `decoder` 为 `NULL`，函数不会执行任何操作
+ * * 释放后，`decoder` 指针将不再有效，不应再次使用
+ * * 每个通过 `opus_ffi_new_decoder` 创建的解码器必须且只能调用一次此函数
+ * * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `decoder` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `decoder` 不是有效的解码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ *
+ * # 示例
+ *
+ * ```c
+ * Decoder *decoder = NULL;
+ * // ... 创建和使用解码器 ...
+ * opus_ffi_free_decoder(decoder);
+ * decoder = NULL; // 防止重复释放
+ * ```
+ */
+int opus_ffi_free_decoder(struct Decoder *decoder);
+
+/**
+ * 创建新的 Opus 编码器
+ *
+ * # 参数
+ *
+ * * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+ *   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
+ * * `sample_rate` - 采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `application` - 应用模式：
+ *   - `1` = Voip（语音通话，低延迟优化）
+ *   - `2` = Audio（音频流，高质量优化）
+ *   - `3` = LowDelay（低延迟模式）
+ *   - 其他值返回错误（宽松模式下默认为 Voip）
+ * * `result` - 输出参数，用于接收创建的编码器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *   声道数或应用模式不受支持时返回 `OPUS_ERROR_CODE_UNSUPPORTED`
+ *
+ * # 安全性
+ *
+ * 调用者负责在不再需要时调用 `opus_ffi_free_encoder` 释放返回的编码器。
+ *
+ * # 示例
+ *
+ * ```c
+ * Encoder *encoder = NULL;
+ * OpusError error = {0, NULL};
+ * int res = opus_ffi_new_encoder(1, 16000, 1, &encoder, &error);
+ * if (res == 0) {
+ *     // 使用编码器...
+ *     opus_ffi_free_encoder(encoder);
+ * }
+ * ```
+ */
+int opus_ffi_new_encoder(uint32_t channels,
+                         uint32_t sample_rate,
+                         uint32_t application,
+                         struct Encoder **result,
+                         struct OpusError *error);
+
+/**
+ * 将 PCM 样本编码为 Opus 数据包（16 位整数输入）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `input` - 指向 PCM 样本数据的指针（16 位有符号整数）
+ * * `input_size` - 输入样本的数量（不是字节数）。对于单声道，这是样本数；对于立体声，这是样本对的数量
+ * * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
+ * * `output_size` - 输出缓冲区的容量（字节数）。建议至少 4000 字节
+ * * `encoded_size` - 输出参数，编码后实际产生的字节数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * `input_size` 是样本数，不是字节数。对于 16 位 PCM，每个样本占 2 字节
+ * * 输入样本格式为 16 位有符号整数（i16），小端序
+ * * 典型的帧大小：20ms 在 16kHz 采样率下 = 320 个样本
+ * * 输出缓冲区应该足够大，Opus 数据包最大约为 4000 字节
+ *
+ * # 示例
+ *
+ * ```c
+ * int16_t pcm_samples[320]; // 20ms @ 16kHz, 单声道
+ * uint8_t opus_packet[4000];
+ * size_t encoded_size = 0;
+ * OpusError error = {0, NULL};
+ *
+ * int res = opus_ffi_encode(encoder, pcm_samples, 320, opus_packet, 4000, &encoded_size, &error);
+ * if (res == 0) {
+ *     // 使用编码后的数据包...
+ * }
+ * ```
+ */
+int opus_ffi_encode(struct Encoder *encoder,
+                    const int16_t *input,
+                    uint32_t input_size,
+                    uint8_t *output,
+                    uint32_t output_size,
+                    uintptr_t *encoded_size,
+                    struct OpusError *error);
+
+/**
+ * 将 PCM 样本编码为 Opus 数据包（32 位浮点数输入）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `input` - 指向 PCM 样本数据的指针（32 位浮点数）
+ * * `input_size` - 输入样本的数量（不是字节数）
+ * * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
+ * * `output_size` - 输出缓冲区的容量（字节数）
+ * * `result` - 输出参数，编码后实际产生的字节数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 浮点样本的范围通常在 [-1.0, 1.0] 之间
+ * * 此函数与 `opus_ffi_encode` 功能相同，但输入格式为浮点数，适合需要高精度处理的场景
+ * * `input_size` 是样本数，不是字节数
+ *
+ * # 示例
+ *
+ * ```c
+ * float pcm_samples[320]; // 20ms @ 16kHz, 单声道
+ * uint8_t opus_packet[4000];
+ * size_t encoded_size = 0;
+ * OpusError error = {0, NULL};
+ *
+ * int res = opus_ffi_encode_float(encoder, pcm_samples, 320, opus_packet, 4000, &encoded_size, &error);
+ * ```
+ */
+int opus_ffi_encode_float(struct Encoder *encoder,
+                          const float *input,
+                          uint32_t input_size,
+                          uint8_t *output,
+                          uint32_t output_size,
+                          uintptr_t *result,
+                          struct OpusError *error);
+
+/**
+ * 将 PCM 样本编码为 Opus 数据包，同时报告是否为 DTX 数据包（16 位整数输入）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `input` - 指向 PCM 样本数据的指针（16 位有符号整数）
+ * * `input_size` - 输入样本的数量（不是字节数）
+ * * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
+ * * `output_size` - 输出缓冲区的容量（字节数）
+ * * `result` - 输出参数，数据包的字节数和 DTX 标志
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 与 `opus_ffi_encode` 功能相同，只是多了 DTX 标志
+ * * 开启 DTX（见 `opus_ffi_set_encoder_dtx`）后，静音期间的数据包 `dtx` 为 `true`，可以不发送；
+ *   编码器仍会每隔约 400ms 输出一个舒适噪声更新包，这些数据包 `dtx` 为 `false`，需要正常发送
+ *
+ * # 示例
+ *
+ * ```c
+ * EncodeResult encoded;
+ * int res = opus_ffi_encode_with_result(encoder, pcm, 320, packet, 4000, &encoded, &error);
+ * if (res == 0 && !encoded.dtx) {
+ *     send_packet(packet, encoded.size);
+ * }
+ * ```
+ */
+int opus_ffi_encode_with_result(struct Encoder *encoder,
+                                const int16_t *input,
+                                uint32_t input_size,
+                                uint8_t *output,
+                                uint32_t output_size,
+                                struct EncodeResult *result,
+                                struct OpusError *error);
+
+/**
+ * 将 PCM 样本编码为 Opus 数据包，同时报告是否为 DTX 数据包（32 位浮点数输入）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `input` - 指向 PCM 样本数据的指针（32 位浮点数）
+ * * `input_size` - 输入样本的数量（不是字节数）
+ * * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
+ * * `output_size` - 输出缓冲区的容量（字节数）
+ * * `result` - 输出参数，数据包的字节数和 DTX 标志
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 此函数与 `opus_ffi_encode_with_result` 功能相同，但输入格式为浮点数
+ */
+int opus_ffi_encode_float_with_result(struct Encoder *encoder,
+                                      const float *input,
+                                      uint32_t input_size,
+                                      uint8_t *output,
+                                      uint32_t output_size,
+                                      struct EncodeResult *result,
+                                      struct OpusError *error);
+
+/**
+ * 一次编码多帧 PCM 样本（16 位整数输入）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `input` - 指向连续多帧 PCM 样本的指针（16 位有符号整数）
+ * * `input_size` - 输入样本的总数（所有声道），必须是 `frame_size` 的整数倍
+ * * `frame_size` - 每帧的样本总数（所有声道），与 `opus_ffi_encode` 的 `input_size` 含义相同，
+ *   例如 16kHz 单声道 20ms 为 320
+ * * `output` - 输出缓冲区，各数据包首尾相接存放
+ * * `output_size` - 输出缓冲区的容量（字节数）
+ * * `lengths` - 输出数组，依次接收每个数据包的长度（字节数）。第 i 个数据包的偏移量是前 i 个长度之和
+ * * `lengths_size` - `lengths` 数组的容量，不能小于帧数 `input_size / frame_size`
+ * * `frame_count` - 输出参数，写入的数据包数量
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。`input_size` 不是 `frame_size` 的整数倍时返回 `OPUS_BAD_ARG`，
+ *   `lengths` 或 `output` 容量不足时返回 `OPUS_BUFFER_TOO_SMALL`
+ *
+ * # 注意事项
+ *
+ * * 结果与逐帧调用 `opus_ffi_encode` 完全相同，但只需跨越一次 FFI 边界
+ * * 编码每一帧前 `output` 至少要剩余 4000 字节（单个数据包的最大长度），否则返回 `OPUS_BUFFER_TOO_SMALL` 且不编码该帧，
+ *   因此除了数据包本身还需多预留 4000 字节。出错时 `frame_count` 为已写入的数据包数量，后续帧从第 `frame_count` 帧继续即可
+ *
+ * # 示例
+ *
+ * ```c
+ * // 50 帧 20ms @ 16kHz 单声道
+ * int16_t pcm[320 * 50];
+ * uint8_t packets[50 * 400 + 4000];
+ * uint32_t lengths[50];
+ * size_t count = 0;
+ * int res = opus_ffi_encode_batch(encoder, pcm, 320 * 50, 320, packets, sizeof(packets), lengths, 50, &count, &error);
+ * ```
+ */
+int opus_ffi_encode_batch(struct Encoder *encoder,
+                          const int16_t *input,
+                          uint32_t input_size,
+                          uint32_t frame_size,
+                          uint8_t *output,
+                          uintptr_t output_size,
+                          uint32_t *lengths,
+                          uint32_t lengths_size,
+                          uintptr_t *frame_count,
+                          struct OpusError *error);
+
+/**
+ * 一次编码多帧 PCM 样本（32 位浮点数输入）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `input` - 指向连续多帧 PCM 样本的指针（32 位浮点数）
+ * * `input_size` - 输入样本的总数（所有声道），必须是 `frame_size` 的整数倍
+ * * `frame_size` - 每帧的样本总数（所有声道）
+ * * `output` - 输出缓冲区，各数据包首尾相接存放
+ * * `output_size` - 输出缓冲区的容量（字节数）
+ * * `lengths` - 输出数组，依次接收每个数据包的长度（字节数）
+ * * `lengths_size` - `lengths` 数组的容量，不能小于帧数 `input_size / frame_size`
+ * * `frame_count` - 输出参数，写入的数据包数量
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 此函数与 `opus_ffi_encode_batch` 功能相同，但输入格式为浮点数
+ */
+int opus_ffi_encode_float_batch(struct Encoder *encoder,
+                                const float *input,
+                                uint32_t input_size,
+                                uint32_t frame_size,
+                                uint8_t *output,
+                                uintptr_t output_size,
+                                uint32_t *lengths,
+                                uint32_t lengths_size,
+                                uintptr_t *frame_count,
+                                struct OpusError *error);
+
+/**
+ * 设置编码器的目标比特率
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `bitrate` - 目标比特率（bit/s），有效范围 500 ~ 512000。也可以传入特殊值：
+ *   - `BITRATE_AUTO`（-1000）= 由编码器自动选择
+ *   - `BITRATE_MAX`（-1）= 使用允许的最大比特率
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 示例
+ *
+ * ```c
+ * OpusError error = {0, NULL};
+ * int res = opus_ffi_set_encoder_bitrate(encoder, 24000, &error);
+ * ```
+ */
+int opus_ffi_set_encoder_bitrate(struct Encoder *encoder,
+                                 int32_t bitrate,
+                                 struct OpusError *error);
+
+/**
+ * 获取编码器当前的比特率
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，当前比特率（bit/s）。编码器总是返回实际生效的比特率，
+ *   即使之前设置的是 `BITRATE_AUTO`
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_bitrate(struct Encoder *encoder,
+                                 int32_t *result,
+                                 struct OpusError *error);
+
+/**
+ * 设置编码器的计算复杂度
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `complexity` - 复杂度，范围 0 ~ 10。数值越大音质越好，但 CPU 占用越高
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。超出范围时返回 `OPUS_BAD_ARG`
+ */
+int opus_ffi_set_encoder_complexity(struct Encoder *encoder,
+                                    int32_t complexity,
+                                    struct OpusError *error);
+
+/**
+ * 获取编码器当前的计算复杂度
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，当前复杂度（0 ~ 10）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_complexity(struct Encoder *encoder,
+                                    int32_t *result,
+                                    struct OpusError *error);
+
+/**
+ * 启用或禁用可变比特率（VBR）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `vbr` - `true` 表示使用 VBR（默认），`false` 表示使用固定比特率（CBR）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_set_encoder_vbr(struct Encoder *encoder, bool vbr, struct OpusError *error);
+
+/**
+ * 查询编码器是否启用了可变比特率（VBR）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，`true` 表示 VBR，`false` 表示 CBR
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_vbr(struct Encoder *encoder, bool *result, struct OpusError *error);
+
+/**
+ * 启用或禁用受约束的可变比特率（Constrained VBR）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `constraint` - `true` 表示受约束 VBR（默认），`false` 表示不受约束 VBR
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 仅在启用 VBR 时生效。受约束 VBR 的码率波动更小，适合对带宽敏感的实时传输
+ */
+int opus_ffi_set_encoder_vbr_constraint(struct Encoder *encoder,
+                                        bool constraint,
+                                        struct OpusError *error);
+
+/**
+ * 查询编码器是否启用了受约束的可变比特率（Constrained VBR）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，`true` 表示受约束 VBR
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_vbr_constraint(struct Encoder *encoder,
+                                        bool *result,
+                                        struct OpusError *error);
+
+/**
+ * 启用或禁用带内前向纠错（In-band FEC）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `fec` - `true` 表示启用 FEC，`false` 表示禁用（默认）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 启用后，编码器会在数据包中携带前一帧的低码率冗余数据，接收端丢包时可通过 `opus_ffi_decode` 的 `fec` 参数恢复
+ * * FEC 仅在 SILK/混合模式下生效，并且需要配合 `opus_ffi_set_encoder_packet_loss_perc` 设置非零的预期丢包率
+ *
+ * # 示例
+ *
+ * ```c
+ * OpusError error = {0, NULL};
+ * opus_ffi_set_encoder_inband_fec(encoder, true, &error);
+ * opus_ffi_set_encoder_packet_loss_perc(encoder, 10, &error);
+ * ```
+ */
+int opus_ffi_set_encoder_inband_fec(struct Encoder *encoder,
+                                    bool fec,
+                                    struct OpusError *error);
+
+/**
+ * 查询编码器是否启用了带内前向纠错（In-band FEC）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，`true` 表示已启用 FEC
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_inband_fec(struct Encoder *encoder, bool *result, struct OpusError *error);
+
+/**
+ * 设置编码器的预期丢包率
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `percentage` - 预期丢包率（百分比），范围 0 ~ 100。默认为 0
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。超出范围时返回 `OPUS_BAD_ARG`
+ *
+ * # 注意事项
+ *
+ * * 丢包率越高，编码器分配给 FEC 冗余数据的码率越多
+ */
+int opus_ffi_set_encoder_packet_loss_perc(struct Encoder *encoder,
+                                          int32_t percentage,
+                                          struct OpusError *error);
+
+/**
+ * 获取编码器当前的预期丢包率
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，预期丢包率（百分比）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_packet_loss_perc(struct Encoder *encoder,
+                                          int32_t *result,
+                                          struct OpusError *error);
+
+/**
+ * 开启或关闭 DTX（不连续传输）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `enabled` - `true` 表示开启（默认关闭）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 开启后，编码器在静音期间输出不含音频数据的 DTX 数据包，可以通过 `opus_ffi_encode_with_result`
+ *   的 `dtx` 标志识别并跳过发送，以节省带宽
+ */
+int opus_ffi_set_encoder_dtx(struct Encoder *encoder,
+                             bool enabled,
+                             struct OpusError *error);
+
+/**
+ * 获取编码器是否开启了 DTX（不连续传输）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，是否开启 DTX
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_dtx(struct Encoder *encoder, bool *result, struct OpusError *error);
+
+/**
+ * 设置编码器的信号类型提示
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `signal` - 信号类型：
+ *   - `SIGNAL_AUTO`（-1000）= 由编码器自动判断（默认）
+ *   - `SIGNAL_VOICE`（3001）= 语音，倾向于使用 SILK 模式
+ *   - `SIGNAL_MUSIC`（3002）= 音乐，倾向于使用 CELT 模式
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。其他取值返回 `OPUS_BAD_ARG`
+ */
+int opus_ffi_set_encoder_signal(struct Encoder *encoder,
+                                int32_t signal,
+                                struct OpusError *error);
+
+/**
+ * 获取编码器当前的信号类型提示
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，`SIGNAL_AUTO`、`SIGNAL_VOICE` 或 `SIGNAL_MUSIC`
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_signal(struct Encoder *encoder, int32_t *result, struct OpusError *error);
+
+/**
+ * 设置编码器使用的音频带宽
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `bandwidth` - `OpusBandwidth` 的取值（1101 窄带 ~ 1105 全带），
+ *   或 `BANDWIDTH_AUTO`（-1000）由编码器根据比特率自动选择（默认）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。其他取值返回 `OPUS_BAD_ARG`
+ *
+ * # 注意事项
+ *
+ * * 带宽仍受 `opus_ffi_set_encoder_max_bandwidth` 和采样率的限制
+ * * libopus 不提供读取此设置的接口，实际使用的带宽通过 `opus_ffi_get_encoder_final_bandwidth` 获取
+ *
+ * # 示例
+ *
+ * ```c
+ * // 语音留言只保留窄带，节省空间
+ * opus_ffi_set_encoder_bandwidth(encoder, Narrowband, &error);
+ * ```
+ */
+int opus_ffi_set_encoder_bandwidth(struct Encoder *encoder,
+                                   int32_t bandwidth,
+                                   struct OpusError *error);
+
+/**
+ * 获取编码器最近一帧实际使用的音频带宽
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，实际使用的带宽
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 带宽由编码器根据比特率、带宽设置和输入信号决定，编码第一帧之前返回的值没有意义
+ */
+int opus_ffi_get_encoder_final_bandwidth(struct Encoder *encoder,
+                                         enum OpusBandwidth *result,
+                                         struct OpusError *error);
+
+/**
+ * 设置编码器允许使用的最大音频带宽
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `bandwidth` - `OpusBandwidth` 的取值（1101 窄带 ~ 1105 全带，默认为全带）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。其他取值返回 `OPUS_BAD_ARG`
+ *
+ * # 注意事项
+ *
+ * * 与 `opus_ffi_set_encoder_bandwidth` 不同，编码器仍可以在比特率较低时选择更窄的带宽
+ */
+int opus_ffi_set_encoder_max_bandwidth(struct Encoder *encoder,
+                                       int32_t bandwidth,
+                                       struct OpusError *error);
+
+/**
+ * 获取编码器允许使用的最大音频带宽
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，最大带宽
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_max_bandwidth(struct Encoder *encoder,
+                                       enum OpusBandwidth *result,
+                                       struct OpusError *error);
+
+/**
+ * 强制编码器输出单声道或立体声数据包
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `channels` - 1 表示强制单声道，2 表示强制立体声，`FORCE_CHANNELS_AUTO`（-1000）表示不强制（默认）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。其他取值返回 `OPUS_BAD_ARG`
+ *
+ * # 注意事项
+ *
+ * * 单声道编码器不能强制为立体声，此时返回 `OPUS_BAD_ARG`
+ */
+int opus_ffi_set_encoder_force_channels(struct Encoder *encoder,
+                                        int32_t channels,
+                                        struct OpusError *error);
+
+/**
+ * 获取编码器强制使用的声道数
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，1、2 或 `FORCE_CHANNELS_AUTO`（-1000）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_get_encoder_force_channels(struct Encoder *encoder,
+                                        int32_t *result,
+                                        struct OpusError *error);
+
+/**
+ * 获取编码器最近一帧的熵编码器最终状态
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `result` - 输出参数，最终状态
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 解码器解码同一个数据包后，`opus_ffi_get_decoder_final_range` 应返回相同的值，
+ *   不同则说明数据包在传输中损坏或编解码结果不一致
+ */
+int opus_ffi_get_encoder_final_range(struct Encoder *encoder,
+                                     uint32_t *result,
+                                     struct OpusError *error);
+
+/**
+ * 重置编码器状态（对应 `OPUS_RESET_STATE`）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 清除之前编码留下的内部状态，之后的输出与新创建的编码器相同，适合在开始新的通话或录音时复用编码器
+ * * 比特率、复杂度等设置保持不变
+ *
+ * # 示例
+ *
+ * ```c
+ * // 上一段录音结束，开始新的录音
+ * opus_ffi_reset_encoder(encoder, &error);
+ * ```
+ */
+int opus_ffi_reset_encoder(struct Encoder *encoder,
+                           struct OpusError *error);
+
+/**
+ * 释放 Opus 编码器实例
+ *
+ * # 参数
+ *
+ * * `encoder` - 通过 `opus_ffi_new_encoder` 创建的编码器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `encoder` 为 `NULL`，函数不会执行任何操作
+ * * 释放后，`encoder` 指针将不再有效，不应再次使用
+ * * 每个通过 `opus_ffi_new_encoder` 创建的编码器必须且只能调用一次此函数
+ * * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `encoder` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `encoder` 不是有效的编码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ *
+ * # 示例
+ *
+ * ```c
+ * Encoder *encoder = NULL;
+ * // ... 创建和使用编码器 ...
+ * opus_ffi_free_encoder(encoder);
+ * encoder = NULL; // 防止重复释放
+ * ```
+ */
+int opus_ffi_free_encoder(struct Encoder *encoder);
+
+/**
+ * 获取错误代码的名称
+ *
+ * # 参数
+ *
+ * * `code` - 任意函数返回的错误代码
+ *
+ * # 返回值
+ *
+ * 错误代码对应的常量名（例如 `-9` 返回 `"OPUS_ERROR_CODE_INVALID_INPUT"`），
+ * 不认识的代码返回 `"OPUS_ERROR_CODE_UNRECOGNIZED"`，不会返回 `NULL`。
+ *
+ * # 安全性
+ *
+ * 返回的是静态字符串，调用者不能释放。
+ *
+ * # 示例
+ *
+ * ```c
+ * int res = opus_ffi_new_decoder(6, 16000, &decoder, NULL);
+ * if (res < 0) {
+ *     printf("%s: %s\n", opus_ffi_error_name(res), opus_ffi_last_error_message());
+ * }
+ * ```
+ */
+const char *opus_ffi_error_name(int code);
+
+/**
+ * 获取当前线程最近一次失败调用的错误码
+ *
+ * # 返回值
+ *
+ * * `0` - 当前线程还没有调用失败过，或已调用 `opus_ffi_clear_last_error` 清除
+ * * 负数 - 最近一次失败调用返回的错误代码
+ *
+ * # 注意事项
+ *
+ * * 与 C 的 `errno` 类似，调用成功时不会清除上一次的错误，应先检查函数的返回值再读取
+ * * 不论调用时 `error` 参数是否为 `NULL`，错误都会被记录，热路径可以传入 `NULL` 以避免分配和释放 `OpusError`
+ * * 错误按线程分别保存，只能读取到本线程的错误
+ *
+ * # 示例
+ *
+ * ```c
+ * if (opus_ffi_decode(decoder, packet, packet_size, pcm, 320, false, &samples, NULL) < 0) {
+ *     printf("Error %d: %s\n", opus_ffi_last_error_code(), opus_ffi_last_error_message());
+ * }
+ * ```
+ */
+int opus_ffi_last_error_code(void);
+
+/**
+ * 获取当前线程最近一次失败调用的错误描述
+ *
+ * # 返回值
+ *
+ * UTF-8 编码的 C 字符串，没有错误时为空字符串，不会返回 `NULL`。
+ *
+ * # 安全性
+ *
+ * * 字符串由库内部持有，调用者不能释放，也不能调用 `opus_ffi_free_c_string`
+ * * 指针在本线程的下一次调用失败或调用 `opus_ffi_clear_last_error` 之前有效，需要保留时应自行复制
+ */
+const char *opus_ffi_last_error_message(void);
+
+/**
+ * 清除当前线程记录的错误，之后 `opus_ffi_last_error_code` 返回 0
+ */
+void opus_ffi_clear_last_error(void);
+
+/**
+ * 创建新的多流（环绕声）解码器
+ *
+ * # 参数
+ *
+ * * `channels` - 声道数（1~8），使用映射族 1 的标准流布局，与同声道数的 `opus_ffi_new_multistream_encoder` 对应
+ * * `sample_rate` - 采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `result` - 输出参数，用于接收创建的解码器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），声道数不在 1~8 之间时返回 `OPUS_ERROR_CODE_UNSUPPORTED`
+ *
+ * # 安全性
+ *
+ * 调用者负责在不再需要时调用 `opus_ffi_free_multistream_decoder` 释放返回的解码器。
+ *
+ * # 示例
+ *
+ * ```c
+ * MultistreamDecoder *decoder = NULL;
+ * OpusError error = {0, NULL};
+ * int res = opus_ffi_new_multistream_decoder(6, 48000, &decoder, &error);
+ * if (res == 0) {
+ *     float pcm[960 * 6];
+ *     size_t samples = 0;
+ *     opus_ffi_multistream_decode_float(decoder, packet, packet_size, pcm, 960 * 6, false, &samples, &error);
+ *     opus_ffi_free_multistream_decoder(decoder);
+ * }
+ * ```
+ */
+int opus_ffi_new_multistream_decoder(uint32_t channels,
+                                     uint32_t sample_rate,
+                                     struct MultistreamDecoder **result,
+                                     struct OpusError *error);
+
+/**
+ * 解码多流 Opus 数据包为交错的 PCM 样本（16 位整数）
+ *
+ * # 参数
+ *
+ * * `decoder` - 之前通过 `opus_ffi_new_multistream_decoder` 创建的解码器实例
+ * * `input` - 指向 Opus 数据包的指针
+ * * `input_size` - 数据包的大小（字节数）
+ * * `output` - 输出缓冲区，用于存储交错的 PCM 样本
+ * * `output_size` - 输出缓冲区的容量（所有声道的样本总数），必须是声道数的整数倍
+ * * `fec` - 前向纠错标志，含义与 `opus_ffi_decode` 相同
+ * * `decoded_size` - 输出参数，解码后实际产生的每声道样本数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_multistream_decode(struct MultistreamDecoder *decoder,
+                                const uint8_t *input,
+                                uint32_t input_size,
+                                int16_t *output,
+                                uint32_t output_size,
+                                bool fec,
+                                uintptr_t *decoded_size,
+                                struct OpusError *error);
+
+/**
+ * 解码多流 Opus 数据包为交错的 PCM 样本（32 位浮点数）
+ *
+ * # 参数
+ *
+ * * `decoder` - 之前通过 `opus_ffi_new_multistream_decoder` 创建的解码器实例
+ * * `input` - 指向 Opus 数据包的指针
+ * * `input_size` - 数据包的大小（字节数）
+ * * `output` - 输出缓冲区，用于存储交错的浮点 PCM 样本
+ * * `output_size` - 输出缓冲区的容量（所有声道的样本总数），必须是声道数的整数倍
+ * * `fec` - 前向纠错标志
+ * * `result` - 输出参数，解码后实际产生的每声道样本数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_multistream_decode_float(struct MultistreamDecoder *decoder,
+                                      const uint8_t *input,
+                                      uint32_t input_size,
+                                      float *output,
+                                      uint32_t output_size,
+                                      bool fec,
+                                      uintptr_t *result,
+                                      struct OpusError *error);
+
+/**
+ * 释放多流解码器实例
+ *
+ * # 参数
+ *
+ * * `decoder` - 通过 `opus_ffi_new_multistream_decoder` 创建的解码器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `decoder` 为 `NULL`，函数不会执行任何操作
+ * * 释放后，`decoder` 指针将不再有效
+ * * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `decoder` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `decoder` 不是有效的多流解码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ */
+int opus_ffi_free_multistream_decoder(struct MultistreamDecoder *decoder);
+
+/**
+ * 创建新的多流（环绕声）编码器
+ *
+ * # 参数
+ *
+ * * `channels` - 声道数（1~8），按映射族 1 的 Vorbis 声道顺序排列，例如 6 表示 5.1，8 表示 7.1
+ * * `sample_rate` - 采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `application` - 应用模式，取值与 `opus_ffi_new_encoder` 相同
+ * * `result` - 输出参数，用于接收创建的编码器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），声道数不在 1~8 之间时返回 `OPUS_ERROR_CODE_UNSUPPORTED`
+ *
+ * # 安全性
+ *
+ * 调用者负责在不再需要时调用 `opus_ffi_free_multistream_encoder` 释放返回的编码器。
+ *
+ * # 示例
+ *
+ * ```c
+ * MultistreamEncoder *encoder = NULL;
+ * OpusError error = {0, NULL};
+ * int res = opus_ffi_new_multistream_encoder(6, 48000, 2, &encoder, &error);
+ * if (res == 0) {
+ *     float pcm[960 * 6]; // 20ms @ 48kHz, 5.1 交错样本
+ *     uint8_t packet[4000 * 6];
+ *     size_t packet_size = 0;
+ *     opus_ffi_multistream_encode_float(encoder, pcm, 960 * 6, packet, sizeof(packet), &packet_size, &error);
+ *     opus_ffi_free_multistream_encoder(encoder);
+ * }
+ * ```
+ */
+int opus_ffi_new_multistream_encoder(uint32_t channels,
+                                     uint32_t sample_rate,
+                                     uint32_t application,
+                                     struct MultistreamEncoder **result,
+                                     struct OpusError *error);
+
+/**
+ * 获取多流编码器的流布局
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_multistream_encoder` 创建的编码器实例
+ * * `result` - 输出参数，用于接收流布局
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 映射族 1 的布局只取决于声道数，`opus_ffi_new_multistream_decoder` 会使用相同的布局
+ */
+int opus_ffi_multistream_encoder_get_layout(struct MultistreamEncoder *encoder,
+                                            struct MultistreamLayout *result,
+                                            struct OpusError *error);
+
+/**
+ * 将交错的 PCM 样本编码为多流 Opus 数据包（16 位整数输入）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_multistream_encoder` 创建的编码器实例
+ * * `input` - 指向交错 PCM 样本的指针（16 位有符号整数）
+ * * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍
+ * * `output` - 输出缓冲区，用于存储编码后的数据包
+ * * `output_size` - 输出缓冲区的容量（字节数）。建议每个声道至少 4000 字节
+ * * `encoded_size` - 输出参数，编码后实际产生的字节数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_multistream_encode(struct MultistreamEncoder *encoder,
+                                const int16_t *input,
+                                uint32_t input_size,
+                                uint8_t *output,
+                                uint32_t output_size,
+                                uintptr_t *encoded_size,
+                                struct OpusError *error);
+
+/**
+ * 将交错的 PCM 样本编码为多流 Opus 数据包（32 位浮点数输入）
+ *
+ * # 参数
+ *
+ * * `encoder` - 之前通过 `opus_ffi_new_multistream_encoder` 创建的编码器实例
+ * * `input` - 指向交错 PCM 样本的指针（32 位浮点数，范围通常在 [-1.0, 1.0] 之间）
+ * * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍
+ * * `output` - 输出缓冲区，用于存储编码后的数据包
+ * * `output_size` - 输出缓冲区的容量（字节数）
+ * * `result` - 输出参数，编码后实际产生的字节数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_multistream_encode_float(struct MultistreamEncoder *encoder,
+                                      const float *input,
+                                      uint32_t input_size,
+                                      uint8_t *output,
+                                      uint32_t output_size,
+                                      uintptr_t *result,
+                                      struct OpusError *error);
+
+/**
+ * 释放多流编码器实例
+ *
+ * # 参数
+ *
+ * * `encoder` - 通过 `opus_ffi_new_multistream_encoder` 创建的编码器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `encoder` 为 `NULL`，函数不会执行任何操作
+ * * 释放后，`encoder` 指针将不再有效
+ * * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `encoder` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `encoder` 不是有效的多流编码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ */
+int opus_ffi_free_multistream_encoder(struct MultistreamEncoder *encoder);
+
+/**
+ * 打开 Ogg Opus 文件用于解码
+ *
+ * # 参数
+ *
+ * * `path` - 输入文件路径（UTF-8 编码的 C 字符串）
+ * * `sample_rate` - 输出 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `result` - 输出参数，用于接收创建的读取器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 安全性
+ *
+ * 调用者负责在不再需要时调用 `opus_ffi_free_ogg_reader` 释放返回的读取器。
+ *
+ * # 注意事项
+ *
+ * * 打开时会读取并校验 OpusHead 和 OpusTags，文件不是 Ogg Opus 格式时返回错误
+ * * 仅支持映射族 0（单声道和立体声）
+ *
+ * # 示例
+ *
+ * ```c
+ * OggOpusReader *reader = NULL;
+ * OpusError error = {0, NULL};
+ * int res = opus_ffi_ogg_reader_open_file("/tmp/voice.opus", 48000, &reader, &error);
+ * if (res == 0) {
+ *     int16_t pcm[960 * 2];
+ *     size_t samples = 0;
+ *     while (opus_ffi_ogg_reader_read(reader, pcm, 960 * 2, &samples, &error) == 0 && samples > 0) {
+ *         // 使用 pcm...
+ *     }
+ *     opus_ffi_free_ogg_reader(reader);
+ * }
+ * ```
+ */
+int opus_ffi_ogg_reader_open_file(const char *path,
+                                  uint32_t sample_rate,
+                                  struct OggOpusReader **result,
+                                  struct OpusError *error);
+
+/**
+ * 从内存中的 Ogg Opus 数据创建读取器
+ *
+ * # 参数
+ *
+ * * `data` - 指向 Ogg Opus 数据的指针
+ * * `data_size` - 数据长度（字节数）
+ * * `sample_rate` - 输出 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `result` - 输出参数，用于接收创建的读取器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 数据会被复制，调用返回后即可释放 `data`
+ */
+int opus_ffi_ogg_reader_open_memory(const uint8_t *data,
+                                    uintptr_t data_size,
+                                    uint32_t sample_rate,
+                                    struct OggOpusReader **result,
+                                    struct OpusError *error);
+
+/**
+ * 获取 OpusHead 中的流参数
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `result` - 输出参数，用于接收流参数。`channels` 即 `opus_ffi_ogg_reader_read` 输出的声道数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_ogg_reader_get_head(struct OggOpusReader *reader,
+                                 struct OggOpusHead *result,
+                                 struct OpusError *error);
+
+/**
+ * 获取 OpusTags 中的厂商字符串
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `result` - 输出参数，用于接收厂商字符串，需使用 `opus_ffi_free_c_string` 释放
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_ogg_reader_get_vendor(struct OggOpusReader *reader,
+                                   char **result,
+                                   struct OpusError *error);
+
+/**
+ * 获取 OpusTags 中用户注释的数量
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `result` - 输出参数，用于接收注释数量
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_ogg_reader_get_comment_count(struct OggOpusReader *reader,
+                                          uint32_t *result,
+                                          struct OpusError *error);
+
+/**
+ * 获取 OpusTags 中的一条用户注释
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `index` - 注释序号，范围为 `[0, opus_ffi_ogg_reader_get_comment_count)`
+ * * `result` - 输出参数，用于接收注释（形如 `TITLE=...`），需使用 `opus_ffi_free_c_string` 释放
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），序号越界时返回无效输入错误
+ */
+int opus_ffi_ogg_reader_get_comment(struct OggOpusReader *reader,
+                                    uint32_t index,
+                                    char **result,
+                                    struct OpusError *error);
+
+/**
+ * 从 Ogg Opus 读取器读取解码后的 PCM 样本（16 位整数）
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `output` - 指向输出缓冲区的指针，用于存储交错的 PCM 样本（16 位有符号整数）
+ * * `output_size` - 输出缓冲区的大小（样本总数，所有声道），至少为声道数，不必是声道数的整数倍
+ * * `samples_read` - 输出参数，实际读取的每声道样本数，`0` 表示已到达流末尾
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），`output_size` 小于声道数时返回 `OPUS_ERROR_CODE_BUFFER_TOO_SMALL`
+ *
+ * # 注意事项
+ *
+ * * 开头的预跳过样本和结尾补齐的样本已被裁掉，OpusHead 中的输出增益已被应用
+ * * 除非到达流末尾，否则总是填满输出缓冲区（按声道数向下取整）
+ */
+int opus_ffi_ogg_reader_read(struct OggOpusReader *reader,
+                             int16_t *output,
+                             uint32_t output_size,
+                             uintptr_t *samples_read,
+                             struct OpusError *error);
+
+/**
+ * 从 Ogg Opus 读取器读取解码后的 PCM 样本（32 位浮点数）
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `output` - 指向输出缓冲区的指针，用于存储交错的 PCM 样本（32 位浮点数）
+ * * `output_size` - 输出缓冲区的大小（样本总数，所有声道），至少为声道数，不必是声道数的整数倍
+ * * `samples_read` - 输出参数，实际读取的每声道样本数，`0` 表示已到达流末尾
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），`output_size` 小于声道数时返回 `OPUS_ERROR_CODE_BUFFER_TOO_SMALL`
+ */
+int opus_ffi_ogg_reader_read_float(struct OggOpusReader *reader,
+                                   float *output,
+                                   uint32_t output_size,
+                                   uintptr_t *samples_read,
+                                   struct OpusError *error);
+
+/**
+ * 获取 Ogg Opus 流的总时长（样本数）
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `result` - 输出参数，每声道样本数（输出采样率），不含开头的预跳过部分
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 时长由最后一页的粒度位置计算，首次调用时需要读取文件末尾，之后使用缓存的结果
+ * * 不影响当前的读取位置
+ */
+int opus_ffi_ogg_reader_get_duration(struct OggOpusReader *reader,
+                                     uint64_t *result,
+                                     struct OpusError *error);
+
+/**
+ * 获取 Ogg Opus 流的总时长（毫秒）
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `result` - 输出参数，总时长（毫秒）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_ogg_reader_get_duration_ms(struct OggOpusReader *reader,
+                                        uint64_t *result,
+                                        struct OpusError *error);
+
+/**
+ * 定位到指定的样本位置
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `sample` - 目标位置，每声道样本数（输出采样率），0 表示流的开头
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 定位是样本精确的：解码器会被重置，并从目标位置前至少 80ms 处开始解码以消除重置带来的失真
+ * * 目标位置超出流末尾时不会报错，之后的 `opus_ffi_ogg_reader_read` 返回 0 个样本
+ *
+ * # 示例
+ *
+ * ```c
+ * uint64_t duration = 0;
+ * opus_ffi_ogg_reader_get_duration(reader, &duration, &error);
+ * // 跳到中间位置继续播放
+ * opus_ffi_ogg_reader_seek(reader, duration / 2, &error);
+ * opus_ffi_ogg_reader_read(reader, pcm, 960 * 2, &samples, &error);
+ * ```
+ */
+int opus_ffi_ogg_reader_seek(struct OggOpusReader *reader,
+                             uint64_t sample,
+                             struct OpusError *error);
+
+/**
+ * 定位到指定的时间位置
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `ms` - 目标位置（毫秒），0 表示流的开头
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_ogg_reader_seek_ms(struct OggOpusReader *reader, uint64_t ms, struct OpusError *error);
+
+/**
+ * 释放 Ogg Opus 读取器实例
+ *
+ * # 参数
+ *
+ * * `reader` - 通过 `opus_ffi_ogg_reader_open_file` 或 `opus_ffi_ogg_reader_open_memory` 创建的读取器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `reader` 为 `NULL`，函数不会执行任何操作
+ * * 释放后，`reader` 指针将不再有效
+ * * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `reader` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `reader` 不是有效的读取器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ */
+int opus_ffi_free_ogg_reader(struct OggOpusReader *reader);
+
+/**
+ * 创建写入到文件的 Ogg Opus 写入器
+ *
+ * # 参数
+ *
+ * * `path` - 输出文件路径（UTF-8 编码的 C 字符串）。文件已存在时会被覆盖
+ * * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+ *   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
+ * * `sample_rate` - 输入 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `application` - 应用模式，取值与 `opus_ffi_new_encoder` 相同
+ * * `result` - 输出参数，用于接收创建的写入器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 安全性
+ *
+ * 调用者负责在不再需要时调用 `opus_ffi_free_ogg_writer` 释放返回的写入器。
+ *
+ * # 示例
+ *
+ * ```c
+ * OggOpusWriter *writer = NULL;
+ * OpusError error = {0, NULL};
+ * int res = opus_ffi_ogg_writer_open_file("/tmp/voice.opus", 1, 16000, 1, &writer, &error);
+ * if (res == 0) {
+ *     opus_ffi_ogg_writer_write(writer, pcm_samples, sample_count, &error);
+ *     opus_ffi_ogg_writer_finish(writer, NULL, NULL, &error);
+ *     opus_ffi_free_ogg_writer(writer);
+ * }
+ * ```
+ */
+int opus_ffi_ogg_writer_open_file(const char *path,
+                                  uint32_t channels,
+                                  uint32_t sample_rate,
+                                  uint32_t application,
+                                  struct OggOpusWriter **result,
+                                  struct OpusError *error);
+
+/**
+ * 创建写入到内存的 Ogg Opus 写入器
+ *
+ * # 参数
+ *
+ * * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+ *   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
+ * * `sample_rate` - 输入 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `application` - 应用模式，取值与 `opus_ffi_new_encoder` 相同
+ * * `result` - 输出参数，用于接收创建的写入器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 编码后的数据保存在内存中，通过 `opus_ffi_ogg_writer_finish` 一次性取出
+ */
+int opus_ffi_ogg_writer_open_memory(uint32_t channels,
+                                    uint32_t sample_rate,
+                                    uint32_t application,
+                                    struct OggOpusWriter **result,
+                                    struct OpusError *error);
+
+/**
+ * 获取写入器内部使用的编码器
+ *
+ * # 参数
+ *
+ * * `writer` - 之前通过 `opus_ffi_ogg_writer_open_file` 或 `opus_ffi_ogg_writer_open_memory` 创建的写入器
+ * * `result` - 输出参数，编码器指针。可以传给 `opus_ffi_set_encoder_bitrate` 等函数调整编码参数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 安全性
+ *
+ * * 返回的编码器归写入器所有，不能调用 `opus_ffi_free_encoder` 释放，也不能在 `opus_ffi_free_ogg_writer` 之后继续使用
+ * * 通过它的调用与写入器上的调用依次执行；写入器释放后它随之失效，使用时返回 `OPUS_ERROR_CODE_INVALID_HANDLE`
+ * * 不要直接用它调用 `opus_ffi_encode`，否则编码器状态与 Ogg 流不一致
+ */
+int opus_ffi_ogg_writer_get_encoder(struct OggOpusWriter *writer,
+                                    struct Encoder **result,
+                                    struct OpusError *error);
+
+/**
+ * 向 Ogg Opus 写入器写入 PCM 样本（16 位整数）
+ *
+ * # 参数
+ *
+ * * `writer` - 之前创建的写入器实例
+ * * `input` - 指向交错 PCM 样本的指针（16 位有符号整数）
+ * * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍，长度不要求是整帧
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 写入器以 20ms 为一帧进行编码，不足一帧的样本会暂存到下一次写入或 `opus_ffi_ogg_writer_finish`
+ */
+int opus_ffi_ogg_writer_write(struct OggOpusWriter *writer,
+                              const int16_t *input,
+                              uint32_t input_size,
+                              struct OpusError *error);
+
+/**
+ * 向 Ogg Opus 写入器写入 PCM 样本（32 位浮点数）
+ *
+ * # 参数
+ *
+ * * `writer` - 之前创建的写入器实例
+ * * `input` - 指向交错 PCM 样本的指针（32 位浮点数，范围通常在 [-1.0, 1.0] 之间）
+ * * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_ogg_writer_write_float(struct OggOpusWriter *writer,
+                                    const float *input,
+                                    uint32_t input_size,
+                                    struct OpusError *error);
+
+/**
+ * 结束 Ogg Opus 写入
+ *
+ * # 参数
+ *
+ * * `writer` - 之前创建的写入器实例
+ * * `data` - 可选的输出参数。对于内存写入器，用于接收完整的 Ogg Opus 数据，需使用 `opus_ffi_free_buffer` 释放；
+ *   对于文件写入器，将被设置为 `NULL`
+ * * `data_size` - 可选的输出参数，`data` 的长度（字节数）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 最后不足一帧的样本会用静音补齐，最后一页的粒度位置会标记实际长度，播放器据此裁掉补齐的部分
+ * * 结束后写入器不能再写入，但仍需调用 `opus_ffi_free_ogg_writer` 释放
+ *
+ * # 示例
+ *
+ * ```c
+ * uint8_t *data = NULL;
+ * size_t size = 0;
+ * int res = opus_ffi_ogg_writer_finish(writer, &data, &size, &error);
+ * if (res == 0) {
+ *     // 使用 data...
+ *     opus_ffi_free_buffer(data, size);
+ * }
+ * opus_ffi_free_ogg_writer(writer);
+ * ```
+ */
+int opus_ffi_ogg_writer_finish(struct OggOpusWriter *writer,
+                               uint8_t **data,
+                               uintptr_t *data_size,
+                               struct OpusError *error);
+
+/**
+ * 释放 Ogg Opus 写入器实例
+ *
+ * # 参数
+ *
+ * * `writer` - 通过 `opus_ffi_ogg_writer_open_file` 或 `opus_ffi_ogg_writer_open_memory` 创建的写入器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `writer` 为 `NULL`，函数不会执行任何操作
+ * * 未调用 `opus_ffi_ogg_writer_finish` 就释放时，文件写入器已写出的数据会保留，但文件不完整
+ * * 释放后，`writer` 指针以及通过 `opus_ffi_ogg_writer_get_encoder` 获取的编码器指针都将不再有效
+ * * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `writer` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `writer` 不是有效的写入器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ */
+int opus_ffi_free_ogg_writer(struct OggOpusWriter *writer);
+
+/**
+ * 解析 Opus 数据包的 TOC 信息（不解码）
+ *
+ * # 参数
+ *
+ * * `data` - 指向 Opus 数据包的指针
+ * * `data_size` - 数据包长度（字节数）
+ * * `sample_rate` - 计算 `samples_per_packet` 使用的采样率（Hz），通常与解码器的采样率相同，
+ *   支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `result` - 输出参数，用于接收数据包信息
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），数据包结构不合法时返回 `OPUS_INVALID_PACKET`
+ *
+ * # 注意事项
+ *
+ * * 解码该数据包需要的输出缓冲区大小为 `samples_per_packet * 声道数`
+ * * 解码器的声道数与数据包的 `stereo` 标志无关，单声道解码器也可以解码立体声数据包
+ *
+ * # 示例
+ *
+ * ```c
+ * PacketInfo info;
+ * OpusError error = {0, NULL};
+ * if (opus_ffi_packet_get_info(packet, packet_size, 16000, &info, &error) == 0) {
+ *     int16_t *pcm = malloc(info.samples_per_packet * channels * sizeof(int16_t));
+ *     opus_ffi_decode(decoder, packet, packet_size, pcm, info.samples_per_packet * channels,
+ *            false, &samples, &error);
+ * }
+ * ```
+ */
+int opus_ffi_packet_get_info(const uint8_t *data,
+                             uintptr_t data_size,
+                             uint32_t sample_rate,
+                             struct PacketInfo *result,
+                             struct OpusError *error);
+
+/**
+ * 判断数据包是否为 DTX（不连续传输）数据包
+ *
+ * # 参数
+ *
+ * * `data` - 指向 Opus 数据包的指针
+ * * `data_size` - 数据包长度（字节数）
+ *
+ * # 返回值
+ *
+ * 数据包为 1~2 字节（只有 TOC，不含音频数据）时返回 `true`，`data` 为 `NULL` 或 `data_size` 为 0 时返回 `false`
+ *
+ * # 注意事项
+ *
+ * * DTX 数据包不需要发送或保存；已经收到的 DTX 数据包直接交给 `opus_ffi_decode` 解码，按数据包时长生成舒适噪声
+ */
+bool opus_ffi_packet_is_dtx(const uint8_t *data,
+                            uintptr_t data_size);
+
+/**
+ * 分析内存中没有头部的固定包长 Opus 录音
+ *
+ * # 参数
+ *
+ * * `data` - 指向录音数据的指针
+ * * `data_size` - 数据长度（字节数）。只分析开头的部分数据，传入整个文件或开头约 640KB 即可
+ * * `result` - 输出参数，用于接收分析结果
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功（包括无法识别的情况，此时 `result->confidence` 为 0）
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 采样率由数据包的编码带宽推断，可直接用于创建解码器；原始录音的采样率可能更高
+ * * 置信度较低（例如小于 0.5）时，数据很可能不是固定包长的 Opus 录音
+ *
+ * # 示例
+ *
+ * ```c
+ * LegacyStreamInfo info;
+ * OpusError error = {0, NULL};
+ * if (opus_ffi_analyze_legacy_file("/tmp/R20250728-151607.opus", &info, &error) == 0
+ *     && info.confidence > 0.5) {
+ *     PacketStreamReader *reader = NULL;
+ *     opus_ffi_packet_reader_open_legacy_file("/tmp/R20250728-151607.opus", info.packet_size,
+ *                                    info.sample_rate, info.channels, &reader, &error);
+ * }
+ * ```
+ */
+int opus_ffi_analyze_legacy_memory(const uint8_t *data,
+                                   uintptr_t data_size,
+                                   struct LegacyStreamInfo *result,
+                                   struct OpusError *error);
+
+/**
+ * 分析没有头部的固定包长 Opus 录音文件
+ *
+ * # 参数
+ *
+ * * `path` - 录音文件路径（UTF-8 编码的 C 字符串）
+ * * `result` - 输出参数，用于接收分析结果
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功（包括无法识别的情况，此时 `result->confidence` 为 0）
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 只读取文件开头的部分数据，大文件也能很快完成
+ */
+int opus_ffi_analyze_legacy_file(const char *path,
+                                 struct LegacyStreamInfo *result,
+                                 struct OpusError *error);
+
+/**
+ * 打开数据包流文件
+ *
+ * # 参数
+ *
+ * * `path` - 输入文件路径（UTF-8 编码的 C 字符串）
+ * * `result` - 输出参数，用于接收创建的读取器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），文件不是数据包流格式时返回格式错误
+ *
+ * # 安全性
+ *
+ * 调用者负责在不再需要时调用 `opus_ffi_free_packet_reader` 释放返回的读取器。
+ *
+ * # 示例
+ *
+ * ```c
+ * PacketStreamReader *reader = NULL;
+ * OpusError error = {0, NULL};
+ * if (opus_ffi_packet_reader_open_file("/tmp/voice.opkt", &reader, &error) == 0) {
+ *     PacketStreamHeader header;
+ *     opus_ffi_packet_reader_get_header(reader, &header, &error);
+ *     uint8_t packet[4000];
+ *     size_t packet_size = 0;
+ *     while (opus_ffi_packet_reader_next(reader, packet, sizeof(packet), &packet_size, &error) == 0
+ *            && packet_size > 0) {
+ *         opus_ffi_decode(decoder, packet, packet_size, pcm, pcm_size, false, &samples, &error);
+ *     }
+ *     opus_ffi_free_packet_reader(reader);
+ * }
+ * ```
+ */
+int opus_ffi_packet_reader_open_file(const char *path,
+                                     struct PacketStreamReader **result,
+                                     struct OpusError *error);
+
+/**
+ * 从内存中的数据包流创建读取器
+ *
+ * # 参数
+ *
+ * * `data` - 指向数据包流数据的指针
+ * * `data_size` - 数据长度（字节数）
+ * * `result` - 输出参数，用于接收创建的读取器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 数据会被复制，调用返回后即可释放 `data`
+ */
+int opus_ffi_packet_reader_open_memory(const uint8_t *data,
+                                       uintptr_t data_size,
+                                       struct PacketStreamReader **result,
+                                       struct OpusError *error);
+
+/**
+ * 打开没有头部、固定包长的旧版录音文件
+ *
+ * # 参数
+ *
+ * * `path` - 输入文件路径（UTF-8 编码的 C 字符串）
+ * * `packet_size` - 每个数据包的固定长度（字节数），例如现有录音使用的 80，范围 1 ~ 65535
+ * * `sample_rate` - 录音编码时使用的采样率（Hz）
+ * * `channels` - 录音的声道数
+ * * `result` - 输出参数，用于接收创建的读取器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 旧版录音只能用于 CBR 编码、包长固定的数据，文件末尾不足一个包长的数据会被忽略
+ * * `opus_ffi_packet_reader_get_header` 返回调用者传入的参数，`frame_duration_us` 为 0
+ */
+int opus_ffi_packet_reader_open_legacy_file(const char *path,
+                                            uintptr_t packet_size,
+                                            uint32_t sample_rate,
+                                            uint32_t channels,
+                                            struct PacketStreamReader **result,
+                                            struct OpusError *error);
+
+/**
+ * 从内存中没有头部、固定包长的旧版录音创建读取器
+ *
+ * # 参数
+ *
+ * * `data` - 指向录音数据的指针
+ * * `data_size` - 数据长度（字节数）
+ * * `packet_size` - 每个数据包的固定长度（字节数），范围 1 ~ 65535
+ * * `sample_rate` - 录音编码时使用的采样率（Hz）
+ * * `channels` - 录音的声道数
+ * * `result` - 输出参数，用于接收创建的读取器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 数据会被复制，调用返回后即可释放 `data`
+ */
+int opus_ffi_packet_reader_open_legacy_memory(const uint8_t *data,
+                                              uintptr_t data_size,
+                                              uintptr_t packet_size,
+                                              uint32_t sample_rate,
+                                              uint32_t channels,
+                                              struct PacketStreamReader **result,
+                                              struct OpusError *error);
+
+/**
+ * 获取数据包流的头部信息
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `result` - 输出参数，用于接收头部信息，可据此创建解码器
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_packet_reader_get_header(struct PacketStreamReader *reader,
+                                      struct PacketStreamHeader *result,
+                                      struct OpusError *error);
+
+/**
+ * 读取下一个数据包
+ *
+ * # 参数
+ *
+ * * `reader` - 之前创建的读取器实例
+ * * `output` - 指向输出缓冲区的指针，用于存储数据包
+ * * `output_size` - 输出缓冲区的大小（字节数），建议不小于 4000
+ * * `packet_size` - 输出参数，数据包长度（字节数），`0` 表示已到达流末尾
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 缓冲区放不下数据包时返回 `BufferTooSmall` 错误，数据包不会被丢弃，可以换用更大的缓冲区重新读取
+ */
+int opus_ffi_packet_reader_next(struct PacketStreamReader *reader,
+                                uint8_t *output,
+                                uintptr_t output_size,
+                                uintptr_t *packet_size,
+                                struct OpusError *error);
+
+/**
+ * 释放数据包流读取器实例
+ *
+ * # 参数
+ *
+ * * `reader` - 通过 `packet_reader_open_*` 系列函数创建的读取器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `reader` 为 `NULL`，函数不会执行任何操作
+ * * 释放后，`reader` 指针将不再有效
+ * * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `reader` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `reader` 不是有效的读取器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ */
+int opus_ffi_free_packet_reader(struct PacketStreamReader *reader);
+
+/**
+ * 创建写入到文件的数据包流写入器
+ *
+ * # 参数
+ *
+ * * `path` - 输出文件路径（UTF-8 编码的 C 字符串）。文件已存在时会被覆盖
+ * * `sample_rate` - 编码时使用的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `channels` - 声道数
+ * * `frame_duration_us` - 每帧时长（微秒），例如 20ms 帧为 20000；帧长不固定时传 0
+ * * `result` - 输出参数，用于接收创建的写入器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 安全性
+ *
+ * 调用者负责在不再需要时调用 `opus_ffi_free_packet_writer` 释放返回的写入器。
+ *
+ * # 示例
+ *
+ * ```c
+ * PacketStreamWriter *writer = NULL;
+ * OpusError error = {0, NULL};
+ * int res = opus_ffi_packet_writer_open_file("/tmp/voice.opkt", 16000, 1, 20000, &writer, &error);
+ * if (res == 0) {
+ *     // 每次编码得到的数据包长度可以不同
+ *     opus_ffi_packet_writer_write(writer, packet, packet_size, &error);
+ *     opus_ffi_packet_writer_finish(writer, NULL, NULL, &error);
+ *     opus_ffi_free_packet_writer(writer);
+ * }
+ * ```
+ */
+int opus_ffi_packet_writer_open_file(const char *path,
+                                     uint32_t sample_rate,
+                                     uint32_t channels,
+                                     uint32_t frame_duration_us,
+                                     struct PacketStreamWriter **result,
+                                     struct OpusError *error);
+
+/**
+ * 创建写入到内存的数据包流写入器
+ *
+ * # 参数
+ *
+ * * `sample_rate` - 编码时使用的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `channels` - 声道数
+ * * `frame_duration_us` - 每帧时长（微秒），帧长不固定时传 0
+ * * `result` - 输出参数，用于接收创建的写入器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 数据保存在内存中，通过 `opus_ffi_packet_writer_finish` 一次性取出
+ */
+int opus_ffi_packet_writer_open_memory(uint32_t sample_rate,
+                                       uint32_t channels,
+                                       uint32_t frame_duration_us,
+                                       struct PacketStreamWriter **result,
+                                       struct OpusError *error);
+
+/**
+ * 向数据包流写入一个 Opus 数据包
+ *
+ * # 参数
+ *
+ * * `writer` - 之前创建的写入器实例
+ * * `packet` - 指向 Opus 数据包的指针（通常是 `opus_ffi_encode` 的输出）
+ * * `packet_size` - 数据包长度（字节数），范围为 1~65535
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_packet_writer_write(struct PacketStreamWriter *writer,
+                                 const uint8_t *packet,
+                                 uintptr_t packet_size,
+                                 struct OpusError *error);
+
+/**
+ * 结束数据包流写入
+ *
+ * # 参数
+ *
+ * * `writer` - 之前创建的写入器实例
+ * * `data` - 可选的输出参数。对于内存写入器，用于接收完整的数据，需使用 `opus_ffi_free_buffer` 释放；
+ *   对于文件写入器，将被设置为 `NULL`
+ * * `data_size` - 可选的输出参数，`data` 的长度（字节数）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 结束后写入器不能再写入，但仍需调用 `opus_ffi_free_packet_writer` 释放
+ */
+int opus_ffi_packet_writer_finish(struct PacketStreamWriter *writer,
+                                  uint8_t **data,
+                                  uintptr_t *data_size,
+                                  struct OpusError *error);
+
+/**
+ * 释放数据包流写入器实例
+ *
+ * # 参数
+ *
+ * * `writer` - 通过 `opus_ffi_packet_writer_open_file` 或 `opus_ffi_packet_writer_open_memory` 创建的写入器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `writer` 为 `NULL`，函数不会执行任何操作
+ * * 释放后，`writer` 指针将不再有效
+ * * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `writer` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `writer` 不是有效的写入器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ */
+int opus_ffi_free_packet_writer(struct PacketStreamWriter *writer);
+
+/**
+ * 创建新的 Opus 重组器
+ *
+ * # 参数
+ *
+ * * `result` - 输出参数，用于接收创建的重组器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 安全性
+ *
+ * 调用者负责在不再需要时调用 `opus_ffi_free_repacketizer` 释放返回的重组器。
+ *
+ * # 示例
+ *
+ * ```c
+ * // 把 3 个 20ms 数据包合并为一个 60ms 数据包
+ * Repacketizer *rp = NULL;
+ * OpusError error = {0, NULL};
+ * if (opus_ffi_new_repacketizer(&rp, &error) == 0) {
+ *     for (int i = 0; i < 3; i++) {
+ *         opus_ffi_repacketizer_cat(rp, packets[i], packet_sizes[i], &error);
+ *     }
+ *     uint8_t merged[4000];
+ *     size_t merged_size = 0;
+ *     opus_ffi_repacketizer_out(rp, merged, sizeof(merged), &merged_size, &error);
+ *     opus_ffi_free_repacketizer(rp);
+ * }
+ * ```
+ */
+int opus_ffi_new_repacketizer(struct Repacketizer **result, struct OpusError *error);
+
+/**
+ * 向重组器加入一个数据包
+ *
+ * # 参数
+ *
+ * * `repacketizer` - 之前创建的重组器实例
+ * * `data` - 指向 Opus 数据包的指针，数据会被复制
+ * * `data_size` - 数据包长度（字节数）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 加入的所有数据包必须具有相同的 TOC 配置（模式、带宽、帧长、声道），
+ *   否则返回 `OPUS_INVALID_PACKET`，此时重组器中已有的数据包不受影响
+ * * 总时长超过 120ms 时同样返回 `OPUS_INVALID_PACKET`
+ */
+int opus_ffi_repacketizer_cat(struct Repacketizer *repacketizer,
+                              const uint8_t *data,
+                              uintptr_t data_size,
+                              struct OpusError *error);
+
+/**
+ * 获取重组器中已加入的帧数
+ *
+ * # 参数
+ *
+ * * `repacketizer` - 之前创建的重组器实例
+ * * `result` - 输出参数，用于接收帧数。拆分数据包时可据此逐帧调用 `opus_ffi_repacketizer_out_range`
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_repacketizer_get_nb_frames(struct Repacketizer *repacketizer,
+                                        uint32_t *result,
+                                        struct OpusError *error);
+
+/**
+ * 把重组器中的全部帧输出为一个数据包
+ *
+ * # 参数
+ *
+ * * `repacketizer` - 之前创建的重组器实例
+ * * `output` - 指向输出缓冲区的指针
+ * * `output_size` - 输出缓冲区的大小（字节数）
+ * * `result_size` - 输出参数，输出数据包的长度（字节数）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），缓冲区不足时返回 `OPUS_BUFFER_TOO_SMALL`
+ *
+ * # 注意事项
+ *
+ * * 输出后重组器中的数据包仍然保留，需要调用 `opus_ffi_repacketizer_reset` 才能开始下一轮合并
+ */
+int opus_ffi_repacketizer_out(struct Repacketizer *repacketizer,
+                              uint8_t *output,
+                              uintptr_t output_size,
+                              uintptr_t *result_size,
+                              struct OpusError *error);
+
+/**
+ * 把重组器中指定范围的帧输出为一个数据包
+ *
+ * # 参数
+ *
+ * * `repacketizer` - 之前创建的重组器实例
+ * * `begin` - 第一帧的序号（从 0 开始）
+ * * `end` - 最后一帧之后的序号，范围 `[begin, end)` 必须非空且不超过已加入的帧数
+ * * `output` - 指向输出缓冲区的指针
+ * * `output_size` - 输出缓冲区的大小（字节数）
+ * * `result_size` - 输出参数，输出数据包的长度（字节数）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），范围无效时返回 `OPUS_BAD_ARG`
+ *
+ * # 示例
+ *
+ * ```c
+ * // 把一个 60ms 数据包拆分为 3 个 20ms 数据包
+ * opus_ffi_repacketizer_cat(rp, packet, packet_size, &error);
+ * uint32_t frames = 0;
+ * opus_ffi_repacketizer_get_nb_frames(rp, &frames, &error);
+ * for (uint32_t i = 0; i < frames; i++) {
+ *     opus_ffi_repacketizer_out_range(rp, i, i + 1, out, sizeof(out), &out_size, &error);
+ * }
+ * opus_ffi_repacketizer_reset(rp, &error);
+ * ```
+ */
+int opus_ffi_repacketizer_out_range(struct Repacketizer *repacketizer,
+                                    uint32_t begin,
+                                    uint32_t end,
+                                    uint8_t *output,
+                                    uintptr_t output_size,
+                                    uintptr_t *result_size,
+                                    struct OpusError *error);
+
+/**
+ * 清空重组器中已加入的数据包
+ *
+ * # 参数
+ *
+ * * `repacketizer` - 之前创建的重组器实例
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_repacketizer_reset(struct Repacketizer *repacketizer, struct OpusError *error);
+
+/**
+ * 释放 Opus 重组器实例
+ *
+ * # 参数
+ *
+ * * `repacketizer` - 通过 `opus_ffi_new_repacketizer` 创建的重组器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `repacketizer` 为 `NULL`，函数不会执行任何操作
+ * * 释放后，`repacketizer` 指针将不再有效
+ * * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `repacketizer` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `repacketizer` 不是有效的重组器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ */
+int opus_ffi_free_repacketizer(struct Repacketizer *repacketizer);
+
+/**
+ * 把数据包填充到指定长度（不改变解码结果）
+ *
+ * # 参数
+ *
+ * * `data` - 指向数据包缓冲区的指针，缓冲区容量至少为 `new_size` 字节
+ * * `data_size` - 数据包当前的长度（字节数）
+ * * `new_size` - 填充后的长度（字节数），不能小于 `data_size`
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，数据包长度变为 `new_size`
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 常用于在 VBR 下把数据包补齐到固定长度，以便按固定包长传输
+ */
+int opus_ffi_packet_pad(uint8_t *data,
+                        uintptr_t data_size,
+                        uintptr_t new_size,
+                        struct OpusError *error);
+
+/**
+ * 去除数据包中的填充
+ *
+ * # 参数
+ *
+ * * `data` - 指向数据包的指针，结果原地写回
+ * * `data_size` - 数据包的长度（字节数）
+ * * `result_size` - 输出参数，去除填充后的长度（字节数）
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中），数据包不合法时返回 `OPUS_INVALID_PACKET`
+ */
+int opus_ffi_packet_unpad(uint8_t *data,
+                          uintptr_t data_size,
+                          uintptr_t *result_size,
+                          struct OpusError *error);
+
+/**
+ * 创建新的流式编码器
+ *
+ * # 参数
+ *
+ * * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+ *   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
+ * * `sample_rate` - 输入 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+ * * `application` - 应用模式，取值与 `opus_ffi_new_encoder` 相同
+ * * `frame_duration_us` - 每个数据包的时长（微秒）：2500, 5000, 10000, 20000, 40000 或 60000
+ * * `result` - 输出参数，用于接收创建的流式编码器指针
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）。帧时长不合法时返回 `OPUS_BAD_ARG`
+ *
+ * # 安全性
+ *
+ * 调用者负责在不再需要时调用 `opus_ffi_free_stream_encoder` 释放返回的编码器。
+ *
+ * # 示例
+ *
+ * ```c
+ * StreamEncoder *stream = NULL;
+ * OpusError error = {0, NULL};
+ * int res = opus_ffi_new_stream_encoder(1, 16000, 1, 20000, &stream, &error);
+ * if (res == 0) {
+ *     size_t count = 0;
+ *     opus_ffi_stream_encoder_push(stream, mic_samples, mic_sample_count, &count, &error);
+ *     for (size_t i = 0; i < count; i++) {
+ *         uint8_t packet[4000];
+ *         size_t packet_size = 0;
+ *         opus_ffi_stream_encoder_next_packet(stream, packet, sizeof(packet), &packet_size, &error);
+ *         send_packet(packet, packet_size);
+ *     }
+ *     opus_ffi_free_stream_encoder(stream);
+ * }
+ * ```
+ */
+int opus_ffi_new_stream_encoder(uint32_t channels,
+                                uint32_t sample_rate,
+                                uint32_t application,
+                                uint32_t frame_duration_us,
+                                struct StreamEncoder **result,
+                                struct OpusError *error);
+
+/**
+ * 获取流式编码器内部使用的编码器
+ *
+ * # 参数
+ *
+ * * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+ * * `result` - 输出参数，编码器指针。可以传给 `opus_ffi_set_encoder_bitrate`、`opus_ffi_set_encoder_dtx` 等函数调整编码参数
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 安全性
+ *
+ * * 返回的编码器归流式编码器所有，不能调用 `opus_ffi_free_encoder` 释放，也不能在 `opus_ffi_free_stream_encoder` 之后继续使用
+ * * 通过它的调用与流式编码器上的调用依次执行；流式编码器释放后它随之失效，使用时返回 `OPUS_ERROR_CODE_INVALID_HANDLE`
+ * * 不要直接用它调用 `opus_ffi_encode`，否则编码器状态与数据包队列不一致
+ */
+int opus_ffi_stream_encoder_get_encoder(struct StreamEncoder *stream,
+                                        struct Encoder **result,
+                                        struct OpusError *error);
+
+/**
+ * 向流式编码器写入 PCM 样本（16 位整数）
+ *
+ * # 参数
+ *
+ * * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+ * * `input` - 指向交错 PCM 样本的指针（16 位有符号整数）
+ * * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍，长度不要求是整帧
+ * * `packet_count` - 可选的输出参数，写入后队列中等待取出的数据包数量
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 每凑满一帧就编码一个数据包放入队列，不足一帧的样本暂存到下一次写入或 `opus_ffi_stream_encoder_flush`
+ * * 数据包通过 `opus_ffi_stream_encoder_next_packet` 按顺序取出
+ * * 整帧按 16 位整数编码，得到的数据包与对同样的 PCM 逐帧调用 `opus_ffi_encode` 相同
+ */
+int opus_ffi_stream_encoder_push(struct StreamEncoder *stream,
+                                 const int16_t *input,
+                                 uint32_t input_size,
+                                 uintptr_t *packet_count,
+                                 struct OpusError *error);
+
+/**
+ * 向流式编码器写入 PCM 样本（32 位浮点数）
+ *
+ * # 参数
+ *
+ * * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+ * * `input` - 指向交错 PCM 样本的指针（32 位浮点数，范围通常在 [-1.0, 1.0] 之间）
+ * * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍
+ * * `packet_count` - 可选的输出参数，写入后队列中等待取出的数据包数量
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ */
+int opus_ffi_stream_encoder_push_float(struct StreamEncoder *stream,
+                                       const float *input,
+                                       uint32_t input_size,
+                                       uintptr_t *packet_count,
+                                       struct OpusError *error);
+
+/**
+ * 用静音补齐暂存的样本并编码为最后一个数据包
+ *
+ * # 参数
+ *
+ * * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+ * * `packet_count` - 可选的输出参数，补齐后队列中等待取出的数据包数量
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 没有暂存样本时不产生数据包
+ * * 补齐后可以继续写入，例如在一段录音结束后开始下一段
+ */
+int opus_ffi_stream_encoder_flush(struct StreamEncoder *stream,
+                                  uintptr_t *packet_count,
+                                  struct OpusError *error);
+
+/**
+ * 取出下一个已编码的数据包
+ *
+ * # 参数
+ *
+ * * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+ * * `output` - 指向输出缓冲区的指针，用于存储数据包
+ * * `output_size` - 输出缓冲区的大小（字节数），建议不小于 4000
+ * * `packet_size` - 输出参数，数据包长度（字节数），`0` 表示队列中没有数据包
+ * * `error` - 可选的错误输出参数
+ *
+ * # 返回值
+ *
+ * * `0` - 成功
+ * * 负数 - 错误代码（错误详情在 `error` 参数中）
+ *
+ * # 注意事项
+ *
+ * * 缓冲区放不下数据包时返回 `BufferTooSmall` 错误，数据包不会被丢弃，可以换用更大的缓冲区重新读取
+ */
+int opus_ffi_stream_encoder_next_packet(struct StreamEncoder *stream,
+                                        uint8_t *output,
+                                        uintptr_t output_size,
+                                        uintptr_t *packet_size,
+                                        struct OpusError *error);
+
+/**
+ * 释放流式编码器实例
+ *
+ * # 参数
+ *
+ * * `stream` - 通过 `opus_ffi_new_stream_encoder` 创建的流式编码器指针
+ *
+ * # 安全性
+ *
+ * * 如果 `stream` 为 `NULL`，函数不会执行任何操作
+ * * 队列中尚未取出的数据包和暂存的样本会被丢弃，需要时先调用 `opus_ffi_stream_encoder_flush` 并取出全部数据包
+ * * 释放后，`stream` 指针以及通过 `opus_ffi_stream_encoder_get_encoder` 获取的编码器指针都将不再有效
+ *
+ * # 返回值
+ *
+ * * `0` - 成功，或 `stream` 为 `NULL`
+ * * `OPUS_ERROR_CODE_INVALID_HANDLE` - `stream` 不是有效的流式编码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+ */
+int opus_ffi_free_stream_encoder(struct StreamEncoder *stream);
+
+/**
+ * 释放由 Rust 分配的 C 字符串
+ *
+ * # 参数
+ *
+ * * `p` - 指向 C 字符串指针的指针（`char**`）。函数会释放字符串并将指针设置为 `NULL`
+ *
+ * # 安全性
+ *
+ * * 如果 `p` 为 `NULL` 或 `*p` 为 `NULL`，函数不会执行任何操作
+ * * 只能释放由 Rust 代码分配的 C 字符串（通过 `CString::into_raw()` 创建）
+ * * 释放后，字符串指针将被设置为 `NULL`，防止重复释放
+ *
+ * # 示例
+ *
+ * ```c
+ * char *message = NULL;
+ * // ... 从 Rust 函数获取字符串 ...
+ * opus_ffi_free_c_string(&message);
+ * // message 现在为 NULL
+ * ```
+ */
+void opus_ffi_free_c_string(char **p);
+
+/**
+ * 释放堆上分配的 OpusError 结构
+ *
+ * # 参数
+ *
+ * * `e` - 指向堆上分配的 `OpusError` 结构的指针
+ *
+ * # 安全性
+ *
+ * * 如果 `e` 为 `NULL`，函数不会执行任何操作
+ * * 只能释放通过 `Box` 在堆上分配的 `OpusError`
+ * * 栈上分配的 `OpusError` 不需要调用此函数，它们会自动释放
+ * * 释放后，指针将不再有效，不应再次使用
+ *
+ * # 注意事项
+ *
+ * 大多数情况下，`OpusError` 是在栈上分配的，不需要调用此函数。
+ * 只有在特殊情况下（如在堆上分配）才需要调用此函数。
+ *
+ * # 示例
+ *
+ * ```c
+ * OpusError *error = malloc(sizeof(OpusError));
+ * // ... 使用 error ...
+ * opus_ffi_free_opus_error(error);
+ * ```
+ */
+void opus_ffi_free_opus_error(struct OpusError *e);
+
+/**
+ * 释放由 Rust 分配的字节缓冲区
+ *
+ * # 参数
+ *
+ * * `data` - 由 Rust 函数返回的缓冲区指针（例如 `opus_ffi_ogg_writer_finish` 输出的数据）
+ * * `size` - 缓冲区的长度（字节数），必须与返回时的长度一致
+ *
+ * # 安全性
+ *
+ * * 如果 `data` 为 `NULL`，函数不会执行任何操作
+ * * 只能释放由 Rust 代码分配并返回的缓冲区，且每个缓冲区只能释放一次
+ *
+ * # 示例
+ *
+ * ```c
+ * uint8_t *data = NULL;
+ * size_t size = 0;
+ * // ... 从 Rust 函数获取缓冲区 ...
+ * opus_ffi_free_buffer(data, size);
+ * ```
+ */
+void opus_ffi_free_buffer(uint8_t *data,
+                          uintptr_t size);
+
+/**
+ * 设置宽松模式
+ *
+ * # 参数
+ *
+ * * `enabled` - `true` 表示开启宽松模式：不支持的声道数按单声道处理，不支持的应用模式按 Voip 处理（旧版行为）；
+ *   `false` 表示关闭（默认），此时这些值会返回错误
+ *
+ * # 注意事项
+ *
+ * * 该设置是全局的，对之后所有线程创建的编码器、解码器和 Ogg 写入器生效
+ * * 宽松模式只用于兼容依赖旧版行为的调用者，新代码应传入正确的参数
+ *
+ * # 示例
+ *
+ * ```c
+ * opus_ffi_set_lenient_mode(true);
+ * // 声道数 6 会被当作单声道，不再返回错误
+ * opus_ffi_new_encoder(6, 16000, 1, &encoder, &error);
+ * ```
+ */
+void opus_ffi_set_lenient_mode(bool enabled);
+
+/**
+ * 获取当前是否开启了宽松模式
+ */
+bool opus_ffi_get_lenient_mode(void);
+
+/**
+ * 获取版本字符串
+ *
+ * # 返回值
+ *
+ * 形如 `"opus_ffi 0.1.0 (libopus 1.3.1)"` 的 C 字符串，包含本库的版本号和底层 libopus 的版本，不会返回 `NULL`。
+ *
+ * # 安全性
+ *
+ * 返回的是静态字符串，调用者不能释放。
+ *
+ * # 示例
+ *
+ * ```c
+ * printf("%s\n", opus_ffi_version());
+ * ```
+ */
+const char *opus_ffi_version(void);
+
+/**
+ * 获取库的能力描述
+ *
+ * # 参数
+ *
+ * * `result` - 输出参数，用于接收能力描述，为 `NULL` 时不做任何操作
+ *
+ * # 示例
+ *
+ * ```c
+ * OpusFfiCapabilities caps;
+ * opus_ffi_get_capabilities(&caps);
+ * if (caps.version_major != 0 || !(caps.flags & OPUS_FFI_CAP_MULTISTREAM)) {
+ *     // 加载到了不匹配的库文件
+ * }
  * ```
  */
-void free_opus_error(struct OpusError *e);
+void opus_ffi_get_capabilities(struct OpusFfiCapabilities *result);
 
 #endif  /* OPUS_FFI_H */
@@ -60,7 +60,7 @@ class OpusFfiBindings {
             ffi.Pointer<OpusError>,
           )
         >
-      >('new_decoder');
+      >('opus_ffi_new_decoder');
   late final _new_decoder = _new_decoderPtr
       .asFunction<
         int Function(
@@ -108,7 +108,7 @@ class OpusFfiBindings {
             ffi.Pointer<OpusError>,
           )
         >
-      >('decode');
+      >('opus_ffi_decode');
   late final _decode = _decodePtr
       .asFunction<
         int Function(
@@ -160,7 +160,7 @@ class OpusFfiBindings {
             ffi.Pointer<OpusError>,
           )
         >
-      >('decode_float');
+      >('opus_ffi_decode_float');
   late final _decode_float = _decode_floatPtr
       .asFunction<
         int Function(
@@ -176,16 +176,16 @@ class OpusFfiBindings {
       >();
 
   /// 释放 Opus 解码器实例
-  void free_decoder(ffi.Pointer<Decoder> decoder) {
+  int free_decoder(ffi.Pointer<Decoder> decoder) {
     return _free_decoder(decoder);
   }
 
   late final _free_decoderPtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<Decoder>)>>(
-        'free_decoder',
+      _lookup<ffi.NativeFunction<ffi.Int32 Function(ffi.Pointer<Decoder>)>>(
+        'opus_ffi_free_decoder',
       );
   late final _free_decoder = _free_decoderPtr
-      .asFunction<void Function(ffi.Pointer<Decoder>)>();
+      .asFunction<int Function(ffi.Pointer<Decoder>)>();
 
   /// 创建新的 Opus 编码器
   int new_encoder(
@@ -209,7 +209,7 @@ class OpusFfiBindings {
             ffi.Pointer<OpusError>,
           )
         >
-      >('new_encoder');
+      >('opus_ffi_new_encoder');
   late final _new_encoder = _new_encoderPtr
       .asFunction<
         int Function(
@@ -255,7 +255,7 @@ class OpusFfiBindings {
             ffi.Pointer<OpusError>,
           )
         >
-      >('encode');
+      >('opus_ffi_encode');
   late final _encode = _encodePtr
       .asFunction<
         int Function(
@@ -303,7 +303,7 @@ class OpusFfiBindings {
             ffi.Pointer<OpusError>,
           )
         >
-      >('encode_float');
+      >('opus_ffi_encode_float');
   late final _encode_float = _encode_floatPtr
       .asFunction<
         int Function(
@@ -318,16 +318,16 @@ class OpusFfiBindings {
       >();
 
   /// 释放 Opus 编码器实例
-  void free_encoder(ffi.Pointer<Encoder> encoder) {
+  int free_encoder(ffi.Pointer<Encoder> encoder) {
     return _free_encoder(encoder);
   }
 
   late final _free_encoderPtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<Encoder>)>>(
-        'free_encoder',
+      _lookup<ffi.NativeFunction<ffi.Int32 Function(ffi.Pointer<Encoder>)>>(
+        'opus_ffi_free_encoder',
       );
   late final _free_encoder = _free_encoderPtr
-      .asFunction<void Function(ffi.Pointer<Encoder>)>();
+      .asFunction<int Function(ffi.Pointer<Encoder>)>();
 
   /// 释放由 Rust 分配的 C 字符串
   void free_c_string(ffi.Pointer<ffi.Pointer<ffi.Char>> p) {
@@ -339,7 +339,7 @@ class OpusFfiBindings {
         ffi.NativeFunction<
           ffi.Void Function(ffi.Pointer<ffi.Pointer<ffi.Char>>)
         >
-      >('free_c_string');
+      >('opus_ffi_free_c_string');
   late final _free_c_string = _free_c_stringPtr
       .asFunction<void Function(ffi.Pointer<ffi.Pointer<ffi.Char>>)>();
 
@@ -350,7 +350,7 @@ class OpusFfiBindings {
 
   late final _free_opus_errorPtr =
       _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<OpusError>)>>(
-        'free_opus_error',
+        'opus_ffi_free_opus_error',
       );
   late final _free_opus_error = _free_opus_errorPtr
       .asFunction<void Function(ffi.Pointer<OpusError>)>();
@@ -8,15 +8,8 @@ fn main() {
         .with_language(cbindgen::Language::C)
         .with_header("/* Opus FFI Bindings for Rust */")
         .with_include_guard("OPUS_FFI_H")
-        .with_after_include(    r#"
-/**
- * Opus 解码器不透明指针类型
- */
-typedef struct Decoder Decoder;
-/**
- * Opus 编码器不透明指针类型
- */
-typedef struct Encoder Encoder;"#)
+        // 错误代码只作为 int 返回，没有出现在函数签名中，需要显式导出
+        .include_item("OpusErrorCode")
         .generate()
         .expect("Unable to generate bindings")
         .write_to_file("include/opus_ffi.h");
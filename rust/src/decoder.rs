@@ -1,17 +1,40 @@
 use std::ffi::c_int;
 
-use opus_rs::{Channels, Decoder};
+use opus_rs::ErrorCode;
 
 use crate::{
     error::OpusError,
-    utils::{ffi_exec, invalid_input},
+    handle,
+    utils::{buffer_too_small_error, channels_from, ffi_exec, invalid_input, invalid_input_error},
 };
 
+/// Opus 解码器不透明指针类型
+pub struct Decoder {
+    pub(crate) inner: opus_rs::Decoder,
+    // 创建时的声道数和采样率，用于按帧时长换算样本数
+    pub(crate) channels: usize,
+    pub(crate) sample_rate: u32,
+}
+
+impl Decoder {
+    /// 将帧时长（微秒）换算为输出缓冲区所需的样本总数（所有声道）
+    ///
+    /// Opus 的帧时长必须是 2.5ms 的整数倍，且不超过 120ms，否则返回 `None`。
+    pub(crate) fn samples_for_duration(&self, duration_us: u32) -> Option<usize> {
+        if duration_us == 0 || !duration_us.is_multiple_of(2500) || duration_us > 120_000 {
+            return None;
+        }
+
+        Some(self.sample_rate as usize * duration_us as usize / 1_000_000 * self.channels)
+    }
+}
+
 /// 创建新的 Opus 解码器
 ///
 /// # 参数
 ///
-/// * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo），其他值默认为单声道
+/// * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+///   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
 /// * `sample_rate` - 采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
 /// * `result` - 输出参数，用于接收创建的解码器指针。如果函数成功返回，此指针将被设置为有效的解码器实例
 /// * `error` - 可选的错误输出参数。如果函数失败，错误信息将被填充到此结构中
@@ -20,23 +43,24 @@ use crate::{
 ///
 /// * `0` - 成功
 /// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///   声道数不受支持时返回 `OPUS_ERROR_CODE_UNSUPPORTED`
 ///
 /// # 安全性
 ///
-/// 调用者负责在不再需要时调用 `free_decoder` 释放返回的解码器。
+/// 调用者负责在不再需要时调用 `opus_ffi_free_decoder` 释放返回的解码器。
 ///
 /// # 示例
 ///
 /// ```c
 /// Decoder *decoder = NULL;
 /// OpusError error = {0, NULL};
-/// int res = new_decoder(1, 16000, &decoder, &error);
+/// int res = opus_ffi_new_decoder(1, 16000, &decoder, &error);
 /// if (res == 0) {
 ///     // 使用解码器...
-///     free_decoder(decoder);
+///     opus_ffi_free_decoder(decoder);
 /// }
 /// ```
-#[no_mangle]
+#[export_name = "opus_ffi_new_decoder"]
 pub extern "C" fn new_decoder(
     channels: u32,
     sample_rate: u32,
@@ -47,16 +71,15 @@ pub extern "C" fn new_decoder(
         return invalid_input(error);
     }
 
-    let channels = match channels {
-        1 => Channels::Mono,
-        2 => Channels::Stereo,
-        _ => Channels::Mono,
-    };
-
     ffi_exec(error, || {
-        let decoder = Decoder::new(sample_rate, channels)?;
+        let channels = channels_from(channels)?;
+        let decoder = Decoder {
+            inner: opus_rs::Decoder::new(sample_rate, channels)?,
+            channels: channels as usize,
+            sample_rate,
+        };
         unsafe {
-            *result = Box::into_raw(Box::new(decoder));
+            *result = handle::into_handle(decoder);
         }
 
         Ok(())
@@ -67,7 +90,7 @@ pub extern "C" fn new_decoder(
 ///
 /// # 参数
 ///
-/// * `decoder` - 之前通过 `new_decoder` 创建的解码器实例
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
 /// * `input` - 指向 Opus 编码数据包的指针
 /// * `input_size` - 输入数据包的大小（字节数）
 /// * `output` - 输出缓冲区，用于存储解码后的 PCM 样本
@@ -95,12 +118,12 @@ pub extern "C" fn new_decoder(
 /// size_t decoded_samples = 0;
 /// OpusError error = {0, NULL};
 ///
-/// int res = decode(decoder, opus_packet, packet_size, pcm_buffer, 320, false, &decoded_samples, &error);
+/// int res = opus_ffi_decode(decoder, opus_packet, packet_size, pcm_buffer, 320, false, &decoded_samples, &error);
 /// if (res == 0) {
 ///     // 使用解码后的 PCM 数据...
 /// }
 /// ```
-#[no_mangle]
+#[export_name = "opus_ffi_decode"]
 pub extern "C" fn decode(
     decoder: *mut Decoder,
     input: *const u8,
@@ -116,10 +139,10 @@ pub extern "C" fn decode(
     }
 
     ffi_exec(error, || {
-        let decoder = unsafe { &mut *decoder };
+        let mut decoder = handle::get_mut(decoder)?;
         let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
         let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
-        let size = decoder.decode(input, output, fec)?;
+        let size = decoder.inner.decode(input, output, fec)?;
         unsafe {
             (*decoded_size) = size;
         }
@@ -132,7 +155,7 @@ pub extern "C" fn decode(
 ///
 /// # 参数
 ///
-/// * `decoder` - 之前通过 `new_decoder` 创建的解码器实例
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
 /// * `input` - 指向 Opus 编码数据包的指针
 /// * `input_size` - 输入数据包的大小（字节数）
 /// * `output` - 输出缓冲区，用于存储解码后的浮点 PCM 样本
@@ -149,7 +172,7 @@ pub extern "C" fn decode(
 /// # 注意事项
 ///
 /// * 浮点样本的范围通常在 [-1.0, 1.0] 之间
-/// * 此函数与 `decode` 功能相同，但输出格式为浮点数，适合需要高精度处理的场景
+/// * 此函数与 `opus_ffi_decode` 功能相同，但输出格式为浮点数，适合需要高精度处理的场景
 ///
 /// # 示例
 ///
@@ -159,9 +182,9 @@ pub extern "C" fn decode(
 /// size_t decoded_samples = 0;
 /// OpusError error = {0, NULL};
 ///
-/// int res = decode_float(decoder, opus_packet, packet_size, pcm_buffer, 320, false, &decoded_samples, &error);
+/// int res = opus_ffi_decode_float(decoder, opus_packet, packet_size, pcm_buffer, 320, false, &decoded_samples, &error);
 /// ```
-#[no_mangle]
+#[export_name = "opus_ffi_decode_float"]
 pub extern "C" fn decode_float(
     decoder: *mut Decoder,
     input: *const u8,
@@ -177,10 +200,464 @@ pub extern "C" fn decode_float(
     }
 
     ffi_exec(error, || {
-        let decoder = unsafe { &mut *decoder };
+        let mut decoder = handle::get_mut(decoder)?;
         let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
         let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
-        let size = decoder.decode_float(input, output, fec)?;
+        let size = decoder.inner.decode_float(input, output, fec)?;
+        unsafe {
+            (*result) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 依次解码 `input` 中首尾相接的数据包，PCM 样本连续写入 `output`
+///
+/// 每解码一个数据包就累加 `decoded_size`（每声道样本数），出错时其中是已经解码的样本数。
+fn decode_packets<T>(
+    decoder: &mut Decoder,
+    input: &[u8],
+    lengths: &[u32],
+    output: &mut [T],
+    decoded_size: &mut usize,
+    mut decode: impl FnMut(&mut opus_rs::Decoder, &[u8], &mut [T]) -> Result<usize, opus_rs::Error>,
+) -> Result<(), OpusError> {
+    *decoded_size = 0;
+    let total: usize = lengths.iter().map(|&length| length as usize).sum();
+    if total > input.len() {
+        return Err(OpusError::new(
+            ErrorCode::BadArg as c_int,
+            &format!(
+                "Packet lengths add up to {total} bytes, input has {}",
+                input.len()
+            ),
+        ));
+    }
+
+    let mut input_offset = 0;
+    let mut output_offset = 0;
+    for &length in lengths {
+        let packet = &input[input_offset..input_offset + length as usize];
+        input_offset += length as usize;
+        let output = &mut output[output_offset..];
+
+        // 先计算样本数并检查容量，放不下时返回 `BufferTooSmall`，已解码的数据包保持不变
+        let required = if packet.is_empty() {
+            // 长度为 0 的项表示数据包丢失，按上一个数据包的时长生成丢包隐藏音频
+            let duration = decoder.inner.get_last_packet_duration()? as usize;
+            if duration == 0 {
+                return Err(OpusError::new(
+                    ErrorCode::BadArg as c_int,
+                    "Lost packet has no previous packet to take its duration from",
+                ));
+            }
+            duration * decoder.channels
+        } else {
+            opus_rs::packet::get_nb_samples(packet, decoder.sample_rate)? * decoder.channels
+        };
+        if required > output.len() {
+            return Err(buffer_too_small_error(required, output.len()));
+        }
+        let samples = decode(&mut decoder.inner, packet, &mut output[..required])?;
+        output_offset += samples * decoder.channels;
+        *decoded_size += samples;
+    }
+
+    Ok(())
+}
+
+/// 一次解码多个 Opus 数据包（16 位整数输出）
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `input` - 指向首尾相接的多个数据包的指针，例如 `opus_ffi_encode_batch` 的输出
+/// * `input_size` - 输入数据的大小（字节数）
+/// * `lengths` - 每个数据包的长度（字节数），长度之和不能超过 `input_size`。长度为 0 的项表示丢失的数据包
+/// * `packet_count` - 数据包数量，即 `lengths` 的元素个数
+/// * `output` - 输出缓冲区，解码后的交错 PCM 样本连续存放
+/// * `output_size` - 输出缓冲区的容量（样本数，不是字节数）
+/// * `decoded_size` - 输出参数，所有数据包解码后的每声道样本数之和
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。输出缓冲区容量不足时返回 `OPUS_BUFFER_TOO_SMALL`
+///
+/// # 注意事项
+///
+/// * 结果与逐个调用 `opus_ffi_decode` 完全相同（包括 DTX 数据包），但只需跨越一次 FFI 边界
+/// * 出错时 `decoded_size` 为已经解码的每声道样本数，`output` 中这部分数据有效
+/// * 丢失的数据包按上一个数据包的时长生成丢包隐藏音频，与调用 `opus_ffi_decode_plc` 的结果相同；
+///   第一项（或 `opus_ffi_reset_decoder` 之后的第一项）长度为 0 时无法确定时长，返回 `OPUS_BAD_ARG`
+/// * 不支持前向纠错，丢包需要恢复时逐个调用 `opus_ffi_decode_with_frame_size`
+///
+/// # 示例
+///
+/// ```c
+/// int16_t pcm[320 * 50];
+/// size_t samples = 0;
+/// int res = opus_ffi_decode_batch(decoder, packets, packets_size, lengths, 50, pcm, 320 * 50, &samples, &error);
+/// ```
+#[export_name = "opus_ffi_decode_batch"]
+pub extern "C" fn decode_batch(
+    decoder: *mut Decoder,
+    input: *const u8,
+    input_size: usize,
+    lengths: *const u32,
+    packet_count: u32,
+    output: *mut i16,
+    output_size: usize,
+    decoded_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null()
+        || input.is_null()
+        || lengths.is_null()
+        || output.is_null()
+        || decoded_size.is_null()
+    {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size) };
+        let lengths = unsafe { std::slice::from_raw_parts(lengths, packet_count as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size) };
+        let decoded_size = unsafe { &mut *decoded_size };
+        decode_packets(
+            &mut decoder,
+            input,
+            lengths,
+            output,
+            decoded_size,
+            |decoder, packet, output| decoder.decode(packet, output, false),
+        )
+    })
+}
+
+/// 一次解码多个 Opus 数据包（32 位浮点数输出）
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `input` - 指向首尾相接的多个数据包的指针
+/// * `input_size` - 输入数据的大小（字节数）
+/// * `lengths` - 每个数据包的长度（字节数），长度之和不能超过 `input_size`。长度为 0 的项表示丢失的数据包
+/// * `packet_count` - 数据包数量，即 `lengths` 的元素个数
+/// * `output` - 输出缓冲区，解码后的交错浮点 PCM 样本连续存放
+/// * `output_size` - 输出缓冲区的容量（样本数，不是字节数）
+/// * `result` - 输出参数，所有数据包解码后的每声道样本数之和
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 此函数与 `opus_ffi_decode_batch` 功能相同（包括对丢失数据包的处理），但输出格式为浮点数
+#[export_name = "opus_ffi_decode_float_batch"]
+pub extern "C" fn decode_float_batch(
+    decoder: *mut Decoder,
+    input: *const u8,
+    input_size: usize,
+    lengths: *const u32,
+    packet_count: u32,
+    output: *mut f32,
+    output_size: usize,
+    result: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null()
+        || input.is_null()
+        || lengths.is_null()
+        || output.is_null()
+        || result.is_null()
+    {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size) };
+        let lengths = unsafe { std::slice::from_raw_parts(lengths, packet_count as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size) };
+        let result = unsafe { &mut *result };
+        decode_packets(
+            &mut decoder,
+            input,
+            lengths,
+            output,
+            result,
+            |decoder, packet, output| decoder.decode_float(packet, output, false),
+        )
+    })
+}
+
+/// 按指定帧大小解码 Opus 音频数据包为 PCM 样本（16 位整数）
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `input` - 指向 Opus 编码数据包的指针
+/// * `input_size` - 输入数据包的大小（字节数）
+/// * `output` - 输出缓冲区，用于存储解码后的 PCM 样本
+/// * `output_size` - 输出缓冲区的容量（样本数，不是字节数）
+/// * `frame_size` - 期望解码的每声道样本数。使用 FEC 恢复丢失的数据包时，必须与丢失数据包的帧大小一致
+/// * `fec` - 前向纠错（Forward Error Correction）标志
+/// * `decoded_size` - 输出参数，解码后实际产生的每声道样本数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。如果 `output_size` 小于 `frame_size × 声道数`，
+///   返回 `OPUS_BUFFER_TOO_SMALL`
+///
+/// # 注意事项
+///
+/// * 与 `opus_ffi_decode` 不同，此函数只向解码器提供 `frame_size × 声道数` 个样本的空间，而不是整个输出缓冲区
+///
+/// # 示例
+///
+/// ```c
+/// int16_t pcm_buffer[960];
+/// size_t decoded_samples = 0;
+/// OpusError error = {0, NULL};
+///
+/// // 使用下一个数据包的 FEC 数据恢复丢失的 20ms（16kHz 单声道 = 320 个样本）
+/// int res = opus_ffi_decode_with_frame_size(decoder, next_packet, next_size, pcm_buffer, 960, 320, true, &decoded_samples, &error);
+/// ```
+#[export_name = "opus_ffi_decode_with_frame_size"]
+pub extern "C" fn decode_with_frame_size(
+    decoder: *mut Decoder,
+    input: *const u8,
+    input_size: u32,
+    output: *mut i16,
+    output_size: u32,
+    frame_size: u32,
+    fec: bool,
+    decoded_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null()
+        || input.is_null()
+        || output.is_null()
+        || decoded_size.is_null()
+        || frame_size == 0
+    {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let samples = frame_size as usize * decoder.channels;
+        if samples > output_size as usize {
+            return Err(buffer_too_small_error(samples, output_size as usize));
+        }
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, samples) };
+        let size = decoder.inner.decode(input, output, fec)?;
+        unsafe {
+            (*decoded_size) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 按指定帧大小解码 Opus 音频数据包为 PCM 样本（32 位浮点数）
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `input` - 指向 Opus 编码数据包的指针
+/// * `input_size` - 输入数据包的大小（字节数）
+/// * `output` - 输出缓冲区，用于存储解码后的浮点 PCM 样本
+/// * `output_size` - 输出缓冲区的容量（样本数，不是字节数）
+/// * `frame_size` - 期望解码的每声道样本数
+/// * `fec` - 前向纠错（Forward Error Correction）标志
+/// * `result` - 输出参数，解码后实际产生的每声道样本数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。如果 `output_size` 小于 `frame_size × 声道数`，
+///   返回 `OPUS_BUFFER_TOO_SMALL`
+///
+/// # 注意事项
+///
+/// * 此函数与 `opus_ffi_decode_with_frame_size` 功能相同，但输出格式为浮点数
+///
+/// # 示例
+///
+/// ```c
+/// float pcm_buffer[960];
+/// size_t decoded_samples = 0;
+/// OpusError error = {0, NULL};
+///
+/// int res = opus_ffi_decode_float_with_frame_size(decoder, packet, packet_size, pcm_buffer, 960, 320, false, &decoded_samples, &error);
+/// ```
+#[export_name = "opus_ffi_decode_float_with_frame_size"]
+pub extern "C" fn decode_float_with_frame_size(
+    decoder: *mut Decoder,
+    input: *const u8,
+    input_size: u32,
+    output: *mut f32,
+    output_size: u32,
+    frame_size: u32,
+    fec: bool,
+    result: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null()
+        || input.is_null()
+        || output.is_null()
+        || result.is_null()
+        || frame_size == 0
+    {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let samples = frame_size as usize * decoder.channels;
+        if samples > output_size as usize {
+            return Err(buffer_too_small_error(samples, output_size as usize));
+        }
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, samples) };
+        let size = decoder.inner.decode_float(input, output, fec)?;
+        unsafe {
+            (*result) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 为丢失的数据包生成丢包隐藏（PLC）音频（16 位整数）
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `duration_us` - 需要隐藏的时长（微秒），必须是 2500（2.5ms）的整数倍且不超过 120000（120ms）。
+///   通常等于丢失数据包的帧时长，例如 20ms 对应 `20000`
+/// * `output` - 输出缓冲区，用于存储生成的 PCM 样本
+/// * `output_size` - 输出缓冲区的容量（样本数，不是字节数），至少为 `采样率 × 时长 × 声道数`，
+///   否则返回 `OPUS_BUFFER_TOO_SMALL`
+/// * `decoded_size` - 输出参数，实际产生的每声道样本数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 解码器会根据之前解码的音频外推出平滑的填充数据，避免直接补零造成的爆音
+/// * 连续调用时输出会逐渐衰减为静音
+///
+/// # 示例
+///
+/// ```c
+/// int16_t pcm_buffer[320]; // 20ms @ 16kHz, 单声道
+/// size_t decoded_samples = 0;
+/// OpusError error = {0, NULL};
+///
+/// int res = opus_ffi_decode_plc(decoder, 20000, pcm_buffer, 320, &decoded_samples, &error);
+/// ```
+#[export_name = "opus_ffi_decode_plc"]
+pub extern "C" fn decode_plc(
+    decoder: *mut Decoder,
+    duration_us: u32,
+    output: *mut i16,
+    output_size: u32,
+    decoded_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null() || output.is_null() || decoded_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let samples = decoder
+            .samples_for_duration(duration_us)
+            .ok_or_else(invalid_input_error)?;
+        if samples > output_size as usize {
+            return Err(buffer_too_small_error(samples, output_size as usize));
+        }
+        let output = unsafe { std::slice::from_raw_parts_mut(output, samples) };
+        let size = decoder.inner.decode(&[], output, false)?;
+        unsafe {
+            (*decoded_size) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 为丢失的数据包生成丢包隐藏（PLC）音频（32 位浮点数）
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `duration_us` - 需要隐藏的时长（微秒），必须是 2500（2.5ms）的整数倍且不超过 120000（120ms）
+/// * `output` - 输出缓冲区，用于存储生成的浮点 PCM 样本
+/// * `output_size` - 输出缓冲区的容量（样本数，不是字节数），至少为 `采样率 × 时长 × 声道数`，
+///   否则返回 `OPUS_BUFFER_TOO_SMALL`
+/// * `result` - 输出参数，实际产生的每声道样本数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 此函数与 `opus_ffi_decode_plc` 功能相同，但输出格式为浮点数
+///
+/// # 示例
+///
+/// ```c
+/// float pcm_buffer[320];
+/// size_t decoded_samples = 0;
+/// OpusError error = {0, NULL};
+///
+/// int res = opus_ffi_decode_plc_float(decoder, 20000, pcm_buffer, 320, &decoded_samples, &error);
+/// ```
+#[export_name = "opus_ffi_decode_plc_float"]
+pub extern "C" fn decode_plc_float(
+    decoder: *mut Decoder,
+    duration_us: u32,
+    output: *mut f32,
+    output_size: u32,
+    result: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null() || output.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let samples = decoder
+            .samples_for_duration(duration_us)
+            .ok_or_else(invalid_input_error)?;
+        if samples > output_size as usize {
+            return Err(buffer_too_small_error(samples, output_size as usize));
+        }
+        let output = unsafe { std::slice::from_raw_parts_mut(output, samples) };
+        let size = decoder.inner.decode_float(&[], output, false)?;
         unsafe {
             (*result) = size;
         }
@@ -189,40 +666,307 @@ pub extern "C" fn decode_float(
     })
 }
 
+/// 设置解码器的输出增益
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `gain` - 增益，Q8 格式的分贝值（dB × 256），范围 -32768 ~ 32767，默认为 0。
+///   例如 256 表示放大 1dB，-1536 表示衰减 6dB
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。超出范围时返回 `OPUS_BAD_ARG`
+///
+/// # 注意事项
+///
+/// * Ogg Opus 头部中的 `output_gain` 使用相同的格式，`opus_ffi_ogg_reader_open_*` 已经自动应用该增益。
+///   在此基础上叠加用户音量时，应传入两者之和
+///
+/// # 示例
+///
+/// ```c
+/// // 音量减半（约 -6dB）
+/// opus_ffi_set_decoder_gain(decoder, -1541, &error);
+/// ```
+#[export_name = "opus_ffi_set_decoder_gain"]
+pub extern "C" fn set_decoder_gain(
+    decoder: *mut Decoder,
+    gain: i32,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        decoder.inner.set_gain(gain)?;
+
+        Ok(())
+    })
+}
+
+/// 获取解码器的输出增益
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `result` - 输出参数，Q8 格式的分贝值（dB × 256）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_decoder_gain"]
+pub extern "C" fn get_decoder_gain(
+    decoder: *mut Decoder,
+    result: *mut i32,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let gain = decoder.inner.get_gain()?;
+        unsafe {
+            (*result) = gain;
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取最近一次解码（包括丢包隐藏）产生的每声道样本数
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `result` - 输出参数，每声道样本数，按解码器的采样率计算
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 数据包丢失时，可以按上一个数据包的时长调用 `opus_ffi_decode_plc`
+#[export_name = "opus_ffi_get_decoder_last_packet_duration"]
+pub extern "C" fn get_decoder_last_packet_duration(
+    decoder: *mut Decoder,
+    result: *mut u32,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let duration = decoder.inner.get_last_packet_duration()?;
+        unsafe {
+            (*result) = duration;
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取最近一次解码的帧的基音周期
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `result` - 输出参数，基音周期（48kHz 下的样本数），没有检测到基音时为 0
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 只有 SILK 模式和开启了基音预滤波的 CELT 帧才有基音信息，可用于简单的语音检测
+#[export_name = "opus_ffi_get_decoder_pitch"]
+pub extern "C" fn get_decoder_pitch(
+    decoder: *mut Decoder,
+    result: *mut i32,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let pitch = decoder.inner.get_pitch()?;
+        unsafe {
+            (*result) = pitch;
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取解码器最近一帧的熵编码器最终状态
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `result` - 输出参数，最终状态
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 解码某个数据包后的值与编码该数据包后 `opus_ffi_get_encoder_final_range` 的值相同，
+///   可以在测试中用来确认编解码结果逐位一致
+#[export_name = "opus_ffi_get_decoder_final_range"]
+pub extern "C" fn get_decoder_final_range(
+    decoder: *mut Decoder,
+    result: *mut u32,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let range = decoder.inner.get_final_range()?;
+        unsafe {
+            (*result) = range;
+        }
+
+        Ok(())
+    })
+}
+
+/// 重置解码器状态（对应 `OPUS_RESET_STATE`）
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_decoder` 创建的解码器实例
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 清除之前解码留下的内部状态（包括丢包隐藏使用的历史数据），之后的输出与新创建的解码器相同，
+///   适合在切换到新的通话或音频流时复用解码器
+///
+/// # 示例
+///
+/// ```c
+/// // 开始解码新的音频流
+/// opus_ffi_reset_decoder(decoder, &error);
+/// ```
+#[export_name = "opus_ffi_reset_decoder"]
+pub extern "C" fn reset_decoder(decoder: *mut Decoder, error: *mut OpusError) -> c_int {
+    if decoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        decoder.inner.reset_state()?;
+
+        Ok(())
+    })
+}
+
 /// 释放 Opus 解码器实例
 ///
 /// # 参数
 ///
-/// * `decoder` - 通过 `new_decoder` 创建的解码器指针
+/// * `decoder` - 通过 `opus_ffi_new_decoder` 创建的解码器指针
 ///
 /// # 安全性
 ///
 /// * 如果 `decoder` 为 `NULL`，函数不会执行任何操作
 /// * 释放后，`decoder` 指针将不再有效，不应再次使用
-/// * 每个通过 `new_decoder` 创建的解码器必须且只能调用一次此函数
+/// * 每个通过 `opus_ffi_new_decoder` 创建的解码器必须且只能调用一次此函数
+/// * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `decoder` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `decoder` 不是有效的解码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
 ///
 /// # 示例
 ///
 /// ```c
 /// Decoder *decoder = NULL;
 /// // ... 创建和使用解码器 ...
-/// free_decoder(decoder);
+/// opus_ffi_free_decoder(decoder);
 /// decoder = NULL; // 防止重复释放
 /// ```
-#[no_mangle]
-pub extern "C" fn free_decoder(decoder: *mut Decoder) {
-    unsafe {
-        if !decoder.is_null() {
-            let _ = Box::from_raw(decoder);
-        }
+#[export_name = "opus_ffi_free_decoder"]
+pub extern "C" fn free_decoder(decoder: *mut Decoder) -> c_int {
+    match handle::release(decoder) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{encoder::Encoder, packet::is_dtx_packet};
     use std::ptr;
 
+    #[test]
+    fn test_invalid_handles() {
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        let mut encoder = ptr::null_mut();
+        assert_eq!(
+            crate::encoder::new_encoder(1, 16000, 1, &mut encoder, ptr::null_mut()),
+            0
+        );
+
+        // 把编码器当作解码器使用
+        let wrong_type = encoder as *mut Decoder;
+        let packet = [0xf8u8];
+        let mut output = [0i16; 320];
+        let mut decoded = 0;
+        let res = decode(
+            wrong_type,
+            packet.as_ptr(),
+            1,
+            output.as_mut_ptr(),
+            320,
+            false,
+            &mut decoded,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_decoder(wrong_type), invalid_handle);
+        // 编码器没有被释放
+        assert_eq!(crate::encoder::free_encoder(encoder), 0);
+
+        // 从未创建的指针
+        let mut fake = [0u64; 8];
+        let fake = fake.as_mut_ptr() as *mut Decoder;
+        assert_eq!(free_decoder(fake), invalid_handle);
+        assert_eq!(free_decoder(ptr::null_mut()), 0);
+    }
+
     #[test]
     fn test_null_checks_decoder() {
         let expected_error = invalid_input(ptr::null_mut());
@@ -240,5 +984,425 @@ mod tests {
             ptr::null_mut(),
         );
         assert_eq!(res, expected_error);
+
+        let res = decode_plc(
+            ptr::null_mut(),
+            20000,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_decode_plc() {
+        let mut decoder: *mut Decoder = ptr::null_mut();
+        let res = new_decoder(1, 16000, &mut decoder, ptr::null_mut());
+        assert_eq!(res, 0);
+
+        let mut output = [0i16; 320];
+        let mut decoded_size: usize = 0;
+        let res = decode_plc(
+            decoder,
+            20000,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(decoded_size, 320);
+
+        // 时长不是 2.5ms 的整数倍
+        let res = decode_plc(
+            decoder,
+            1000,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, invalid_input(ptr::null_mut()));
+
+        // 输出缓冲区不足以容纳 40ms
+        let res = decode_plc(
+            decoder,
+            40000,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, ErrorCode::BufferTooSmall as c_int);
+
+        free_decoder(decoder);
+    }
+
+    #[test]
+    fn test_decode_with_frame_size_buffer_too_small() {
+        let mut decoder: *mut Decoder = ptr::null_mut();
+        let res = new_decoder(2, 48000, &mut decoder, ptr::null_mut());
+        assert_eq!(res, 0);
+
+        // 20ms @ 48kHz 立体声需要 960 × 2 个样本
+        let packet = [0xfcu8, 0xff, 0xfe];
+        let mut output = [0i16; 960];
+        let mut decoded_size: usize = 0;
+        let mut error = OpusError {
+            code: 0,
+            message: ptr::null_mut(),
+        };
+        let res = decode_with_frame_size(
+            decoder,
+            packet.as_ptr(),
+            packet.len() as u32,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            960,
+            false,
+            &mut decoded_size,
+            &mut error,
+        );
+        assert_eq!(res, ErrorCode::BufferTooSmall as c_int);
+        assert_eq!(error.code, res);
+        assert!(!error.message.is_null());
+
+        free_decoder(decoder);
+    }
+
+    #[test]
+    fn test_reset_decoder() {
+        let mut encoder =
+            Encoder::new(16000, opus_rs::Channels::Mono, opus_rs::Application::Voip).unwrap();
+        let packets: Vec<Vec<u8>> = (0..5)
+            .map(|frame| {
+                let pcm: Vec<i16> = (0..320)
+                    .map(|i| (((frame * 320 + i) as f32 * 0.05).sin() * 8000.0) as i16)
+                    .collect();
+                let mut packet = [0u8; 4000];
+                let size = encoder.encode(&pcm, &mut packet).unwrap();
+                packet[..size].to_vec()
+            })
+            .collect();
+
+        let decode_packets = |decoder: *mut Decoder| -> Vec<i16> {
+            let mut pcm = Vec::new();
+            for packet in &packets {
+                let mut output = [0i16; 320];
+                let mut size = 0;
+                let res = decode(
+                    decoder,
+                    packet.as_ptr(),
+                    packet.len() as u32,
+                    output.as_mut_ptr(),
+                    output.len() as u32,
+                    false,
+                    &mut size,
+                    ptr::null_mut(),
+                );
+                assert_eq!(res, 0);
+                pcm.extend_from_slice(&output[..size]);
+            }
+            pcm
+        };
+
+        let mut used: *mut Decoder = ptr::null_mut();
+        let mut fresh: *mut Decoder = ptr::null_mut();
+        assert_eq!(new_decoder(1, 16000, &mut used, ptr::null_mut()), 0);
+        assert_eq!(new_decoder(1, 16000, &mut fresh, ptr::null_mut()), 0);
+
+        decode_packets(used);
+        assert_eq!(reset_decoder(used, ptr::null_mut()), 0);
+        assert_eq!(decode_packets(used), decode_packets(fresh));
+
+        assert_eq!(
+            reset_decoder(ptr::null_mut(), ptr::null_mut()),
+            invalid_input(ptr::null_mut())
+        );
+
+        free_decoder(used);
+        free_decoder(fresh);
+    }
+
+    #[test]
+    fn test_decode_dtx() {
+        let mut encoder =
+            Encoder::new(16000, opus_rs::Channels::Mono, opus_rs::Application::Voip).unwrap();
+        encoder.set_dtx(true).unwrap();
+        let mut packet = [0u8; 4000];
+        let mut size = 0;
+        for _ in 0..30 {
+            size = encoder.encode(&[0i16; 320], &mut packet).unwrap();
+        }
+        assert!(is_dtx_packet(&packet[..size]));
+
+        let mut decoder: *mut Decoder = ptr::null_mut();
+        assert_eq!(new_decoder(1, 16000, &mut decoder, ptr::null_mut()), 0);
+
+        // DTX 数据包直接交给 opus_ffi_decode，输出缓冲区大于一帧时也只生成数据包时长（20ms）的样本
+        let mut output = [0i16; 5760];
+        let mut decoded_size = 0;
+        let res = decode(
+            decoder,
+            packet.as_ptr(),
+            size as u32,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            false,
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(decoded_size, 320);
+
+        free_decoder(decoder);
+    }
+
+    #[test]
+    fn test_decoder_ctl() {
+        let mut encoder =
+            Encoder::new(16000, opus_rs::Channels::Mono, opus_rs::Application::Voip).unwrap();
+        let pcm: Vec<i16> = (0..320)
+            .map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16)
+            .collect();
+        let mut packet = [0u8; 4000];
+        let size = encoder.encode(&pcm, &mut packet).unwrap();
+
+        let mut decoder: *mut Decoder = ptr::null_mut();
+        assert_eq!(new_decoder(1, 16000, &mut decoder, ptr::null_mut()), 0);
+
+        let mut gain = -1;
+        assert_eq!(get_decoder_gain(decoder, &mut gain, ptr::null_mut()), 0);
+        assert_eq!(gain, 0);
+        assert_eq!(set_decoder_gain(decoder, -1536, ptr::null_mut()), 0);
+        assert_eq!(get_decoder_gain(decoder, &mut gain, ptr::null_mut()), 0);
+        assert_eq!(gain, -1536);
+        assert_eq!(
+            set_decoder_gain(decoder, 40000, ptr::null_mut()),
+            ErrorCode::BadArg as c_int
+        );
+
+        let mut output = [0i16; 5760];
+        let mut decoded_size = 0;
+        let res = decode(
+            decoder,
+            packet.as_ptr(),
+            size as u32,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            false,
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+
+        let mut duration = 0;
+        assert_eq!(
+            get_decoder_last_packet_duration(decoder, &mut duration, ptr::null_mut()),
+            0
+        );
+        assert_eq!(duration, 320);
+
+        let mut pitch = -1;
+        assert_eq!(get_decoder_pitch(decoder, &mut pitch, ptr::null_mut()), 0);
+        assert!(pitch >= 0);
+
+        let mut range = 0;
+        assert_eq!(
+            get_decoder_final_range(decoder, &mut range, ptr::null_mut()),
+            0
+        );
+        assert_eq!(range, encoder.get_final_range().unwrap());
+
+        free_decoder(decoder);
+    }
+
+    #[test]
+    fn test_decode_batch() {
+        let mut encoder =
+            Encoder::new(16000, opus_rs::Channels::Mono, opus_rs::Application::Voip).unwrap();
+        encoder.set_dtx(true).unwrap();
+        // 先是正弦波，之后的静音部分会产生 DTX 数据包
+        let mut input = Vec::new();
+        let mut lengths = Vec::new();
+        for frame in 0..40 {
+            let pcm: Vec<i16> = (0..320)
+                .map(|i| match frame {
+                    0..10 => (((frame * 320 + i) as f32 * 0.05).sin() * 8000.0) as i16,
+                    _ => 0,
+                })
+                .collect();
+            let mut packet = [0u8; 4000];
+            let size = encoder.encode(&pcm, &mut packet).unwrap();
+            input.extend_from_slice(&packet[..size]);
+            lengths.push(size as u32);
+        }
+        assert!(lengths.iter().any(|&length| length <= 2));
+
+        let mut batch: *mut Decoder = ptr::null_mut();
+        let mut single: *mut Decoder = ptr::null_mut();
+        assert_eq!(new_decoder(1, 16000, &mut batch, ptr::null_mut()), 0);
+        assert_eq!(new_decoder(1, 16000, &mut single, ptr::null_mut()), 0);
+
+        let mut output = vec![0i16; 320 * lengths.len()];
+        let mut decoded_size = 0;
+        let res = decode_batch(
+            batch,
+            input.as_ptr(),
+            input.len(),
+            lengths.as_ptr(),
+            lengths.len() as u32,
+            output.as_mut_ptr(),
+            output.len(),
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(decoded_size, 320 * lengths.len());
+
+        // 与逐个解码的结果完全相同
+        let mut expected = Vec::new();
+        let mut offset = 0;
+        for &length in &lengths {
+            let packet = &input[offset..offset + length as usize];
+            offset += length as usize;
+            let mut pcm = [0i16; 5760];
+            let mut size = 0;
+            let res = decode(
+                single,
+                packet.as_ptr(),
+                packet.len() as u32,
+                pcm.as_mut_ptr(),
+                pcm.len() as u32,
+                false,
+                &mut size,
+                ptr::null_mut(),
+            );
+            assert_eq!(res, 0);
+            expected.extend_from_slice(&pcm[..size]);
+        }
+        assert_eq!(output, expected);
+
+        // 长度之和超出输入
+        let res = decode_batch(
+            batch,
+            input.as_ptr(),
+            input.len() - 1,
+            lengths.as_ptr(),
+            lengths.len() as u32,
+            output.as_mut_ptr(),
+            output.len(),
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, ErrorCode::BadArg as c_int);
+
+        // 输出缓冲区只够 3 帧
+        let res = decode_batch(
+            batch,
+            input.as_ptr(),
+            input.len(),
+            lengths.as_ptr(),
+            lengths.len() as u32,
+            output.as_mut_ptr(),
+            320 * 3,
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, ErrorCode::BufferTooSmall as c_int);
+        assert_eq!(decoded_size, 320 * 3);
+
+        free_decoder(batch);
+        free_decoder(single);
+    }
+
+    #[test]
+    fn test_decode_batch_lost_packet() {
+        let mut encoder =
+            Encoder::new(16000, opus_rs::Channels::Mono, opus_rs::Application::Audio).unwrap();
+        let mut packets = Vec::new();
+        for frame in 0..3 {
+            let pcm: Vec<i16> = (0..320)
+                .map(|i| (((frame * 320 + i) as f32 * 0.05).sin() * 8000.0) as i16)
+                .collect();
+            let mut packet = [0u8; 4000];
+            let size = encoder.encode(&pcm, &mut packet).unwrap();
+            packets.push(packet[..size].to_vec());
+        }
+        // 第二个数据包丢失
+        let input = [packets[0].as_slice(), packets[2].as_slice()].concat();
+        let lengths = [packets[0].len() as u32, 0, packets[2].len() as u32];
+
+        let mut batch: *mut Decoder = ptr::null_mut();
+        let mut single: *mut Decoder = ptr::null_mut();
+        assert_eq!(new_decoder(1, 16000, &mut batch, ptr::null_mut()), 0);
+        assert_eq!(new_decoder(1, 16000, &mut single, ptr::null_mut()), 0);
+
+        let mut output = [0i16; 320 * 3];
+        let mut decoded_size = 0;
+        let res = decode_batch(
+            batch,
+            input.as_ptr(),
+            input.len(),
+            lengths.as_ptr(),
+            lengths.len() as u32,
+            output.as_mut_ptr(),
+            output.len(),
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(decoded_size, 320 * 3);
+
+        // 与逐个解码并对丢失的数据包调用 PLC 的结果相同
+        let mut expected = [0i16; 320 * 3];
+        let mut size = 0;
+        for (index, chunk) in expected.chunks_mut(320).enumerate() {
+            let res = if index == 1 {
+                decode_plc(
+                    single,
+                    20000,
+                    chunk.as_mut_ptr(),
+                    320,
+                    &mut size,
+                    ptr::null_mut(),
+                )
+            } else {
+                decode(
+                    single,
+                    packets[index].as_ptr(),
+                    packets[index].len() as u32,
+                    chunk.as_mut_ptr(),
+                    320,
+                    false,
+                    &mut size,
+                    ptr::null_mut(),
+                )
+            };
+            assert_eq!(res, 0);
+            assert_eq!(size, 320);
+        }
+        assert_eq!(output, expected);
+
+        // 重置后第一项就丢失时无法确定时长
+        assert_eq!(reset_decoder(batch, ptr::null_mut()), 0);
+        let res = decode_batch(
+            batch,
+            input.as_ptr(),
+            input.len(),
+            lengths[1..].as_ptr(),
+            2,
+            output.as_mut_ptr(),
+            output.len(),
+            &mut decoded_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, ErrorCode::BadArg as c_int);
+        assert_eq!(decoded_size, 0);
+
+        free_decoder(batch);
+        free_decoder(single);
     }
 }
@@ -1,23 +1,323 @@
 use std::ffi::c_int;
 
-use opus_rs::{Application, Channels, Encoder};
+use audiopus_sys::{
+    OpusEncoder, OPUS_AUTO, OPUS_BITRATE_MAX, OPUS_GET_BANDWIDTH_REQUEST, OPUS_GET_BITRATE_REQUEST,
+    OPUS_GET_COMPLEXITY_REQUEST, OPUS_GET_DTX_REQUEST, OPUS_GET_FINAL_RANGE_REQUEST,
+    OPUS_GET_FORCE_CHANNELS_REQUEST, OPUS_GET_INBAND_FEC_REQUEST, OPUS_GET_LOOKAHEAD_REQUEST,
+    OPUS_GET_MAX_BANDWIDTH_REQUEST, OPUS_GET_PACKET_LOSS_PERC_REQUEST, OPUS_GET_SIGNAL_REQUEST,
+    OPUS_GET_VBR_CONSTRAINT_REQUEST, OPUS_GET_VBR_REQUEST, OPUS_RESET_STATE,
+    OPUS_SET_BANDWIDTH_REQUEST, OPUS_SET_BITRATE_REQUEST, OPUS_SET_COMPLEXITY_REQUEST,
+    OPUS_SET_DTX_REQUEST, OPUS_SET_FORCE_CHANNELS_REQUEST, OPUS_SET_INBAND_FEC_REQUEST,
+    OPUS_SET_MAX_BANDWIDTH_REQUEST, OPUS_SET_PACKET_LOSS_PERC_REQUEST, OPUS_SET_SIGNAL_REQUEST,
+    OPUS_SET_VBR_CONSTRAINT_REQUEST, OPUS_SET_VBR_REQUEST,
+};
+use opus_rs::{Application, Bitrate, Channels, ErrorCode};
 
 use crate::{
     error::OpusError,
-    utils::{ffi_exec, invalid_input},
+    handle,
+    packet::{is_dtx_packet, OpusBandwidth},
+    utils::{application_from, channels_from, check, ffi_exec, frame_size, invalid_input},
 };
 
+/// 比特率特殊值：由编码器根据采样率和声道数自动选择比特率（对应 `OPUS_AUTO`）
+pub const BITRATE_AUTO: i32 = -1000;
+/// 比特率特殊值：使用编码器允许的最大比特率（对应 `OPUS_BITRATE_MAX`）
+pub const BITRATE_MAX: i32 = -1;
+
+/// 信号类型：由编码器自动判断（对应 `OPUS_AUTO`）
+pub const SIGNAL_AUTO: i32 = -1000;
+/// 信号类型：语音（对应 `OPUS_SIGNAL_VOICE`）
+pub const SIGNAL_VOICE: i32 = 3001;
+/// 信号类型：音乐（对应 `OPUS_SIGNAL_MUSIC`）
+pub const SIGNAL_MUSIC: i32 = 3002;
+/// 带宽特殊值：由编码器根据比特率自动选择带宽（对应 `OPUS_AUTO`）
+pub const BANDWIDTH_AUTO: i32 = -1000;
+/// 强制声道数特殊值：不强制，由编码器自动选择（对应 `OPUS_AUTO`）
+pub const FORCE_CHANNELS_AUTO: i32 = -1000;
+
+/// 单个 Opus 数据包的最大长度
+const MAX_PACKET_SIZE: usize = 4000;
+
+/// 编码结果
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncodeResult {
+    /// 数据包的字节数
+    pub size: usize,
+    /// 是否为 DTX 数据包（静音期间不含音频数据的数据包），为 `true` 时不需要发送
+    pub dtx: bool,
+}
+
+impl EncodeResult {
+    fn new(packet: &[u8]) -> Self {
+        Self {
+            size: packet.len(),
+            dtx: is_dtx_packet(packet),
+        }
+    }
+}
+
+fn bad_arg(message: &str) -> OpusError {
+    OpusError::new(ErrorCode::BadArg as c_int, message)
+}
+
+/// 将 `OpusBandwidth` 的取值转换为带宽，`BANDWIDTH_AUTO` 转换为 `None`
+fn bandwidth_from(value: i32) -> Result<Option<OpusBandwidth>, OpusError> {
+    if value == BANDWIDTH_AUTO {
+        return Ok(None);
+    }
+
+    OpusBandwidth::from_raw(value)
+        .map(Some)
+        .ok_or_else(|| bad_arg(&format!("Invalid bandwidth: {value}")))
+}
+
+/// Opus 编码器不透明指针类型
+///
+/// 直接持有 libopus 的编码器状态，编码参数通过 `opus_encoder_ctl` 读写。
+pub struct Encoder {
+    ptr: *mut OpusEncoder,
+    channels: usize,
+}
+
+impl Drop for Encoder {
+    fn drop(&mut self) {
+        unsafe { audiopus_sys::opus_encoder_destroy(self.ptr) }
+    }
+}
+
+impl Encoder {
+    /// 创建编码器
+    pub fn new(
+        sample_rate: u32,
+        channels: Channels,
+        application: Application,
+    ) -> Result<Self, OpusError> {
+        let mut error = 0;
+        let ptr = unsafe {
+            audiopus_sys::opus_encoder_create(
+                sample_rate as i32,
+                channels as c_int,
+                application as c_int,
+                &mut error,
+            )
+        };
+        check("opus_encoder_create", error)?;
+
+        Ok(Self {
+            ptr,
+            channels: channels as usize,
+        })
+    }
+
+    /// 编码一帧交错的 16 位 PCM，返回数据包的字节数
+    pub fn encode(&mut self, input: &[i16], output: &mut [u8]) -> Result<usize, OpusError> {
+        let frame_size = frame_size(input.len(), self.channels)?;
+        let size = unsafe {
+            audiopus_sys::opus_encode(
+                self.ptr,
+                input.as_ptr(),
+                frame_size as c_int,
+                output.as_mut_ptr(),
+                output.len().min(i32::MAX as usize) as i32,
+            )
+        };
+
+        check("opus_encode", size)
+    }
+
+    /// 编码一帧交错的浮点 PCM，返回数据包的字节数
+    pub fn encode_float(&mut self, input: &[f32], output: &mut [u8]) -> Result<usize, OpusError> {
+        let frame_size = frame_size(input.len(), self.channels)?;
+        let size = unsafe {
+            audiopus_sys::opus_encode_float(
+                self.ptr,
+                input.as_ptr(),
+                frame_size as c_int,
+                output.as_mut_ptr(),
+                output.len().min(i32::MAX as usize) as i32,
+            )
+        };
+
+        check("opus_encode_float", size)
+    }
+
+    fn set(&mut self, request: c_int, value: i32) -> Result<(), OpusError> {
+        let code = unsafe { audiopus_sys::opus_encoder_ctl(self.ptr, request, value) };
+        check("opus_encoder_ctl", code).map(|_| ())
+    }
+
+    fn get(&mut self, request: c_int) -> Result<i32, OpusError> {
+        let mut value = 0i32;
+        let code = unsafe { audiopus_sys::opus_encoder_ctl(self.ptr, request, &mut value) };
+        check("opus_encoder_ctl", code)?;
+
+        Ok(value)
+    }
+
+    /// 重置编码器状态，编码参数保持不变
+    pub fn reset_state(&mut self) -> Result<(), OpusError> {
+        let code = unsafe { audiopus_sys::opus_encoder_ctl(self.ptr, OPUS_RESET_STATE) };
+        check("opus_encoder_ctl", code).map(|_| ())
+    }
+
+    /// 最近一帧的熵编码器最终状态
+    pub fn get_final_range(&mut self) -> Result<u32, OpusError> {
+        let mut range = 0u32;
+        let code = unsafe {
+            audiopus_sys::opus_encoder_ctl(self.ptr, OPUS_GET_FINAL_RANGE_REQUEST, &mut range)
+        };
+        check("opus_encoder_ctl", code)?;
+
+        Ok(range)
+    }
+
+    /// 编码器前瞻的样本数（每声道），Ogg Opus 的预跳过长度由此得出
+    pub fn get_lookahead(&mut self) -> Result<i32, OpusError> {
+        self.get(OPUS_GET_LOOKAHEAD_REQUEST)
+    }
+
+    /// 设置比特率
+    pub fn set_bitrate(&mut self, bitrate: Bitrate) -> Result<(), OpusError> {
+        let value = match bitrate {
+            Bitrate::Auto => OPUS_AUTO,
+            Bitrate::Max => OPUS_BITRATE_MAX,
+            Bitrate::Bits(bits) => bits,
+        };
+        self.set(OPUS_SET_BITRATE_REQUEST, value)
+    }
+
+    /// 当前的比特率设置
+    pub fn get_bitrate(&mut self) -> Result<Bitrate, OpusError> {
+        Ok(match self.get(OPUS_GET_BITRATE_REQUEST)? {
+            OPUS_AUTO => Bitrate::Auto,
+            OPUS_BITRATE_MAX => Bitrate::Max,
+            bits => Bitrate::Bits(bits),
+        })
+    }
+
+    /// 设置计算复杂度（0~10）
+    pub fn set_complexity(&mut self, complexity: i32) -> Result<(), OpusError> {
+        self.set(OPUS_SET_COMPLEXITY_REQUEST, complexity)
+    }
+
+    /// 当前的计算复杂度
+    pub fn get_complexity(&mut self) -> Result<i32, OpusError> {
+        self.get(OPUS_GET_COMPLEXITY_REQUEST)
+    }
+
+    /// 启用或关闭可变比特率
+    pub fn set_vbr(&mut self, vbr: bool) -> Result<(), OpusError> {
+        self.set(OPUS_SET_VBR_REQUEST, vbr as i32)
+    }
+
+    /// 是否启用可变比特率
+    pub fn get_vbr(&mut self) -> Result<bool, OpusError> {
+        Ok(self.get(OPUS_GET_VBR_REQUEST)? != 0)
+    }
+
+    /// 启用或关闭受约束的可变比特率
+    pub fn set_vbr_constraint(&mut self, constraint: bool) -> Result<(), OpusError> {
+        self.set(OPUS_SET_VBR_CONSTRAINT_REQUEST, constraint as i32)
+    }
+
+    /// 是否启用受约束的可变比特率
+    pub fn get_vbr_constraint(&mut self) -> Result<bool, OpusError> {
+        Ok(self.get(OPUS_GET_VBR_CONSTRAINT_REQUEST)? != 0)
+    }
+
+    /// 启用或关闭带内前向纠错
+    pub fn set_inband_fec(&mut self, fec: bool) -> Result<(), OpusError> {
+        self.set(OPUS_SET_INBAND_FEC_REQUEST, fec as i32)
+    }
+
+    /// 是否启用带内前向纠错
+    pub fn get_inband_fec(&mut self) -> Result<bool, OpusError> {
+        Ok(self.get(OPUS_GET_INBAND_FEC_REQUEST)? != 0)
+    }
+
+    /// 设置预期的丢包率（0~100）
+    pub fn set_packet_loss_perc(&mut self, percentage: i32) -> Result<(), OpusError> {
+        self.set(OPUS_SET_PACKET_LOSS_PERC_REQUEST, percentage)
+    }
+
+    /// 当前的预期丢包率
+    pub fn get_packet_loss_perc(&mut self) -> Result<i32, OpusError> {
+        self.get(OPUS_GET_PACKET_LOSS_PERC_REQUEST)
+    }
+
+    /// 启用或关闭不连续传输（DTX）
+    pub fn set_dtx(&mut self, enabled: bool) -> Result<(), OpusError> {
+        self.set(OPUS_SET_DTX_REQUEST, enabled as i32)
+    }
+
+    /// 是否启用不连续传输（DTX）
+    pub fn get_dtx(&mut self) -> Result<bool, OpusError> {
+        Ok(self.get(OPUS_GET_DTX_REQUEST)? != 0)
+    }
+
+    /// 设置信号类型提示
+    pub fn set_signal(&mut self, signal: i32) -> Result<(), OpusError> {
+        self.set(OPUS_SET_SIGNAL_REQUEST, signal)
+    }
+
+    /// 当前的信号类型提示
+    pub fn get_signal(&mut self) -> Result<i32, OpusError> {
+        self.get(OPUS_GET_SIGNAL_REQUEST)
+    }
+
+    /// 设置使用的带宽，`None` 表示自动选择
+    pub fn set_bandwidth(&mut self, bandwidth: Option<OpusBandwidth>) -> Result<(), OpusError> {
+        let value = bandwidth.map_or(OPUS_AUTO, |bandwidth| bandwidth as i32);
+        self.set(OPUS_SET_BANDWIDTH_REQUEST, value)
+    }
+
+    /// 最近一帧实际使用的带宽
+    pub fn get_bandwidth(&mut self) -> Result<OpusBandwidth, OpusError> {
+        let value = self.get(OPUS_GET_BANDWIDTH_REQUEST)?;
+        Ok(OpusBandwidth::from_raw(value).unwrap_or(OpusBandwidth::Fullband))
+    }
+
+    /// 设置允许使用的最大带宽
+    pub fn set_max_bandwidth(&mut self, bandwidth: OpusBandwidth) -> Result<(), OpusError> {
+        self.set(OPUS_SET_MAX_BANDWIDTH_REQUEST, bandwidth as i32)
+    }
+
+    /// 允许使用的最大带宽
+    pub fn get_max_bandwidth(&mut self) -> Result<OpusBandwidth, OpusError> {
+        let value = self.get(OPUS_GET_MAX_BANDWIDTH_REQUEST)?;
+        Ok(OpusBandwidth::from_raw(value).unwrap_or(OpusBandwidth::Fullband))
+    }
+
+    /// 强制输出单声道或立体声数据包，`None` 表示不强制
+    pub fn set_force_channels(&mut self, channels: Option<Channels>) -> Result<(), OpusError> {
+        let value = channels.map_or(OPUS_AUTO, |channels| channels as i32);
+        self.set(OPUS_SET_FORCE_CHANNELS_REQUEST, value)
+    }
+
+    /// 强制使用的声道数，不强制时为 `None`
+    pub fn get_force_channels(&mut self) -> Result<Option<Channels>, OpusError> {
+        Ok(match self.get(OPUS_GET_FORCE_CHANNELS_REQUEST)? {
+            1 => Some(Channels::Mono),
+            2 => Some(Channels::Stereo),
+            _ => None,
+        })
+    }
+}
+
 /// 创建新的 Opus 编码器
 ///
 /// # 参数
 ///
-/// * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo），其他值默认为单声道
+/// * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+///   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
 /// * `sample_rate` - 采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
 /// * `application` - 应用模式：
 ///   - `1` = Voip（语音通话，低延迟优化）
 ///   - `2` = Audio（音频流，高质量优化）
 ///   - `3` = LowDelay（低延迟模式）
-///   - 其他值默认为 Voip
+///   - 其他值返回错误（宽松模式下默认为 Voip）
 /// * `result` - 输出参数，用于接收创建的编码器指针
 /// * `error` - 可选的错误输出参数
 ///
@@ -25,23 +325,24 @@ use crate::{
 ///
 /// * `0` - 成功
 /// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///   声道数或应用模式不受支持时返回 `OPUS_ERROR_CODE_UNSUPPORTED`
 ///
 /// # 安全性
 ///
-/// 调用者负责在不再需要时调用 `free_encoder` 释放返回的编码器。
+/// 调用者负责在不再需要时调用 `opus_ffi_free_encoder` 释放返回的编码器。
 ///
 /// # 示例
 ///
 /// ```c
 /// Encoder *encoder = NULL;
 /// OpusError error = {0, NULL};
-/// int res = new_encoder(1, 16000, 1, &encoder, &error);
+/// int res = opus_ffi_new_encoder(1, 16000, 1, &encoder, &error);
 /// if (res == 0) {
 ///     // 使用编码器...
-///     free_encoder(encoder);
+///     opus_ffi_free_encoder(encoder);
 /// }
 /// ```
-#[no_mangle]
+#[export_name = "opus_ffi_new_encoder"]
 pub extern "C" fn new_encoder(
     channels: u32,
     sample_rate: u32,
@@ -53,22 +354,12 @@ pub extern "C" fn new_encoder(
         return invalid_input(error);
     }
 
-    let channels = match channels {
-        1 => Channels::Mono,
-        2 => Channels::Stereo,
-        _ => Channels::Mono,
-    };
-    let mode = match application {
-        1 => Application::Voip,
-        2 => Application::Audio,
-        3 => Application::LowDelay,
-        _ => Application::Voip,
-    };
-
     ffi_exec(error, || {
+        let channels = channels_from(channels)?;
+        let mode = application_from(application)?;
         let encoder = Encoder::new(sample_rate, channels, mode)?;
         unsafe {
-            *result = Box::into_raw(Box::new(encoder));
+            *result = handle::into_handle(encoder);
         }
 
         Ok(())
@@ -79,7 +370,7 @@ pub extern "C" fn new_encoder(
 ///
 /// # 参数
 ///
-/// * `encoder` - 之前通过 `new_encoder` 创建的编码器实例
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
 /// * `input` - 指向 PCM 样本数据的指针（16 位有符号整数）
 /// * `input_size` - 输入样本的数量（不是字节数）。对于单声道，这是样本数；对于立体声，这是样本对的数量
 /// * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
@@ -107,12 +398,12 @@ pub extern "C" fn new_encoder(
 /// size_t encoded_size = 0;
 /// OpusError error = {0, NULL};
 ///
-/// int res = encode(encoder, pcm_samples, 320, opus_packet, 4000, &encoded_size, &error);
+/// int res = opus_ffi_encode(encoder, pcm_samples, 320, opus_packet, 4000, &encoded_size, &error);
 /// if (res == 0) {
 ///     // 使用编码后的数据包...
 /// }
 /// ```
-#[no_mangle]
+#[export_name = "opus_ffi_encode"]
 pub extern "C" fn encode(
     encoder: *mut Encoder,
     input: *const i16,
@@ -127,7 +418,7 @@ pub extern "C" fn encode(
     }
 
     ffi_exec(error, || {
-        let encoder = unsafe { &mut *encoder };
+        let mut encoder = handle::get_mut(encoder)?;
         let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
         let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
         let size = encoder.encode(input, output)?;
@@ -143,7 +434,7 @@ pub extern "C" fn encode(
 ///
 /// # 参数
 ///
-/// * `encoder` - 之前通过 `new_encoder` 创建的编码器实例
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
 /// * `input` - 指向 PCM 样本数据的指针（32 位浮点数）
 /// * `input_size` - 输入样本的数量（不是字节数）
 /// * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
@@ -159,7 +450,7 @@ pub extern "C" fn encode(
 /// # 注意事项
 ///
 /// * 浮点样本的范围通常在 [-1.0, 1.0] 之间
-/// * 此函数与 `encode` 功能相同，但输入格式为浮点数，适合需要高精度处理的场景
+/// * 此函数与 `opus_ffi_encode` 功能相同，但输入格式为浮点数，适合需要高精度处理的场景
 /// * `input_size` 是样本数，不是字节数
 ///
 /// # 示例
@@ -170,9 +461,9 @@ pub extern "C" fn encode(
 /// size_t encoded_size = 0;
 /// OpusError error = {0, NULL};
 ///
-/// int res = encode_float(encoder, pcm_samples, 320, opus_packet, 4000, &encoded_size, &error);
+/// int res = opus_ffi_encode_float(encoder, pcm_samples, 320, opus_packet, 4000, &encoded_size, &error);
 /// ```
-#[no_mangle]
+#[export_name = "opus_ffi_encode_float"]
 pub extern "C" fn encode_float(
     encoder: *mut Encoder,
     input: *const f32,
@@ -187,7 +478,7 @@ pub extern "C" fn encode_float(
     }
 
     ffi_exec(error, || {
-        let encoder = unsafe { &mut *encoder };
+        let mut encoder = handle::get_mut(encoder)?;
         let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
         let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
         let size = encoder.encode_float(input, output)?;
@@ -199,56 +490,1529 @@ pub extern "C" fn encode_float(
     })
 }
 
-/// 释放 Opus 编码器实例
+/// 将 PCM 样本编码为 Opus 数据包，同时报告是否为 DTX 数据包（16 位整数输入）
 ///
 /// # 参数
 ///
-/// * `encoder` - 通过 `new_encoder` 创建的编码器指针
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `input` - 指向 PCM 样本数据的指针（16 位有符号整数）
+/// * `input_size` - 输入样本的数量（不是字节数）
+/// * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
+/// * `output_size` - 输出缓冲区的容量（字节数）
+/// * `result` - 输出参数，数据包的字节数和 DTX 标志
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
 ///
-/// # 安全性
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
 ///
-/// * 如果 `encoder` 为 `NULL`，函数不会执行任何操作
-/// * 释放后，`encoder` 指针将不再有效，不应再次使用
-/// * 每个通过 `new_encoder` 创建的编码器必须且只能调用一次此函数
+/// # 注意事项
+///
+/// * 与 `opus_ffi_encode` 功能相同，只是多了 DTX 标志
+/// * 开启 DTX（见 `opus_ffi_set_encoder_dtx`）后，静音期间的数据包 `dtx` 为 `true`，可以不发送；
+///   编码器仍会每隔约 400ms 输出一个舒适噪声更新包，这些数据包 `dtx` 为 `false`，需要正常发送
 ///
 /// # 示例
 ///
 /// ```c
-/// Encoder *encoder = NULL;
-/// // ... 创建和使用编码器 ...
-/// free_encoder(encoder);
-/// encoder = NULL; // 防止重复释放
+/// EncodeResult encoded;
+/// int res = opus_ffi_encode_with_result(encoder, pcm, 320, packet, 4000, &encoded, &error);
+/// if (res == 0 && !encoded.dtx) {
+///     send_packet(packet, encoded.size);
+/// }
 /// ```
-#[no_mangle]
-pub extern "C" fn free_encoder(encoder: *mut Encoder) {
-    unsafe {
-        if !encoder.is_null() {
-            let _ = Box::from_raw(encoder);
+#[export_name = "opus_ffi_encode_with_result"]
+pub extern "C" fn encode_with_result(
+    encoder: *mut Encoder,
+    input: *const i16,
+    input_size: u32,
+    output: *mut u8,
+    output_size: u32,
+    result: *mut EncodeResult,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || input.is_null() || output.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
+        let size = encoder.encode(input, output)?;
+        unsafe {
+            (*result) = EncodeResult::new(&output[..size]);
+        }
+
+        Ok(())
+    })
+}
+
+/// 将 PCM 样本编码为 Opus 数据包，同时报告是否为 DTX 数据包（32 位浮点数输入）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `input` - 指向 PCM 样本数据的指针（32 位浮点数）
+/// * `input_size` - 输入样本的数量（不是字节数）
+/// * `output` - 输出缓冲区，用于存储编码后的 Opus 数据包
+/// * `output_size` - 输出缓冲区的容量（字节数）
+/// * `result` - 输出参数，数据包的字节数和 DTX 标志
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 此函数与 `opus_ffi_encode_with_result` 功能相同，但输入格式为浮点数
+#[export_name = "opus_ffi_encode_float_with_result"]
+pub extern "C" fn encode_float_with_result(
+    encoder: *mut Encoder,
+    input: *const f32,
+    input_size: u32,
+    output: *mut u8,
+    output_size: u32,
+    result: *mut EncodeResult,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || input.is_null() || output.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
+        let size = encoder.encode_float(input, output)?;
+        unsafe {
+            (*result) = EncodeResult::new(&output[..size]);
+        }
+
+        Ok(())
+    })
+}
+
+/// 把 `input` 按每帧 `frame_samples` 个样本依次编码，数据包首尾相接写入 `output`，长度写入 `lengths`
+///
+/// 每写完一个数据包就更新 `frame_count`，出错时其中是已经写入的数据包数量。
+/// 剩余容量不足一个最大数据包时不再编码，因容量不足出错时编码器恰好处理了 `frame_count` 帧。
+fn encode_frames<T>(
+    input: &[T],
+    frame_samples: usize,
+    output: &mut [u8],
+    lengths: &mut [u32],
+    frame_count: &mut usize,
+    mut encode: impl FnMut(&[T], &mut [u8]) -> Result<usize, OpusError>,
+) -> Result<(), OpusError> {
+    *frame_count = 0;
+    if frame_samples == 0 || !input.len().is_multiple_of(frame_samples) {
+        return Err(bad_arg("Input size must be a multiple of the frame size"));
+    }
+    let frames = input.len() / frame_samples;
+    if frames > lengths.len() {
+        return Err(OpusError::new(
+            ErrorCode::BufferTooSmall as c_int,
+            &format!(
+                "Lengths array too small: {frames} entries required, {} available",
+                lengths.len()
+            ),
+        ));
+    }
+
+    let mut offset = 0;
+    for (index, frame) in input.chunks_exact(frame_samples).enumerate() {
+        // 编码前检查，编码后才发现放不下时编码器状态已经前进，调用者无法从 `frame_count` 继续
+        if output.len() - offset < MAX_PACKET_SIZE {
+            return Err(OpusError::new(
+                ErrorCode::BufferTooSmall as c_int,
+                &format!(
+                    "Output buffer too small: {} bytes required, {} available",
+                    offset + MAX_PACKET_SIZE,
+                    output.len()
+                ),
+            ));
         }
+        let size = encode(frame, &mut output[offset..offset + MAX_PACKET_SIZE])?;
+        lengths[index] = size as u32;
+        offset += size;
+        *frame_count = index + 1;
     }
+
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::utils::invalid_input;
-    use std::ptr;
+/// 一次编码多帧 PCM 样本（16 位整数输入）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `input` - 指向连续多帧 PCM 样本的指针（16 位有符号整数）
+/// * `input_size` - 输入样本的总数（所有声道），必须是 `frame_size` 的整数倍
+/// * `frame_size` - 每帧的样本总数（所有声道），与 `opus_ffi_encode` 的 `input_size` 含义相同，
+///   例如 16kHz 单声道 20ms 为 320
+/// * `output` - 输出缓冲区，各数据包首尾相接存放
+/// * `output_size` - 输出缓冲区的容量（字节数）
+/// * `lengths` - 输出数组，依次接收每个数据包的长度（字节数）。第 i 个数据包的偏移量是前 i 个长度之和
+/// * `lengths_size` - `lengths` 数组的容量，不能小于帧数 `input_size / frame_size`
+/// * `frame_count` - 输出参数，写入的数据包数量
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。`input_size` 不是 `frame_size` 的整数倍时返回 `OPUS_BAD_ARG`，
+///   `lengths` 或 `output` 容量不足时返回 `OPUS_BUFFER_TOO_SMALL`
+///
+/// # 注意事项
+///
+/// * 结果与逐帧调用 `opus_ffi_encode` 完全相同，但只需跨越一次 FFI 边界
+/// * 编码每一帧前 `output` 至少要剩余 4000 字节（单个数据包的最大长度），否则返回 `OPUS_BUFFER_TOO_SMALL` 且不编码该帧，
+///   因此除了数据包本身还需多预留 4000 字节。出错时 `frame_count` 为已写入的数据包数量，后续帧从第 `frame_count` 帧继续即可
+///
+/// # 示例
+///
+/// ```c
+/// // 50 帧 20ms @ 16kHz 单声道
+/// int16_t pcm[320 * 50];
+/// uint8_t packets[50 * 400 + 4000];
+/// uint32_t lengths[50];
+/// size_t count = 0;
+/// int res = opus_ffi_encode_batch(encoder, pcm, 320 * 50, 320, packets, sizeof(packets), lengths, 50, &count, &error);
+/// ```
+#[export_name = "opus_ffi_encode_batch"]
+pub extern "C" fn encode_batch(
+    encoder: *mut Encoder,
+    input: *const i16,
+    input_size: u32,
+    frame_size: u32,
+    output: *mut u8,
+    output_size: usize,
+    lengths: *mut u32,
+    lengths_size: u32,
+    frame_count: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null()
+        || input.is_null()
+        || output.is_null()
+        || lengths.is_null()
+        || frame_count.is_null()
+    {
+        return invalid_input(error);
+    }
 
-    #[test]
-    fn test_null_checks_encoder() {
-        let expected_error = invalid_input(ptr::null_mut());
-        let res = new_encoder(1, 48000, 1, ptr::null_mut(), ptr::null_mut());
-        assert_eq!(res, expected_error);
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size) };
+        let lengths = unsafe { std::slice::from_raw_parts_mut(lengths, lengths_size as usize) };
+        let frame_count = unsafe { &mut *frame_count };
+        encode_frames(
+            input,
+            frame_size as usize,
+            output,
+            lengths,
+            frame_count,
+            |frame, packet| encoder.encode(frame, packet),
+        )
+    })
+}
 
-        let res = encode(
-            ptr::null_mut(),
-            ptr::null(),
-            0,
-            ptr::null_mut(),
-            0,
-            ptr::null_mut(),
-            ptr::null_mut(),
-        );
-        assert_eq!(res, expected_error);
+/// 一次编码多帧 PCM 样本（32 位浮点数输入）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `input` - 指向连续多帧 PCM 样本的指针（32 位浮点数）
+/// * `input_size` - 输入样本的总数（所有声道），必须是 `frame_size` 的整数倍
+/// * `frame_size` - 每帧的样本总数（所有声道）
+/// * `output` - 输出缓冲区，各数据包首尾相接存放
+/// * `output_size` - 输出缓冲区的容量（字节数）
+/// * `lengths` - 输出数组，依次接收每个数据包的长度（字节数）
+/// * `lengths_size` - `lengths` 数组的容量，不能小于帧数 `input_size / frame_size`
+/// * `frame_count` - 输出参数，写入的数据包数量
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 此函数与 `opus_ffi_encode_batch` 功能相同，但输入格式为浮点数
+#[export_name = "opus_ffi_encode_float_batch"]
+pub extern "C" fn encode_float_batch(
+    encoder: *mut Encoder,
+    input: *const f32,
+    input_size: u32,
+    frame_size: u32,
+    output: *mut u8,
+    output_size: usize,
+    lengths: *mut u32,
+    lengths_size: u32,
+    frame_count: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null()
+        || input.is_null()
+        || output.is_null()
+        || lengths.is_null()
+        || frame_count.is_null()
+    {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size) };
+        let lengths = unsafe { std::slice::from_raw_parts_mut(lengths, lengths_size as usize) };
+        let frame_count = unsafe { &mut *frame_count };
+        encode_frames(
+            input,
+            frame_size as usize,
+            output,
+            lengths,
+            frame_count,
+            |frame, packet| encoder.encode_float(frame, packet),
+        )
+    })
+}
+
+/// 设置编码器的目标比特率
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `bitrate` - 目标比特率（bit/s），有效范围 500 ~ 512000。也可以传入特殊值：
+///   - `BITRATE_AUTO`（-1000）= 由编码器自动选择
+///   - `BITRATE_MAX`（-1）= 使用允许的最大比特率
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 示例
+///
+/// ```c
+/// OpusError error = {0, NULL};
+/// int res = opus_ffi_set_encoder_bitrate(encoder, 24000, &error);
+/// ```
+#[export_name = "opus_ffi_set_encoder_bitrate"]
+pub extern "C" fn set_encoder_bitrate(
+    encoder: *mut Encoder,
+    bitrate: i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    let bitrate = match bitrate {
+        BITRATE_AUTO => Bitrate::Auto,
+        BITRATE_MAX => Bitrate::Max,
+        bits => Bitrate::Bits(bits),
+    };
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_bitrate(bitrate)?;
+
+        Ok(())
+    })
+}
+
+/// 获取编码器当前的比特率
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，当前比特率（bit/s）。编码器总是返回实际生效的比特率，
+///   即使之前设置的是 `BITRATE_AUTO`
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_bitrate"]
+pub extern "C" fn get_encoder_bitrate(
+    encoder: *mut Encoder,
+    result: *mut i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let bitrate = match encoder.get_bitrate()? {
+            Bitrate::Auto => BITRATE_AUTO,
+            Bitrate::Max => BITRATE_MAX,
+            Bitrate::Bits(bits) => bits,
+        };
+        unsafe {
+            (*result) = bitrate;
+        }
+
+        Ok(())
+    })
+}
+
+/// 设置编码器的计算复杂度
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `complexity` - 复杂度，范围 0 ~ 10。数值越大音质越好，但 CPU 占用越高
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。超出范围时返回 `OPUS_BAD_ARG`
+#[export_name = "opus_ffi_set_encoder_complexity"]
+pub extern "C" fn set_encoder_complexity(
+    encoder: *mut Encoder,
+    complexity: i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_complexity(complexity)?;
+
+        Ok(())
+    })
+}
+
+/// 获取编码器当前的计算复杂度
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，当前复杂度（0 ~ 10）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_complexity"]
+pub extern "C" fn get_encoder_complexity(
+    encoder: *mut Encoder,
+    result: *mut i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let complexity = encoder.get_complexity()?;
+        unsafe {
+            (*result) = complexity;
+        }
+
+        Ok(())
+    })
+}
+
+/// 启用或禁用可变比特率（VBR）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `vbr` - `true` 表示使用 VBR（默认），`false` 表示使用固定比特率（CBR）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_set_encoder_vbr"]
+pub extern "C" fn set_encoder_vbr(
+    encoder: *mut Encoder,
+    vbr: bool,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_vbr(vbr)?;
+
+        Ok(())
+    })
+}
+
+/// 查询编码器是否启用了可变比特率（VBR）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，`true` 表示 VBR，`false` 表示 CBR
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_vbr"]
+pub extern "C" fn get_encoder_vbr(
+    encoder: *mut Encoder,
+    result: *mut bool,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let vbr = encoder.get_vbr()?;
+        unsafe {
+            (*result) = vbr;
+        }
+
+        Ok(())
+    })
+}
+
+/// 启用或禁用受约束的可变比特率（Constrained VBR）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `constraint` - `true` 表示受约束 VBR（默认），`false` 表示不受约束 VBR
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 仅在启用 VBR 时生效。受约束 VBR 的码率波动更小，适合对带宽敏感的实时传输
+#[export_name = "opus_ffi_set_encoder_vbr_constraint"]
+pub extern "C" fn set_encoder_vbr_constraint(
+    encoder: *mut Encoder,
+    constraint: bool,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_vbr_constraint(constraint)?;
+
+        Ok(())
+    })
+}
+
+/// 查询编码器是否启用了受约束的可变比特率（Constrained VBR）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，`true` 表示受约束 VBR
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_vbr_constraint"]
+pub extern "C" fn get_encoder_vbr_constraint(
+    encoder: *mut Encoder,
+    result: *mut bool,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let constraint = encoder.get_vbr_constraint()?;
+        unsafe {
+            (*result) = constraint;
+        }
+
+        Ok(())
+    })
+}
+
+/// 启用或禁用带内前向纠错（In-band FEC）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `fec` - `true` 表示启用 FEC，`false` 表示禁用（默认）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 启用后，编码器会在数据包中携带前一帧的低码率冗余数据，接收端丢包时可通过 `opus_ffi_decode` 的 `fec` 参数恢复
+/// * FEC 仅在 SILK/混合模式下生效，并且需要配合 `opus_ffi_set_encoder_packet_loss_perc` 设置非零的预期丢包率
+///
+/// # 示例
+///
+/// ```c
+/// OpusError error = {0, NULL};
+/// opus_ffi_set_encoder_inband_fec(encoder, true, &error);
+/// opus_ffi_set_encoder_packet_loss_perc(encoder, 10, &error);
+/// ```
+#[export_name = "opus_ffi_set_encoder_inband_fec"]
+pub extern "C" fn set_encoder_inband_fec(
+    encoder: *mut Encoder,
+    fec: bool,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_inband_fec(fec)?;
+
+        Ok(())
+    })
+}
+
+/// 查询编码器是否启用了带内前向纠错（In-band FEC）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，`true` 表示已启用 FEC
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_inband_fec"]
+pub extern "C" fn get_encoder_inband_fec(
+    encoder: *mut Encoder,
+    result: *mut bool,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let fec = encoder.get_inband_fec()?;
+        unsafe {
+            (*result) = fec;
+        }
+
+        Ok(())
+    })
+}
+
+/// 设置编码器的预期丢包率
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `percentage` - 预期丢包率（百分比），范围 0 ~ 100。默认为 0
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。超出范围时返回 `OPUS_BAD_ARG`
+///
+/// # 注意事项
+///
+/// * 丢包率越高，编码器分配给 FEC 冗余数据的码率越多
+#[export_name = "opus_ffi_set_encoder_packet_loss_perc"]
+pub extern "C" fn set_encoder_packet_loss_perc(
+    encoder: *mut Encoder,
+    percentage: i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_packet_loss_perc(percentage)?;
+
+        Ok(())
+    })
+}
+
+/// 获取编码器当前的预期丢包率
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，预期丢包率（百分比）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_packet_loss_perc"]
+pub extern "C" fn get_encoder_packet_loss_perc(
+    encoder: *mut Encoder,
+    result: *mut i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let percentage = encoder.get_packet_loss_perc()?;
+        unsafe {
+            (*result) = percentage;
+        }
+
+        Ok(())
+    })
+}
+
+/// 开启或关闭 DTX（不连续传输）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `enabled` - `true` 表示开启（默认关闭）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 开启后，编码器在静音期间输出不含音频数据的 DTX 数据包，可以通过 `opus_ffi_encode_with_result`
+///   的 `dtx` 标志识别并跳过发送，以节省带宽
+#[export_name = "opus_ffi_set_encoder_dtx"]
+pub extern "C" fn set_encoder_dtx(
+    encoder: *mut Encoder,
+    enabled: bool,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_dtx(enabled)?;
+
+        Ok(())
+    })
+}
+
+/// 获取编码器是否开启了 DTX（不连续传输）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，是否开启 DTX
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_dtx"]
+pub extern "C" fn get_encoder_dtx(
+    encoder: *mut Encoder,
+    result: *mut bool,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let enabled = encoder.get_dtx()?;
+        unsafe {
+            (*result) = enabled;
+        }
+
+        Ok(())
+    })
+}
+
+/// 设置编码器的信号类型提示
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `signal` - 信号类型：
+///   - `SIGNAL_AUTO`（-1000）= 由编码器自动判断（默认）
+///   - `SIGNAL_VOICE`（3001）= 语音，倾向于使用 SILK 模式
+///   - `SIGNAL_MUSIC`（3002）= 音乐，倾向于使用 CELT 模式
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。其他取值返回 `OPUS_BAD_ARG`
+#[export_name = "opus_ffi_set_encoder_signal"]
+pub extern "C" fn set_encoder_signal(
+    encoder: *mut Encoder,
+    signal: i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        if !matches!(signal, SIGNAL_AUTO | SIGNAL_VOICE | SIGNAL_MUSIC) {
+            return Err(bad_arg(&format!("Invalid signal: {signal}")));
+        }
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_signal(signal)?;
+
+        Ok(())
+    })
+}
+
+/// 获取编码器当前的信号类型提示
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，`SIGNAL_AUTO`、`SIGNAL_VOICE` 或 `SIGNAL_MUSIC`
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_signal"]
+pub extern "C" fn get_encoder_signal(
+    encoder: *mut Encoder,
+    result: *mut i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let signal = encoder.get_signal()?;
+        unsafe {
+            (*result) = signal;
+        }
+
+        Ok(())
+    })
+}
+
+/// 设置编码器使用的音频带宽
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `bandwidth` - `OpusBandwidth` 的取值（1101 窄带 ~ 1105 全带），
+///   或 `BANDWIDTH_AUTO`（-1000）由编码器根据比特率自动选择（默认）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。其他取值返回 `OPUS_BAD_ARG`
+///
+/// # 注意事项
+///
+/// * 带宽仍受 `opus_ffi_set_encoder_max_bandwidth` 和采样率的限制
+/// * libopus 不提供读取此设置的接口，实际使用的带宽通过 `opus_ffi_get_encoder_final_bandwidth` 获取
+///
+/// # 示例
+///
+/// ```c
+/// // 语音留言只保留窄带，节省空间
+/// opus_ffi_set_encoder_bandwidth(encoder, Narrowband, &error);
+/// ```
+#[export_name = "opus_ffi_set_encoder_bandwidth"]
+pub extern "C" fn set_encoder_bandwidth(
+    encoder: *mut Encoder,
+    bandwidth: i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let bandwidth = bandwidth_from(bandwidth)?;
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_bandwidth(bandwidth)?;
+
+        Ok(())
+    })
+}
+
+/// 获取编码器最近一帧实际使用的音频带宽
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，实际使用的带宽
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 带宽由编码器根据比特率、带宽设置和输入信号决定，编码第一帧之前返回的值没有意义
+#[export_name = "opus_ffi_get_encoder_final_bandwidth"]
+pub extern "C" fn get_encoder_final_bandwidth(
+    encoder: *mut Encoder,
+    result: *mut OpusBandwidth,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let bandwidth = encoder.get_bandwidth()?;
+        unsafe {
+            (*result) = bandwidth;
+        }
+
+        Ok(())
+    })
+}
+
+/// 设置编码器允许使用的最大音频带宽
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `bandwidth` - `OpusBandwidth` 的取值（1101 窄带 ~ 1105 全带，默认为全带）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。其他取值返回 `OPUS_BAD_ARG`
+///
+/// # 注意事项
+///
+/// * 与 `opus_ffi_set_encoder_bandwidth` 不同，编码器仍可以在比特率较低时选择更窄的带宽
+#[export_name = "opus_ffi_set_encoder_max_bandwidth"]
+pub extern "C" fn set_encoder_max_bandwidth(
+    encoder: *mut Encoder,
+    bandwidth: i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let bandwidth =
+            bandwidth_from(bandwidth)?.ok_or_else(|| bad_arg("Max bandwidth cannot be auto"))?;
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_max_bandwidth(bandwidth)?;
+
+        Ok(())
+    })
+}
+
+/// 获取编码器允许使用的最大音频带宽
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，最大带宽
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_max_bandwidth"]
+pub extern "C" fn get_encoder_max_bandwidth(
+    encoder: *mut Encoder,
+    result: *mut OpusBandwidth,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let bandwidth = encoder.get_max_bandwidth()?;
+        unsafe {
+            (*result) = bandwidth;
+        }
+
+        Ok(())
+    })
+}
+
+/// 强制编码器输出单声道或立体声数据包
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `channels` - 1 表示强制单声道，2 表示强制立体声，`FORCE_CHANNELS_AUTO`（-1000）表示不强制（默认）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。其他取值返回 `OPUS_BAD_ARG`
+///
+/// # 注意事项
+///
+/// * 单声道编码器不能强制为立体声，此时返回 `OPUS_BAD_ARG`
+#[export_name = "opus_ffi_set_encoder_force_channels"]
+pub extern "C" fn set_encoder_force_channels(
+    encoder: *mut Encoder,
+    channels: i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let channels = match channels {
+            FORCE_CHANNELS_AUTO => None,
+            1 => Some(Channels::Mono),
+            2 => Some(Channels::Stereo),
+            other => return Err(bad_arg(&format!("Invalid force channels: {other}"))),
+        };
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.set_force_channels(channels)?;
+
+        Ok(())
+    })
+}
+
+/// 获取编码器强制使用的声道数
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，1、2 或 `FORCE_CHANNELS_AUTO`（-1000）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_get_encoder_force_channels"]
+pub extern "C" fn get_encoder_force_channels(
+    encoder: *mut Encoder,
+    result: *mut i32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let channels = encoder.get_force_channels()?;
+        unsafe {
+            (*result) = channels.map_or(FORCE_CHANNELS_AUTO, |channels| channels as i32);
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取编码器最近一帧的熵编码器最终状态
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `result` - 输出参数，最终状态
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 解码器解码同一个数据包后，`opus_ffi_get_decoder_final_range` 应返回相同的值，
+///   不同则说明数据包在传输中损坏或编解码结果不一致
+#[export_name = "opus_ffi_get_encoder_final_range"]
+pub extern "C" fn get_encoder_final_range(
+    encoder: *mut Encoder,
+    result: *mut u32,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let range = encoder.get_final_range()?;
+        unsafe {
+            (*result) = range;
+        }
+
+        Ok(())
+    })
+}
+
+/// 重置编码器状态（对应 `OPUS_RESET_STATE`）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_encoder` 创建的编码器实例
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 清除之前编码留下的内部状态，之后的输出与新创建的编码器相同，适合在开始新的通话或录音时复用编码器
+/// * 比特率、复杂度等设置保持不变
+///
+/// # 示例
+///
+/// ```c
+/// // 上一段录音结束，开始新的录音
+/// opus_ffi_reset_encoder(encoder, &error);
+/// ```
+#[export_name = "opus_ffi_reset_encoder"]
+pub extern "C" fn reset_encoder(encoder: *mut Encoder, error: *mut OpusError) -> c_int {
+    if encoder.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        encoder.reset_state()?;
+
+        Ok(())
+    })
+}
+
+/// 释放 Opus 编码器实例
+///
+/// # 参数
+///
+/// * `encoder` - 通过 `opus_ffi_new_encoder` 创建的编码器指针
+///
+/// # 安全性
+///
+/// * 如果 `encoder` 为 `NULL`，函数不会执行任何操作
+/// * 释放后，`encoder` 指针将不再有效，不应再次使用
+/// * 每个通过 `opus_ffi_new_encoder` 创建的编码器必须且只能调用一次此函数
+/// * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `encoder` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `encoder` 不是有效的编码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+///
+/// # 示例
+///
+/// ```c
+/// Encoder *encoder = NULL;
+/// // ... 创建和使用编码器 ...
+/// opus_ffi_free_encoder(encoder);
+/// encoder = NULL; // 防止重复释放
+/// ```
+#[export_name = "opus_ffi_free_encoder"]
+pub extern "C" fn free_encoder(encoder: *mut Encoder) -> c_int {
+    match handle::release(encoder) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::invalid_input;
+    use std::ptr;
+
+    #[test]
+    fn test_null_checks_encoder() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = new_encoder(1, 48000, 1, ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+
+        let res = encode(
+            ptr::null_mut(),
+            ptr::null(),
+            0,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, expected_error);
+
+        let res = set_encoder_bitrate(ptr::null_mut(), BITRATE_AUTO, ptr::null_mut());
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_encoder_ctl() {
+        let mut encoder: *mut Encoder = ptr::null_mut();
+        let res = new_encoder(1, 16000, 1, &mut encoder, ptr::null_mut());
+        assert_eq!(res, 0);
+
+        assert_eq!(set_encoder_bitrate(encoder, 24000, ptr::null_mut()), 0);
+        let mut bitrate = 0;
+        assert_eq!(
+            get_encoder_bitrate(encoder, &mut bitrate, ptr::null_mut()),
+            0
+        );
+        assert_eq!(bitrate, 24000);
+
+        assert_eq!(set_encoder_complexity(encoder, 5, ptr::null_mut()), 0);
+        let mut complexity = 0;
+        assert_eq!(
+            get_encoder_complexity(encoder, &mut complexity, ptr::null_mut()),
+            0
+        );
+        assert_eq!(complexity, 5);
+        assert!(set_encoder_complexity(encoder, 11, ptr::null_mut()) < 0);
+
+        assert_eq!(set_encoder_vbr(encoder, false, ptr::null_mut()), 0);
+        let mut vbr = true;
+        assert_eq!(get_encoder_vbr(encoder, &mut vbr, ptr::null_mut()), 0);
+        assert!(!vbr);
+
+        assert_eq!(
+            set_encoder_vbr_constraint(encoder, false, ptr::null_mut()),
+            0
+        );
+        let mut constraint = true;
+        assert_eq!(
+            get_encoder_vbr_constraint(encoder, &mut constraint, ptr::null_mut()),
+            0
+        );
+        assert!(!constraint);
+
+        free_encoder(encoder);
+    }
+
+    #[test]
+    fn test_reset_encoder() {
+        let encode_frames = |encoder: *mut Encoder| -> Vec<Vec<u8>> {
+            (0..5)
+                .map(|frame| {
+                    let pcm: Vec<i16> = (0..320)
+                        .map(|i| (((frame * 320 + i) as f32 * 0.05).sin() * 8000.0) as i16)
+                        .collect();
+                    let mut packet = [0u8; 4000];
+                    let mut size = 0;
+                    let res = encode(
+                        encoder,
+                        pcm.as_ptr(),
+                        pcm.len() as u32,
+                        packet.as_mut_ptr(),
+                        packet.len() as u32,
+                        &mut size,
+                        ptr::null_mut(),
+                    );
+                    assert_eq!(res, 0);
+                    packet[..size].to_vec()
+                })
+                .collect()
+        };
+
+        let mut used: *mut Encoder = ptr::null_mut();
+        let mut fresh: *mut Encoder = ptr::null_mut();
+        assert_eq!(new_encoder(1, 16000, 1, &mut used, ptr::null_mut()), 0);
+        assert_eq!(new_encoder(1, 16000, 1, &mut fresh, ptr::null_mut()), 0);
+
+        let first = encode_frames(used);
+        assert_eq!(reset_encoder(used, ptr::null_mut()), 0);
+        let expected = encode_frames(fresh);
+        assert_eq!(encode_frames(used), expected);
+        assert_eq!(first, expected);
+
+        assert_eq!(
+            reset_encoder(ptr::null_mut(), ptr::null_mut()),
+            invalid_input(ptr::null_mut())
+        );
+
+        free_encoder(used);
+        free_encoder(fresh);
+    }
+
+    #[test]
+    fn test_encoder_signal_and_bandwidth() {
+        let mut encoder: *mut Encoder = ptr::null_mut();
+        assert_eq!(new_encoder(2, 48000, 2, &mut encoder, ptr::null_mut()), 0);
+
+        let mut signal = 0;
+        assert_eq!(
+            set_encoder_signal(encoder, SIGNAL_VOICE, ptr::null_mut()),
+            0
+        );
+        assert_eq!(get_encoder_signal(encoder, &mut signal, ptr::null_mut()), 0);
+        assert_eq!(signal, SIGNAL_VOICE);
+        assert_eq!(
+            set_encoder_signal(encoder, 1, ptr::null_mut()),
+            ErrorCode::BadArg as c_int
+        );
+
+        let mut bandwidth = OpusBandwidth::Fullband;
+        assert_eq!(
+            set_encoder_max_bandwidth(
+                encoder,
+                OpusBandwidth::Superwideband as i32,
+                ptr::null_mut()
+            ),
+            0
+        );
+        assert_eq!(
+            get_encoder_max_bandwidth(encoder, &mut bandwidth, ptr::null_mut()),
+            0
+        );
+        assert_eq!(bandwidth, OpusBandwidth::Superwideband);
+        assert_eq!(
+            set_encoder_max_bandwidth(encoder, BANDWIDTH_AUTO, ptr::null_mut()),
+            ErrorCode::BadArg as c_int
+        );
+
+        // 强制窄带后，实际使用的带宽也是窄带
+        assert_eq!(
+            set_encoder_bandwidth(encoder, OpusBandwidth::Narrowband as i32, ptr::null_mut()),
+            0
+        );
+        assert_eq!(
+            set_encoder_bandwidth(encoder, 1100, ptr::null_mut()),
+            ErrorCode::BadArg as c_int
+        );
+        let pcm = [0i16; 960 * 2];
+        let mut packet = [0u8; 4000];
+        let mut size = 0;
+        let res = encode(
+            encoder,
+            pcm.as_ptr(),
+            pcm.len() as u32,
+            packet.as_mut_ptr(),
+            packet.len() as u32,
+            &mut size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(
+            get_encoder_final_bandwidth(encoder, &mut bandwidth, ptr::null_mut()),
+            0
+        );
+        assert_eq!(bandwidth, OpusBandwidth::Narrowband);
+
+        let mut channels = 0;
+        assert_eq!(set_encoder_force_channels(encoder, 1, ptr::null_mut()), 0);
+        assert_eq!(
+            get_encoder_force_channels(encoder, &mut channels, ptr::null_mut()),
+            0
+        );
+        assert_eq!(channels, 1);
+        assert_eq!(
+            set_encoder_force_channels(encoder, FORCE_CHANNELS_AUTO, ptr::null_mut()),
+            0
+        );
+        assert_eq!(
+            get_encoder_force_channels(encoder, &mut channels, ptr::null_mut()),
+            0
+        );
+        assert_eq!(channels, FORCE_CHANNELS_AUTO);
+        assert_eq!(
+            set_encoder_force_channels(encoder, 3, ptr::null_mut()),
+            ErrorCode::BadArg as c_int
+        );
+
+        free_encoder(encoder);
+    }
+
+    #[test]
+    fn test_encoder_dtx() {
+        let mut encoder: *mut Encoder = ptr::null_mut();
+        assert_eq!(new_encoder(1, 16000, 1, &mut encoder, ptr::null_mut()), 0);
+
+        let mut enabled = true;
+        assert_eq!(get_encoder_dtx(encoder, &mut enabled, ptr::null_mut()), 0);
+        assert!(!enabled);
+        assert_eq!(set_encoder_dtx(encoder, true, ptr::null_mut()), 0);
+        assert_eq!(get_encoder_dtx(encoder, &mut enabled, ptr::null_mut()), 0);
+        assert!(enabled);
+
+        // 持续静音一段时间后进入 DTX
+        let pcm = [0i16; 320];
+        let mut packet = [0u8; 4000];
+        let mut results = Vec::new();
+        for _ in 0..30 {
+            let mut encoded = EncodeResult::default();
+            let res = encode_with_result(
+                encoder,
+                pcm.as_ptr(),
+                pcm.len() as u32,
+                packet.as_mut_ptr(),
+                packet.len() as u32,
+                &mut encoded,
+                ptr::null_mut(),
+            );
+            assert_eq!(res, 0);
+            assert_eq!(encoded.dtx, encoded.size <= 2);
+            results.push(encoded);
+        }
+        assert!(!results[0].dtx);
+        assert!(results.last().unwrap().dtx);
+
+        free_encoder(encoder);
+    }
+
+    #[test]
+    fn test_encode_batch() {
+        const FRAMES: usize = 10;
+        let pcm: Vec<i16> = (0..320 * FRAMES)
+            .map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16)
+            .collect();
+
+        let mut batch: *mut Encoder = ptr::null_mut();
+        let mut single: *mut Encoder = ptr::null_mut();
+        assert_eq!(new_encoder(1, 16000, 1, &mut batch, ptr::null_mut()), 0);
+        assert_eq!(new_encoder(1, 16000, 1, &mut single, ptr::null_mut()), 0);
+
+        let mut output = vec![0u8; FRAMES * 400 + MAX_PACKET_SIZE];
+        let mut lengths = [0u32; FRAMES];
+        let mut count = 0;
+        let res = encode_batch(
+            batch,
+            pcm.as_ptr(),
+            pcm.len() as u32,
+            320,
+            output.as_mut_ptr(),
+            output.len(),
+            lengths.as_mut_ptr(),
+            lengths.len() as u32,
+            &mut count,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(count, FRAMES);
+
+        // 与逐帧编码的结果完全相同
+        let mut offset = 0;
+        for (frame, &length) in pcm.chunks(320).zip(&lengths) {
+            let mut packet = [0u8; 4000];
+            let mut size = 0;
+            let res = encode(
+                single,
+                frame.as_ptr(),
+                frame.len() as u32,
+                packet.as_mut_ptr(),
+                packet.len() as u32,
+                &mut size,
+                ptr::null_mut(),
+            );
+            assert_eq!(res, 0);
+            assert_eq!(&output[offset..offset + length as usize], &packet[..size]);
+            offset += size;
+        }
+
+        // 输入不是整数帧
+        let res = encode_batch(
+            batch,
+            pcm.as_ptr(),
+            321,
+            320,
+            output.as_mut_ptr(),
+            output.len(),
+            lengths.as_mut_ptr(),
+            lengths.len() as u32,
+            &mut count,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, ErrorCode::BadArg as c_int);
+
+        // 输出缓冲区只够放下部分数据包，从 frame_count 继续后结果与一次编码完全相同
+        let expected = output[..offset].to_vec();
+        assert_eq!(reset_encoder(batch, ptr::null_mut()), 0);
+        let res = encode_batch(
+            batch,
+            pcm.as_ptr(),
+            pcm.len() as u32,
+            320,
+            output.as_mut_ptr(),
+            MAX_PACKET_SIZE + (lengths[0] + lengths[1]) as usize,
+            lengths.as_mut_ptr(),
+            lengths.len() as u32,
+            &mut count,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, ErrorCode::BufferTooSmall as c_int);
+        assert!(count > 0 && count < FRAMES);
+
+        let written: usize = lengths[..count].iter().map(|&length| length as usize).sum();
+        let mut rest = [0u32; FRAMES];
+        let mut rest_count = 0;
+        let res = encode_batch(
+            batch,
+            pcm[count * 320..].as_ptr(),
+            ((FRAMES - count) * 320) as u32,
+            320,
+            output[written..].as_mut_ptr(),
+            output.len() - written,
+            rest.as_mut_ptr(),
+            rest.len() as u32,
+            &mut rest_count,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(count + rest_count, FRAMES);
+        assert_eq!(output[..offset], expected[..]);
+
+        free_encoder(batch);
+        free_encoder(single);
     }
 }
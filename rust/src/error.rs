@@ -1,8 +1,128 @@
 use opus_rs::Error;
-use std::ffi::{c_char, c_int, CString};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::fmt;
 
 use crate::utils::free_c_string;
 
+/// 本库返回的全部错误代码
+///
+/// `-1` ~ `-8` 与 libopus 的 `OPUS_*` 错误代码相同，`-9` 及以下是本库自己的错误。
+/// 可以通过 `opus_ffi_error_name` 获取错误代码的名称。
+///
+/// cbindgen:prefix-with-name
+/// cbindgen:rename-all=ScreamingSnakeCase
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpusErrorCode {
+    /// 成功
+    Ok = 0,
+    /// 参数无效（`OPUS_BAD_ARG`）
+    BadArg = -1,
+    /// 输出缓冲区太小（`OPUS_BUFFER_TOO_SMALL`）
+    BufferTooSmall = -2,
+    /// libopus 内部错误（`OPUS_INTERNAL_ERROR`）
+    InternalError = -3,
+    /// 数据包损坏或不合法（`OPUS_INVALID_PACKET`）
+    InvalidPacket = -4,
+    /// 请求的功能未实现（`OPUS_UNIMPLEMENTED`）
+    Unimplemented = -5,
+    /// 编解码器状态无效，或对象已结束使用（`OPUS_INVALID_STATE`）
+    InvalidState = -6,
+    /// 内存分配失败（`OPUS_ALLOC_FAIL`）
+    AllocFail = -7,
+    /// 未知的 libopus 错误
+    Unknown = -8,
+    /// 传入了空指针或不是 UTF-8 的字符串
+    InvalidInput = -9,
+    /// Rust 代码发生 panic，错误描述中包含 panic 信息和位置
+    Panic = -10,
+    /// 文件读写错误
+    Io = -11,
+    /// 容器格式错误（不是 Ogg Opus 流、头部损坏等）
+    Format = -12,
+    /// 不支持的声道数或应用模式
+    Unsupported = -13,
+    /// 句柄已被释放、从未创建或类型不符
+    InvalidHandle = -14,
+}
+
+impl OpusErrorCode {
+    const ALL: [OpusErrorCode; 15] = [
+        OpusErrorCode::Ok,
+        OpusErrorCode::BadArg,
+        OpusErrorCode::BufferTooSmall,
+        OpusErrorCode::InternalError,
+        OpusErrorCode::InvalidPacket,
+        OpusErrorCode::Unimplemented,
+        OpusErrorCode::InvalidState,
+        OpusErrorCode::AllocFail,
+        OpusErrorCode::Unknown,
+        OpusErrorCode::InvalidInput,
+        OpusErrorCode::Panic,
+        OpusErrorCode::Io,
+        OpusErrorCode::Format,
+        OpusErrorCode::Unsupported,
+        OpusErrorCode::InvalidHandle,
+    ];
+
+    /// 由错误代码得到枚举值，不认识的代码返回 `None`
+    pub fn from_code(code: c_int) -> Option<Self> {
+        Self::ALL.into_iter().find(|&e| e as c_int == code)
+    }
+
+    /// 与 C 头文件中一致的常量名
+    pub fn name(self) -> &'static CStr {
+        match self {
+            OpusErrorCode::Ok => c"OPUS_ERROR_CODE_OK",
+            OpusErrorCode::BadArg => c"OPUS_ERROR_CODE_BAD_ARG",
+            OpusErrorCode::BufferTooSmall => c"OPUS_ERROR_CODE_BUFFER_TOO_SMALL",
+            OpusErrorCode::InternalError => c"OPUS_ERROR_CODE_INTERNAL_ERROR",
+            OpusErrorCode::InvalidPacket => c"OPUS_ERROR_CODE_INVALID_PACKET",
+            OpusErrorCode::Unimplemented => c"OPUS_ERROR_CODE_UNIMPLEMENTED",
+            OpusErrorCode::InvalidState => c"OPUS_ERROR_CODE_INVALID_STATE",
+            OpusErrorCode::AllocFail => c"OPUS_ERROR_CODE_ALLOC_FAIL",
+            OpusErrorCode::Unknown => c"OPUS_ERROR_CODE_UNKNOWN",
+            OpusErrorCode::InvalidInput => c"OPUS_ERROR_CODE_INVALID_INPUT",
+            OpusErrorCode::Panic => c"OPUS_ERROR_CODE_PANIC",
+            OpusErrorCode::Io => c"OPUS_ERROR_CODE_IO",
+            OpusErrorCode::Format => c"OPUS_ERROR_CODE_FORMAT",
+            OpusErrorCode::Unsupported => c"OPUS_ERROR_CODE_UNSUPPORTED",
+            OpusErrorCode::InvalidHandle => c"OPUS_ERROR_CODE_INVALID_HANDLE",
+        }
+    }
+}
+
+/// 获取错误代码的名称
+///
+/// # 参数
+///
+/// * `code` - 任意函数返回的错误代码
+///
+/// # 返回值
+///
+/// 错误代码对应的常量名（例如 `-9` 返回 `"OPUS_ERROR_CODE_INVALID_INPUT"`），
+/// 不认识的代码返回 `"OPUS_ERROR_CODE_UNRECOGNIZED"`，不会返回 `NULL`。
+///
+/// # 安全性
+///
+/// 返回的是静态字符串，调用者不能释放。
+///
+/// # 示例
+///
+/// ```c
+/// int res = opus_ffi_new_decoder(6, 16000, &decoder, NULL);
+/// if (res < 0) {
+///     printf("%s: %s\n", opus_ffi_error_name(res), opus_ffi_last_error_message());
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn opus_ffi_error_name(code: c_int) -> *const c_char {
+    OpusErrorCode::from_code(code)
+        .map_or(c"OPUS_ERROR_CODE_UNRECOGNIZED", OpusErrorCode::name)
+        .as_ptr()
+}
+
 /// Opus 错误结构体，用于在 C 和 Rust 之间传递错误信息
 ///
 /// # 内存管理
@@ -10,16 +130,16 @@ use crate::utils::free_c_string;
 /// **重要**：`message` 字段是由 Rust 分配的 C 字符串，需要手动释放。
 ///
 /// - **在 Rust 代码中**：如果 `OpusError` 在 Rust 栈上分配，`Drop` trait 会自动释放 `message`
-/// - **在 C 代码中**：如果 `OpusError` 在 C 栈上分配，必须手动调用 `free_c_string(&error.message)` 来释放 `message`
+/// - **在 C 代码中**：如果 `OpusError` 在 C 栈上分配，必须手动调用 `opus_ffi_free_c_string(&error.message)` 来释放 `message`
 ///
 /// # 示例（C 代码）
 ///
 /// ```c
 /// OpusError error = {0, NULL};
-/// int res = new_decoder(1, 16000, &decoder, &error);
+/// int res = opus_ffi_new_decoder(1, 16000, &decoder, &error);
 /// if (res < 0 && error.message != NULL) {
 ///     printf("Error: %s\n", error.message);
-///     free_c_string(&error.message);  // 必须手动释放
+///     opus_ffi_free_c_string(&error.message);  // 必须手动释放
 /// }
 /// ```
 #[repr(C)]
@@ -32,7 +152,7 @@ impl Drop for OpusError {
     /// 自动释放 `message` 字段（仅对 Rust 代码中分配的 `OpusError` 有效）
     ///
     /// 注意：对于 C 代码中在栈上分配的 `OpusError`，此 `Drop` 不会被调用，
-    /// C 代码必须手动调用 `free_c_string(&error.message)` 来释放内存。
+    /// C 代码必须手动调用 `opus_ffi_free_c_string(&error.message)` 来释放内存。
     fn drop(&mut self) {
         if !self.message.is_null() {
             free_c_string(&mut self.message);
@@ -41,8 +161,28 @@ impl Drop for OpusError {
 }
 
 impl OpusError {
-    pub fn fill(out: *mut OpusError, origin: Error) -> c_int {
+    pub(crate) fn new(code: c_int, message: &str) -> Self {
+        let message = CString::new(message).unwrap();
+        Self {
+            code,
+            message: message.into_raw(),
+        }
+    }
+
+    /// 错误描述，`message` 为空时返回空字符串
+    pub fn message(&self) -> &str {
+        if self.message.is_null() {
+            return "";
+        }
+
+        unsafe { CStr::from_ptr(self.message) }
+            .to_str()
+            .unwrap_or_default()
+    }
+
+    pub fn fill<E: Into<OpusError>>(out: *mut OpusError, origin: E) -> c_int {
         let mut err: OpusError = origin.into();
+        set_last_error(err.code, err.message());
         unsafe {
             if !out.is_null() {
                 (*out).code = err.code;
@@ -55,6 +195,23 @@ impl OpusError {
     }
 }
 
+impl fmt::Debug for OpusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpusError")
+            .field("code", &self.code)
+            .field("message", &self.message())
+            .finish()
+    }
+}
+
+impl fmt::Display for OpusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code: {})", self.message(), self.code)
+    }
+}
+
+impl std::error::Error for OpusError {}
+
 impl From<Error> for OpusError {
     fn from(e: Error) -> Self {
         let message = CString::new(e.description()).unwrap();
@@ -64,3 +221,154 @@ impl From<Error> for OpusError {
         }
     }
 }
+
+impl From<std::io::Error> for OpusError {
+    fn from(e: std::io::Error) -> Self {
+        Self::new(OpusErrorCode::Io as c_int, &e.to_string())
+    }
+}
+
+/// 容器格式错误（不是 Ogg Opus 流、头部损坏等）
+pub(crate) fn format_error(message: &str) -> OpusError {
+    OpusError::new(OpusErrorCode::Format as c_int, message)
+}
+
+/// Rust 代码发生 panic（库内部错误），描述中包含 panic 信息、发生位置和出错的接口函数位置
+pub(crate) fn panic_error(message: &str) -> OpusError {
+    OpusError::new(OpusErrorCode::Panic as c_int, message)
+}
+
+/// 不支持的参数值（声道数、应用模式等），宽松模式下这些值会回退为默认值
+pub(crate) fn unsupported_error(message: &str) -> OpusError {
+    OpusError::new(OpusErrorCode::Unsupported as c_int, message)
+}
+
+thread_local! {
+    /// 当前线程最近一次失败调用的错误码和描述
+    static LAST_ERROR: RefCell<(c_int, CString)> = RefCell::new((0, CString::default()));
+}
+
+/// 记录当前线程的最近一次错误，所有错误路径（`OpusError::fill`、`fill_error`）都会调用
+pub(crate) fn set_last_error(code: c_int, message: &str) {
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = (code, message));
+}
+
+/// 获取当前线程最近一次失败调用的错误码
+///
+/// # 返回值
+///
+/// * `0` - 当前线程还没有调用失败过，或已调用 `opus_ffi_clear_last_error` 清除
+/// * 负数 - 最近一次失败调用返回的错误代码
+///
+/// # 注意事项
+///
+/// * 与 C 的 `errno` 类似，调用成功时不会清除上一次的错误，应先检查函数的返回值再读取
+/// * 不论调用时 `error` 参数是否为 `NULL`，错误都会被记录，热路径可以传入 `NULL` 以避免分配和释放 `OpusError`
+/// * 错误按线程分别保存，只能读取到本线程的错误
+///
+/// # 示例
+///
+/// ```c
+/// if (opus_ffi_decode(decoder, packet, packet_size, pcm, 320, false, &samples, NULL) < 0) {
+///     printf("Error %d: %s\n", opus_ffi_last_error_code(), opus_ffi_last_error_message());
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn opus_ffi_last_error_code() -> c_int {
+    LAST_ERROR.with(|last| last.borrow().0)
+}
+
+/// 获取当前线程最近一次失败调用的错误描述
+///
+/// # 返回值
+///
+/// UTF-8 编码的 C 字符串，没有错误时为空字符串，不会返回 `NULL`。
+///
+/// # 安全性
+///
+/// * 字符串由库内部持有，调用者不能释放，也不能调用 `opus_ffi_free_c_string`
+/// * 指针在本线程的下一次调用失败或调用 `opus_ffi_clear_last_error` 之前有效，需要保留时应自行复制
+#[no_mangle]
+pub extern "C" fn opus_ffi_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().1.as_ptr())
+}
+
+/// 清除当前线程记录的错误，之后 `opus_ffi_last_error_code` 返回 0
+#[no_mangle]
+pub extern "C" fn opus_ffi_clear_last_error() {
+    set_last_error(0, "");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decoder::new_decoder, utils::invalid_input};
+    use opus_rs::ErrorCode;
+    use std::ptr;
+
+    fn last_message() -> String {
+        unsafe { CStr::from_ptr(opus_ffi_last_error_message()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_last_error() {
+        opus_ffi_clear_last_error();
+        assert_eq!(opus_ffi_last_error_code(), 0);
+        assert_eq!(last_message(), "");
+
+        let code = invalid_input(ptr::null_mut());
+        assert_eq!(opus_ffi_last_error_code(), code);
+        assert_eq!(last_message(), "Invalid input");
+
+        // 不传 error 参数时，libopus 返回的错误同样会被记录
+        let mut decoder = ptr::null_mut();
+        let res = new_decoder(1, 44100, &mut decoder, ptr::null_mut());
+        assert_eq!(res, ErrorCode::BadArg as c_int);
+        assert_eq!(opus_ffi_last_error_code(), res);
+        assert!(!last_message().is_empty());
+
+        // 成功的调用不会清除错误
+        let res = new_decoder(1, 16000, &mut decoder, ptr::null_mut());
+        assert_eq!(res, 0);
+        assert_eq!(opus_ffi_last_error_code(), ErrorCode::BadArg as c_int);
+        crate::decoder::free_decoder(decoder);
+
+        // 错误按线程保存
+        std::thread::spawn(|| assert_eq!(opus_ffi_last_error_code(), 0))
+            .join()
+            .unwrap();
+
+        opus_ffi_clear_last_error();
+        assert_eq!(opus_ffi_last_error_code(), 0);
+    }
+
+    #[test]
+    fn test_error_name() {
+        let name = |code| unsafe { CStr::from_ptr(opus_ffi_error_name(code)) };
+        assert_eq!(name(0), c"OPUS_ERROR_CODE_OK");
+        assert_eq!(
+            name(ErrorCode::InvalidPacket as c_int),
+            c"OPUS_ERROR_CODE_INVALID_PACKET"
+        );
+        assert_eq!(name(-9), c"OPUS_ERROR_CODE_INVALID_INPUT");
+        assert_eq!(name(-13), c"OPUS_ERROR_CODE_UNSUPPORTED");
+        assert_eq!(name(-100), c"OPUS_ERROR_CODE_UNRECOGNIZED");
+
+        // libopus 的错误代码与枚举值一致
+        for (ours, libopus) in [
+            (OpusErrorCode::BadArg, ErrorCode::BadArg),
+            (OpusErrorCode::BufferTooSmall, ErrorCode::BufferTooSmall),
+            (OpusErrorCode::InternalError, ErrorCode::InternalError),
+            (OpusErrorCode::InvalidPacket, ErrorCode::InvalidPacket),
+            (OpusErrorCode::Unimplemented, ErrorCode::Unimplemented),
+            (OpusErrorCode::InvalidState, ErrorCode::InvalidState),
+            (OpusErrorCode::AllocFail, ErrorCode::AllocFail),
+            (OpusErrorCode::Unknown, ErrorCode::Unknown),
+        ] {
+            assert_eq!(ours as c_int, libopus as c_int);
+        }
+    }
+}
@@ -0,0 +1,417 @@
+//! 句柄登记表
+//!
+//! 编码器、解码器等对象放在登记表中，交给调用者的句柄是 `槽位 | 代数` 组成的不透明编号，不是对象地址。
+//! 接口函数通过编号查表，已释放、从未创建或类型不符的句柄会返回 `OPUS_ERROR_CODE_INVALID_HANDLE`，
+//! 而不是造成未定义行为。槽位被复用时代数加一，释放后的旧句柄不会与新创建的句柄相同。
+//!
+//! 每次调用在返回前一直占用句柄，同一句柄上的调用依次执行；释放句柄会等待正在进行的调用结束后才销毁对象。
+//! 归其他对象所有的对象（例如流式编码器内部的编码器）与所有者共用占用状态，所有者释放时一并注销。
+
+use std::{
+    any::{type_name, TypeId},
+    ffi::c_int,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+};
+
+use crate::error::{OpusError, OpusErrorCode};
+
+/// 句柄编号中槽位所占的位数，其余高位为代数
+const SLOT_BITS: u32 = usize::BITS / 2;
+const SLOT_MASK: usize = (1 << SLOT_BITS) - 1;
+const GENERATION_MASK: usize = usize::MAX >> SLOT_BITS;
+
+struct Usage {
+    busy: bool,
+    alive: bool,
+}
+
+/// 句柄的占用状态，调用期间 `busy` 为 true，注销后 `alive` 为 false
+struct State {
+    usage: Mutex<Usage>,
+    idle: Condvar,
+}
+
+impl State {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            usage: Mutex::new(Usage {
+                busy: false,
+                alive: true,
+            }),
+            idle: Condvar::new(),
+        })
+    }
+
+    fn usage(&self) -> MutexGuard<'_, Usage> {
+        // 持有锁时不会 panic，锁不会被污染；即使被污染，状态本身仍然完整
+        self.usage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 等待其他调用结束后占用句柄，句柄已注销时返回 false
+    fn acquire(&self) -> bool {
+        let mut usage = self.usage();
+        while usage.busy {
+            usage = self.idle.wait(usage).unwrap_or_else(|e| e.into_inner());
+        }
+        if !usage.alive {
+            return false;
+        }
+        usage.busy = true;
+
+        true
+    }
+
+    /// 等待正在进行的调用结束后把句柄标记为已注销
+    fn retire(&self) {
+        let mut usage = self.usage();
+        while usage.busy {
+            usage = self.idle.wait(usage).unwrap_or_else(|e| e.into_inner());
+        }
+        usage.alive = false;
+    }
+
+    fn finish(&self) {
+        self.usage().busy = false;
+        self.idle.notify_all();
+    }
+}
+
+struct Entry {
+    type_id: TypeId,
+    type_name: &'static str,
+    // 为 false 时对象归其他对象所有（例如 Ogg 写入器内部的编码器），不能单独释放
+    owned: bool,
+    object: usize,
+    state: Arc<State>,
+}
+
+#[derive(Default)]
+struct Slot {
+    generation: usize,
+    entry: Option<Entry>,
+}
+
+struct Registry {
+    slots: Vec<Slot>,
+    free: Vec<usize>,
+}
+
+static HANDLES: Mutex<Registry> = Mutex::new(Registry {
+    slots: Vec::new(),
+    free: Vec::new(),
+});
+
+fn handles() -> MutexGuard<'static, Registry> {
+    // 持有锁时不会 panic，锁不会被污染；即使被污染，表本身仍然完整
+    HANDLES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Registry {
+    fn insert(&mut self, entry: Entry) -> usize {
+        let slot = match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                assert!(self.slots.len() < SLOT_MASK, "Too many live handles");
+                self.slots.push(Slot::default());
+                self.slots.len() - 1
+            }
+        };
+        self.slots[slot].entry = Some(entry);
+
+        // 槽位从 1 开始编号，句柄不会是 NULL
+        (self.slots[slot].generation << SLOT_BITS) | (slot + 1)
+    }
+
+    fn slot(&mut self, id: usize) -> Option<&mut Slot> {
+        let slot = (id & SLOT_MASK).checked_sub(1)?;
+        self.slots
+            .get_mut(slot)
+            .filter(|s| s.entry.is_some() && s.generation == id >> SLOT_BITS)
+    }
+
+    fn remove(&mut self, id: usize) -> Option<Entry> {
+        self.slot(id)?;
+        Some(self.vacate((id & SLOT_MASK) - 1))
+    }
+
+    /// 移除与 `state` 共用占用状态的所有对象，即所有者借出的对象
+    fn remove_borrowed(&mut self, state: &Arc<State>) {
+        for slot in 0..self.slots.len() {
+            let borrowed = self.slots[slot]
+                .entry
+                .as_ref()
+                .is_some_and(|e| !e.owned && Arc::ptr_eq(&e.state, state));
+            if borrowed {
+                self.vacate(slot);
+            }
+        }
+    }
+
+    fn vacate(&mut self, slot: usize) -> Entry {
+        let slot_ref = &mut self.slots[slot];
+        let entry = slot_ref.entry.take().expect("vacant slot");
+        slot_ref.generation = (slot_ref.generation + 1) & GENERATION_MASK;
+        self.free.push(slot);
+
+        entry
+    }
+}
+
+fn short_name<T>() -> &'static str {
+    let name = type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn invalid_handle(message: String) -> OpusError {
+    OpusError::new(OpusErrorCode::InvalidHandle as c_int, &message)
+}
+
+fn freed<T>() -> OpusError {
+    invalid_handle(format!(
+        "Invalid handle: {} has been freed or was never created",
+        short_name::<T>()
+    ))
+}
+
+fn entry<T: 'static>(object: *mut T, owned: bool, state: Arc<State>) -> Entry {
+    Entry {
+        type_id: TypeId::of::<T>(),
+        type_name: short_name::<T>(),
+        owned,
+        object: object as usize,
+        state,
+    }
+}
+
+/// 把对象放到堆上并登记，返回交给调用者的句柄
+pub(crate) fn into_handle<T: 'static>(value: T) -> *mut T {
+    let object = Box::into_raw(Box::new(value));
+    handles().insert(entry(object, true, State::new())) as *mut T
+}
+
+/// 登记归 `owner` 所有的对象并返回句柄
+///
+/// 借出的句柄与所有者共用占用状态：通过它的调用与所有者上的调用依次执行，所有者释放时一并注销，
+/// 不能单独释放。所有者已开始释放时返回错误
+pub(crate) fn register_borrowed<O, T: 'static>(
+    owner: &HandleRef<O>,
+    object: *mut T,
+) -> Result<*mut T, OpusError> {
+    let mut handles = handles();
+    // 所有者的释放可能已经从表中移除了它，正在等待本次调用结束
+    let registered = handles
+        .slot(owner.id)
+        .and_then(|s| s.entry.as_ref())
+        .is_some_and(|e| Arc::ptr_eq(&e.state, &owner.state));
+    if !registered {
+        return Err(freed::<O>());
+    }
+
+    Ok(handles.insert(entry(object, false, owner.state.clone())) as *mut T)
+}
+
+fn check<T: 'static>(entry: Option<&Entry>) -> Result<&Entry, OpusError> {
+    match entry {
+        Some(entry) if entry.type_id == TypeId::of::<T>() => Ok(entry),
+        Some(entry) => Err(invalid_handle(format!(
+            "Invalid handle: expected {}, got {}",
+            short_name::<T>(),
+            entry.type_name
+        ))),
+        None => Err(freed::<T>()),
+    }
+}
+
+/// 对句柄所指对象的独占访问，析构时结束占用
+pub(crate) struct HandleRef<T> {
+    id: usize,
+    object: *mut T,
+    state: Arc<State>,
+}
+
+impl<T> Deref for HandleRef<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.object }
+    }
+}
+
+impl<T> DerefMut for HandleRef<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.object }
+    }
+}
+
+impl<T> Drop for HandleRef<T> {
+    fn drop(&mut self) {
+        self.state.finish();
+    }
+}
+
+/// 检查句柄并占用它，返回的 `HandleRef` 析构前同一句柄上的其他调用和释放都会等待
+pub(crate) fn get_mut<T: 'static>(handle: *mut T) -> Result<HandleRef<T>, OpusError> {
+    let (object, state) = {
+        let mut handles = handles();
+        let entry = check::<T>(handles.slot(handle as usize).and_then(|s| s.entry.as_ref()))?;
+        (entry.object as *mut T, entry.state.clone())
+    };
+
+    // 不能持有登记表的锁等待，否则一个耗时的调用会阻塞所有句柄
+    if !state.acquire() {
+        return Err(freed::<T>());
+    }
+
+    Ok(HandleRef {
+        id: handle as usize,
+        object,
+        state,
+    })
+}
+
+/// 检查并释放句柄及其借出的句柄，等待正在进行的调用结束后销毁对象。`NULL` 不做任何操作
+pub(crate) fn release<T: 'static>(handle: *mut T) -> Result<(), OpusError> {
+    if handle.is_null() {
+        return Ok(());
+    }
+
+    let entry = {
+        let mut handles = handles();
+        let entry = check::<T>(handles.slot(handle as usize).and_then(|s| s.entry.as_ref()))?;
+        if !entry.owned {
+            return Err(invalid_handle(format!(
+                "Invalid handle: {} is owned by another object and cannot be freed",
+                short_name::<T>()
+            )));
+        }
+        let entry = handles.remove(handle as usize);
+        if let Some(entry) = &entry {
+            handles.remove_borrowed(&entry.state);
+        }
+
+        entry
+    };
+
+    if let Some(entry) = entry {
+        entry.state.retire();
+        let _ = unsafe { Box::from_raw(entry.object as *mut T) };
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    #[derive(Debug)]
+    struct Dummy(u64);
+    #[derive(Debug)]
+    struct Borrowed(#[allow(dead_code)] u64);
+    #[derive(Debug)]
+    struct Owner(Borrowed);
+    #[derive(Debug)]
+    struct Other;
+
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_handle_lifecycle() {
+        let handle = into_handle(Dummy(1));
+        assert_eq!(get_mut(handle).unwrap().0, 1);
+
+        let err = get_mut(handle as *mut Other).err().unwrap();
+        assert_eq!(err.code, OpusErrorCode::InvalidHandle as c_int);
+        assert!(err.message().contains("expected Other, got Dummy"));
+
+        release(handle).unwrap();
+        let err = get_mut(handle).err().unwrap();
+        assert_eq!(err.code, OpusErrorCode::InvalidHandle as c_int);
+        // 重复释放
+        assert!(release(handle).is_err());
+        assert!(release(std::ptr::null_mut::<Dummy>()).is_ok());
+    }
+
+    #[test]
+    fn test_stale_handle() {
+        let stale = into_handle(Dummy(1));
+        release(stale).unwrap();
+
+        // 新句柄复用了槽位，但旧句柄仍然无效
+        let handles: Vec<_> = (0..16).map(|i| into_handle(Dummy(i))).collect();
+        assert!(!handles.contains(&stale));
+        assert!(get_mut(stale).is_err());
+        assert!(release(stale).is_err());
+
+        for (i, &handle) in handles.iter().enumerate() {
+            assert_eq!(get_mut(handle).unwrap().0, i as u64);
+            release(handle).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_release_waits_for_call() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let handle = into_handle(DropFlag(dropped.clone())) as usize;
+
+        let guard = get_mut(handle as *mut DropFlag).unwrap();
+        let releaser = thread::spawn(move || release(handle as *mut DropFlag).is_ok());
+        thread::sleep(Duration::from_millis(50));
+        // 调用结束前对象不会被销毁
+        assert!(!dropped.load(Ordering::SeqCst));
+        drop(guard);
+
+        assert!(releaser.join().unwrap());
+        assert!(dropped.load(Ordering::SeqCst));
+        assert!(get_mut(handle as *mut DropFlag).is_err());
+    }
+
+    #[test]
+    fn test_borrowed_handle() {
+        let owner = into_handle(Owner(Borrowed(2)));
+        let mut guard = get_mut(owner).unwrap();
+        let object: *mut Borrowed = &mut guard.0;
+        let handle = register_borrowed(&guard, object).unwrap();
+        drop(guard);
+        assert!(get_mut(handle).is_ok());
+        assert!(release(handle).is_err());
+
+        // 与所有者共用占用状态，所有者的释放会等待借出句柄上的调用
+        let guard = get_mut(handle).unwrap();
+        let owner = owner as usize;
+        let releaser = thread::spawn(move || release(owner as *mut Owner).is_ok());
+        thread::sleep(Duration::from_millis(50));
+        assert!(!releaser.is_finished());
+        drop(guard);
+
+        assert!(releaser.join().unwrap());
+        assert!(get_mut(handle).is_err());
+    }
+
+    #[test]
+    fn test_borrow_during_release() {
+        let owner = into_handle(Owner(Borrowed(3)));
+        let mut guard = get_mut(owner).unwrap();
+        let owner = owner as usize;
+        let releaser = thread::spawn(move || release(owner as *mut Owner).is_ok());
+        thread::sleep(Duration::from_millis(50));
+
+        // 所有者已从表中移除，不能再借出
+        let object: *mut Borrowed = &mut guard.0;
+        assert!(register_borrowed(&guard, object).is_err());
+        drop(guard);
+        assert!(releaser.join().unwrap());
+    }
+}
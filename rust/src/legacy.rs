@@ -0,0 +1,37 @@
+//! 不带 `opus_ffi_` 前缀的旧版导出符号
+//!
+//! 仅在启用 `legacy-symbols` feature（默认启用）时编译，供尚未迁移到新名称的调用者在过渡期间使用。
+//! 只包含改名前就已导出的编解码和释放函数，之后新增的函数只有带前缀的名称。
+//! 每个旧符号都直接转发到对应的新函数，行为完全相同。头文件中只包含新名称，
+//! 新代码应使用 `opus_ffi_*` 符号，并在 `Cargo.toml` 中关闭默认 feature 以避免与其他库的符号冲突。
+
+use std::ffi::{c_char, c_int};
+
+use crate::{decoder::Decoder, encoder::Encoder, error::OpusError};
+
+/// 为每个函数导出一个旧名称的符号，转发到 `$path`
+macro_rules! legacy_symbols {
+    ($($path:path => fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {
+        $(
+            #[no_mangle]
+            pub extern "C" fn $name($($arg: $ty),*) $(-> $ret)? {
+                $path($($arg),*)
+            }
+        )*
+    };
+}
+
+legacy_symbols! {
+    crate::decoder::new_decoder => fn new_decoder(channels: u32, sample_rate: u32, result: *mut *mut Decoder, error: *mut OpusError) -> c_int;
+    crate::decoder::decode => fn decode(decoder: *mut Decoder, input: *const u8, input_size: u32, output: *mut i16, output_size: u32, fec: bool, decoded_size: *mut usize, error: *mut OpusError) -> c_int;
+    crate::decoder::decode_float => fn decode_float(decoder: *mut Decoder, input: *const u8, input_size: u32, output: *mut f32, output_size: u32, fec: bool, result: *mut usize, error: *mut OpusError) -> c_int;
+    crate::decoder::free_decoder => fn free_decoder(decoder: *mut Decoder) -> c_int;
+
+    crate::encoder::new_encoder => fn new_encoder(channels: u32, sample_rate: u32, application: u32, result: *mut *mut Encoder, error: *mut OpusError) -> c_int;
+    crate::encoder::encode => fn encode(encoder: *mut Encoder, input: *const i16, input_size: u32, output: *mut u8, output_size: u32, encoded_size: *mut usize, error: *mut OpusError) -> c_int;
+    crate::encoder::encode_float => fn encode_float(encoder: *mut Encoder, input: *const f32, input_size: u32, output: *mut u8, output_size: u32, result: *mut usize, error: *mut OpusError) -> c_int;
+    crate::encoder::free_encoder => fn free_encoder(encoder: *mut Encoder) -> c_int;
+
+    crate::utils::free_c_string => fn free_c_string(p: *mut *mut c_char);
+    crate::utils::free_opus_error => fn free_opus_error(e: *mut OpusError);
+}
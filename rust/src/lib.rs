@@ -1,4 +1,14 @@
 pub mod decoder;
 pub mod encoder;
 pub mod error;
+mod handle;
+#[cfg(feature = "legacy-symbols")]
+mod legacy;
+pub mod multistream;
+pub mod ogg;
+pub mod packet;
+pub mod packet_stream;
+pub mod repacketizer;
+pub mod stream_encoder;
 pub mod utils;
+pub mod version;
@@ -0,0 +1,341 @@
+use std::ffi::c_int;
+
+use audiopus_sys::OpusMSDecoder;
+
+use super::{surround_layout, MultistreamLayout};
+use crate::{
+    error::OpusError,
+    handle,
+    utils::{check, ffi_exec, frame_size, invalid_input},
+};
+
+/// 多流解码器不透明指针类型
+pub struct MultistreamDecoder {
+    ptr: *mut OpusMSDecoder,
+    channels: usize,
+}
+
+impl Drop for MultistreamDecoder {
+    fn drop(&mut self) {
+        unsafe { audiopus_sys::opus_multistream_decoder_destroy(self.ptr) }
+    }
+}
+
+impl MultistreamDecoder {
+    /// 按映射族 1 的标准布局创建环绕声解码器
+    pub fn new(sample_rate: u32, channels: u32) -> Result<Self, OpusError> {
+        Self::with_layout(sample_rate, &surround_layout(channels)?)
+    }
+
+    /// 按指定的流布局创建解码器
+    pub fn with_layout(sample_rate: u32, layout: &MultistreamLayout) -> Result<Self, OpusError> {
+        let mut error = 0;
+        let ptr = unsafe {
+            audiopus_sys::opus_multistream_decoder_create(
+                sample_rate as i32,
+                layout.channels as c_int,
+                layout.streams as c_int,
+                layout.coupled_streams as c_int,
+                layout.mapping.as_ptr(),
+                &mut error,
+            )
+        };
+        check("opus_multistream_decoder_create", error)?;
+
+        Ok(Self {
+            ptr,
+            channels: layout.channels as usize,
+        })
+    }
+
+    /// 解码一个数据包为交错的 16 位 PCM，返回每声道样本数
+    pub fn decode(
+        &mut self,
+        input: &[u8],
+        output: &mut [i16],
+        fec: bool,
+    ) -> Result<usize, OpusError> {
+        let frame_size = frame_size(output.len(), self.channels)?;
+        let size = unsafe {
+            audiopus_sys::opus_multistream_decode(
+                self.ptr,
+                input.as_ptr(),
+                input.len() as i32,
+                output.as_mut_ptr(),
+                frame_size as c_int,
+                fec as c_int,
+            )
+        };
+
+        check("opus_multistream_decode", size)
+    }
+
+    /// 解码一个数据包为交错的浮点 PCM，返回每声道样本数
+    pub fn decode_float(
+        &mut self,
+        input: &[u8],
+        output: &mut [f32],
+        fec: bool,
+    ) -> Result<usize, OpusError> {
+        let frame_size = frame_size(output.len(), self.channels)?;
+        let size = unsafe {
+            audiopus_sys::opus_multistream_decode_float(
+                self.ptr,
+                input.as_ptr(),
+                input.len() as i32,
+                output.as_mut_ptr(),
+                frame_size as c_int,
+                fec as c_int,
+            )
+        };
+
+        check("opus_multistream_decode_float", size)
+    }
+}
+
+/// 创建新的多流（环绕声）解码器
+///
+/// # 参数
+///
+/// * `channels` - 声道数（1~8），使用映射族 1 的标准流布局，与同声道数的 `opus_ffi_new_multistream_encoder` 对应
+/// * `sample_rate` - 采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `result` - 输出参数，用于接收创建的解码器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），声道数不在 1~8 之间时返回 `OPUS_ERROR_CODE_UNSUPPORTED`
+///
+/// # 安全性
+///
+/// 调用者负责在不再需要时调用 `opus_ffi_free_multistream_decoder` 释放返回的解码器。
+///
+/// # 示例
+///
+/// ```c
+/// MultistreamDecoder *decoder = NULL;
+/// OpusError error = {0, NULL};
+/// int res = opus_ffi_new_multistream_decoder(6, 48000, &decoder, &error);
+/// if (res == 0) {
+///     float pcm[960 * 6];
+///     size_t samples = 0;
+///     opus_ffi_multistream_decode_float(decoder, packet, packet_size, pcm, 960 * 6, false, &samples, &error);
+///     opus_ffi_free_multistream_decoder(decoder);
+/// }
+/// ```
+#[export_name = "opus_ffi_new_multistream_decoder"]
+pub extern "C" fn new_multistream_decoder(
+    channels: u32,
+    sample_rate: u32,
+    result: *mut *mut MultistreamDecoder,
+    error: *mut OpusError,
+) -> c_int {
+    if result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let decoder = MultistreamDecoder::new(sample_rate, channels)?;
+        unsafe {
+            *result = handle::into_handle(decoder);
+        }
+
+        Ok(())
+    })
+}
+
+/// 解码多流 Opus 数据包为交错的 PCM 样本（16 位整数）
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_multistream_decoder` 创建的解码器实例
+/// * `input` - 指向 Opus 数据包的指针
+/// * `input_size` - 数据包的大小（字节数）
+/// * `output` - 输出缓冲区，用于存储交错的 PCM 样本
+/// * `output_size` - 输出缓冲区的容量（所有声道的样本总数），必须是声道数的整数倍
+/// * `fec` - 前向纠错标志，含义与 `opus_ffi_decode` 相同
+/// * `decoded_size` - 输出参数，解码后实际产生的每声道样本数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_multistream_decode"]
+pub extern "C" fn multistream_decode(
+    decoder: *mut MultistreamDecoder,
+    input: *const u8,
+    input_size: u32,
+    output: *mut i16,
+    output_size: u32,
+    fec: bool,
+    decoded_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null() || input.is_null() || output.is_null() || decoded_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
+        let size = decoder.decode(input, output, fec)?;
+        unsafe {
+            (*decoded_size) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 解码多流 Opus 数据包为交错的 PCM 样本（32 位浮点数）
+///
+/// # 参数
+///
+/// * `decoder` - 之前通过 `opus_ffi_new_multistream_decoder` 创建的解码器实例
+/// * `input` - 指向 Opus 数据包的指针
+/// * `input_size` - 数据包的大小（字节数）
+/// * `output` - 输出缓冲区，用于存储交错的浮点 PCM 样本
+/// * `output_size` - 输出缓冲区的容量（所有声道的样本总数），必须是声道数的整数倍
+/// * `fec` - 前向纠错标志
+/// * `result` - 输出参数，解码后实际产生的每声道样本数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_multistream_decode_float"]
+pub extern "C" fn multistream_decode_float(
+    decoder: *mut MultistreamDecoder,
+    input: *const u8,
+    input_size: u32,
+    output: *mut f32,
+    output_size: u32,
+    fec: bool,
+    result: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if decoder.is_null() || input.is_null() || output.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut decoder = handle::get_mut(decoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
+        let size = decoder.decode_float(input, output, fec)?;
+        unsafe {
+            (*result) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 释放多流解码器实例
+///
+/// # 参数
+///
+/// * `decoder` - 通过 `opus_ffi_new_multistream_decoder` 创建的解码器指针
+///
+/// # 安全性
+///
+/// * 如果 `decoder` 为 `NULL`，函数不会执行任何操作
+/// * 释放后，`decoder` 指针将不再有效
+/// * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `decoder` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `decoder` 不是有效的多流解码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+#[export_name = "opus_ffi_free_multistream_decoder"]
+pub extern "C" fn free_multistream_decoder(decoder: *mut MultistreamDecoder) -> c_int {
+    match handle::release(decoder) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_null_checks_multistream_decoder() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = new_multistream_decoder(6, 48000, ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+
+        let res = multistream_decode(
+            ptr::null_mut(),
+            ptr::null(),
+            0,
+            ptr::null_mut(),
+            0,
+            false,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        let mut decoder = ptr::null_mut();
+        assert_eq!(
+            new_multistream_decoder(6, 48000, &mut decoder, ptr::null_mut()),
+            0
+        );
+        assert_eq!(free_multistream_decoder(decoder), 0);
+
+        // 已释放的句柄
+        let packet = [0u8; 8];
+        let mut output = [0i16; 960 * 6];
+        let mut decoded = 0;
+        let res = multistream_decode(
+            decoder,
+            packet.as_ptr(),
+            packet.len() as u32,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            false,
+            &mut decoded,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_multistream_decoder(decoder), invalid_handle);
+
+        // 把多流编码器当作多流解码器使用
+        let mut encoder = ptr::null_mut();
+        let res = crate::multistream::encoder::new_multistream_encoder(
+            6,
+            48000,
+            2,
+            &mut encoder,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        let wrong_type = encoder as *mut MultistreamDecoder;
+        let res = multistream_decode(
+            wrong_type,
+            packet.as_ptr(),
+            packet.len() as u32,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            false,
+            &mut decoded,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_multistream_decoder(wrong_type), invalid_handle);
+        assert_eq!(
+            crate::multistream::encoder::free_multistream_encoder(encoder),
+            0
+        );
+    }
+}
@@ -0,0 +1,372 @@
+use std::ffi::c_int;
+
+use audiopus_sys::OpusMSEncoder;
+use opus_rs::Application;
+
+use super::{MultistreamLayout, MAX_SURROUND_CHANNELS};
+use crate::{
+    error::{unsupported_error, OpusError},
+    handle,
+    utils::{application_from, check, ffi_exec, frame_size, invalid_input},
+};
+
+/// 多流编码器不透明指针类型
+pub struct MultistreamEncoder {
+    ptr: *mut OpusMSEncoder,
+    layout: MultistreamLayout,
+}
+
+impl Drop for MultistreamEncoder {
+    fn drop(&mut self) {
+        unsafe { audiopus_sys::opus_multistream_encoder_destroy(self.ptr) }
+    }
+}
+
+impl MultistreamEncoder {
+    /// 按映射族 1 创建环绕声编码器，流布局由 libopus 根据声道数确定
+    pub fn new(
+        sample_rate: u32,
+        channels: u32,
+        application: Application,
+    ) -> Result<Self, OpusError> {
+        if channels == 0 || channels > MAX_SURROUND_CHANNELS {
+            return Err(unsupported_error(&format!(
+                "Unsupported surround channel count: {channels}, expected 1 to 8"
+            )));
+        }
+
+        let mut streams = 0;
+        let mut coupled_streams = 0;
+        let mut mapping = [0u8; 8];
+        let mut error = 0;
+        let ptr = unsafe {
+            audiopus_sys::opus_multistream_surround_encoder_create(
+                sample_rate as i32,
+                channels as c_int,
+                1,
+                &mut streams,
+                &mut coupled_streams,
+                mapping.as_mut_ptr(),
+                application as c_int,
+                &mut error,
+            )
+        };
+        check("opus_multistream_surround_encoder_create", error)?;
+
+        Ok(Self {
+            ptr,
+            layout: MultistreamLayout {
+                channels,
+                streams: streams as u32,
+                coupled_streams: coupled_streams as u32,
+                mapping,
+            },
+        })
+    }
+
+    /// 流布局，创建对应的解码器或写入 Ogg Opus 头部时使用
+    pub fn layout(&self) -> MultistreamLayout {
+        self.layout
+    }
+
+    /// 编码一帧交错的 16 位 PCM，返回数据包的字节数
+    pub fn encode(&mut self, input: &[i16], output: &mut [u8]) -> Result<usize, OpusError> {
+        let frame_size = frame_size(input.len(), self.layout.channels as usize)?;
+        let size = unsafe {
+            audiopus_sys::opus_multistream_encode(
+                self.ptr,
+                input.as_ptr(),
+                frame_size as c_int,
+                output.as_mut_ptr(),
+                output.len().min(i32::MAX as usize) as i32,
+            )
+        };
+
+        check("opus_multistream_encode", size)
+    }
+
+    /// 编码一帧交错的浮点 PCM，返回数据包的字节数
+    pub fn encode_float(&mut self, input: &[f32], output: &mut [u8]) -> Result<usize, OpusError> {
+        let frame_size = frame_size(input.len(), self.layout.channels as usize)?;
+        let size = unsafe {
+            audiopus_sys::opus_multistream_encode_float(
+                self.ptr,
+                input.as_ptr(),
+                frame_size as c_int,
+                output.as_mut_ptr(),
+                output.len().min(i32::MAX as usize) as i32,
+            )
+        };
+
+        check("opus_multistream_encode_float", size)
+    }
+}
+
+/// 创建新的多流（环绕声）编码器
+///
+/// # 参数
+///
+/// * `channels` - 声道数（1~8），按映射族 1 的 Vorbis 声道顺序排列，例如 6 表示 5.1，8 表示 7.1
+/// * `sample_rate` - 采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `application` - 应用模式，取值与 `opus_ffi_new_encoder` 相同
+/// * `result` - 输出参数，用于接收创建的编码器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），声道数不在 1~8 之间时返回 `OPUS_ERROR_CODE_UNSUPPORTED`
+///
+/// # 安全性
+///
+/// 调用者负责在不再需要时调用 `opus_ffi_free_multistream_encoder` 释放返回的编码器。
+///
+/// # 示例
+///
+/// ```c
+/// MultistreamEncoder *encoder = NULL;
+/// OpusError error = {0, NULL};
+/// int res = opus_ffi_new_multistream_encoder(6, 48000, 2, &encoder, &error);
+/// if (res == 0) {
+///     float pcm[960 * 6]; // 20ms @ 48kHz, 5.1 交错样本
+///     uint8_t packet[4000 * 6];
+///     size_t packet_size = 0;
+///     opus_ffi_multistream_encode_float(encoder, pcm, 960 * 6, packet, sizeof(packet), &packet_size, &error);
+///     opus_ffi_free_multistream_encoder(encoder);
+/// }
+/// ```
+#[export_name = "opus_ffi_new_multistream_encoder"]
+pub extern "C" fn new_multistream_encoder(
+    channels: u32,
+    sample_rate: u32,
+    application: u32,
+    result: *mut *mut MultistreamEncoder,
+    error: *mut OpusError,
+) -> c_int {
+    if result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mode = application_from(application)?;
+        let encoder = MultistreamEncoder::new(sample_rate, channels, mode)?;
+        unsafe {
+            *result = handle::into_handle(encoder);
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取多流编码器的流布局
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_multistream_encoder` 创建的编码器实例
+/// * `result` - 输出参数，用于接收流布局
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 映射族 1 的布局只取决于声道数，`opus_ffi_new_multistream_decoder` 会使用相同的布局
+#[export_name = "opus_ffi_multistream_encoder_get_layout"]
+pub extern "C" fn multistream_encoder_get_layout(
+    encoder: *mut MultistreamEncoder,
+    result: *mut MultistreamLayout,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let encoder = handle::get_mut(encoder)?;
+        unsafe {
+            (*result) = encoder.layout();
+        }
+
+        Ok(())
+    })
+}
+
+/// 将交错的 PCM 样本编码为多流 Opus 数据包（16 位整数输入）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_multistream_encoder` 创建的编码器实例
+/// * `input` - 指向交错 PCM 样本的指针（16 位有符号整数）
+/// * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍
+/// * `output` - 输出缓冲区，用于存储编码后的数据包
+/// * `output_size` - 输出缓冲区的容量（字节数）。建议每个声道至少 4000 字节
+/// * `encoded_size` - 输出参数，编码后实际产生的字节数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_multistream_encode"]
+pub extern "C" fn multistream_encode(
+    encoder: *mut MultistreamEncoder,
+    input: *const i16,
+    input_size: u32,
+    output: *mut u8,
+    output_size: u32,
+    encoded_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || input.is_null() || output.is_null() || encoded_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
+        let size = encoder.encode(input, output)?;
+        unsafe {
+            (*encoded_size) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 将交错的 PCM 样本编码为多流 Opus 数据包（32 位浮点数输入）
+///
+/// # 参数
+///
+/// * `encoder` - 之前通过 `opus_ffi_new_multistream_encoder` 创建的编码器实例
+/// * `input` - 指向交错 PCM 样本的指针（32 位浮点数，范围通常在 [-1.0, 1.0] 之间）
+/// * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍
+/// * `output` - 输出缓冲区，用于存储编码后的数据包
+/// * `output_size` - 输出缓冲区的容量（字节数）
+/// * `result` - 输出参数，编码后实际产生的字节数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_multistream_encode_float"]
+pub extern "C" fn multistream_encode_float(
+    encoder: *mut MultistreamEncoder,
+    input: *const f32,
+    input_size: u32,
+    output: *mut u8,
+    output_size: u32,
+    result: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if encoder.is_null() || input.is_null() || output.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut encoder = handle::get_mut(encoder)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
+        let size = encoder.encode_float(input, output)?;
+        unsafe {
+            (*result) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 释放多流编码器实例
+///
+/// # 参数
+///
+/// * `encoder` - 通过 `opus_ffi_new_multistream_encoder` 创建的编码器指针
+///
+/// # 安全性
+///
+/// * 如果 `encoder` 为 `NULL`，函数不会执行任何操作
+/// * 释放后，`encoder` 指针将不再有效
+/// * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `encoder` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `encoder` 不是有效的多流编码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+#[export_name = "opus_ffi_free_multistream_encoder"]
+pub extern "C" fn free_multistream_encoder(encoder: *mut MultistreamEncoder) -> c_int {
+    match handle::release(encoder) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_null_checks_multistream_encoder() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = new_multistream_encoder(6, 48000, 2, ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+
+        let res = multistream_encode(
+            ptr::null_mut(),
+            ptr::null(),
+            0,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        let mut encoder = ptr::null_mut();
+        assert_eq!(
+            new_multistream_encoder(6, 48000, 2, &mut encoder, ptr::null_mut()),
+            0
+        );
+        assert_eq!(free_multistream_encoder(encoder), 0);
+
+        // 已释放的句柄
+        let mut layout = MultistreamLayout::default();
+        let res = multistream_encoder_get_layout(encoder, &mut layout, ptr::null_mut());
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_multistream_encoder(encoder), invalid_handle);
+
+        // 把多流解码器当作多流编码器使用
+        let mut decoder = ptr::null_mut();
+        let res = crate::multistream::decoder::new_multistream_decoder(
+            6,
+            48000,
+            &mut decoder,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        let wrong_type = decoder as *mut MultistreamEncoder;
+        let res = multistream_encoder_get_layout(wrong_type, &mut layout, ptr::null_mut());
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_multistream_encoder(wrong_type), invalid_handle);
+        assert_eq!(
+            crate::multistream::decoder::free_multistream_decoder(decoder),
+            0
+        );
+    }
+
+    #[test]
+    fn test_invalid_channels() {
+        let mut encoder = ptr::null_mut();
+        let res = new_multistream_encoder(9, 48000, 2, &mut encoder, ptr::null_mut());
+        assert_eq!(res, crate::error::OpusErrorCode::Unsupported as c_int);
+        assert!(encoder.is_null());
+    }
+}
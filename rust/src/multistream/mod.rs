@@ -0,0 +1,123 @@
+//! 多流（环绕声）编解码，使用 Vorbis 声道顺序的映射族 1（RFC 7845 第 5.1.1.2 节）
+
+use crate::error::{unsupported_error, OpusError};
+
+pub mod decoder;
+pub mod encoder;
+
+/// 映射族 1 支持的最大声道数（7.1 环绕声）
+pub const MAX_SURROUND_CHANNELS: u32 = 8;
+
+/// 多流的流布局，与 Ogg Opus 头部中的声道映射表含义相同
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MultistreamLayout {
+    /// 声道数（1~8）
+    pub channels: u32,
+    /// 流的总数
+    pub streams: u32,
+    /// 其中立体声（耦合）流的数量
+    pub coupled_streams: u32,
+    /// 每个输出声道对应的解码声道序号，只有前 `channels` 项有效
+    pub mapping: [u8; 8],
+}
+
+/// 映射族 1 下各声道数的标准布局
+///
+/// 声道顺序：单声道；左右；左中右；左前右前左后右后；左中右左后右后；
+/// 5.1 为左中右左后右后 LFE；6.1 为左中右左侧右侧后中 LFE；7.1 为左中右左侧右侧左后右后 LFE。
+pub fn surround_layout(channels: u32) -> Result<MultistreamLayout, OpusError> {
+    let (streams, coupled_streams, order): (u32, u32, &[u8]) = match channels {
+        1 => (1, 0, &[0]),
+        2 => (1, 1, &[0, 1]),
+        3 => (2, 1, &[0, 2, 1]),
+        4 => (2, 2, &[0, 1, 2, 3]),
+        5 => (3, 2, &[0, 4, 1, 2, 3]),
+        6 => (4, 2, &[0, 4, 1, 2, 3, 5]),
+        7 => (4, 3, &[0, 4, 1, 2, 3, 5, 6]),
+        8 => (5, 3, &[0, 6, 1, 2, 3, 4, 5, 7]),
+        _ => {
+            return Err(unsupported_error(&format!(
+                "Unsupported surround channel count: {channels}, expected 1 to 8"
+            )))
+        }
+    };
+
+    let mut mapping = [0u8; 8];
+    mapping[..order.len()].copy_from_slice(order);
+
+    Ok(MultistreamLayout {
+        channels,
+        streams,
+        coupled_streams,
+        mapping,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decoder::MultistreamDecoder, encoder::MultistreamEncoder, *};
+    use crate::error::OpusErrorCode;
+    use opus_rs::{Application, ErrorCode};
+    use std::ffi::c_int;
+
+    #[test]
+    fn test_surround_layout() {
+        let layout = surround_layout(6).unwrap();
+        assert_eq!((layout.streams, layout.coupled_streams), (4, 2));
+        assert_eq!(&layout.mapping[..6], &[0, 4, 1, 2, 3, 5]);
+
+        for channels in [0, 9] {
+            let err = surround_layout(channels).unwrap_err();
+            assert_eq!(err.code, OpusErrorCode::Unsupported as c_int);
+        }
+    }
+
+    #[test]
+    fn test_surround_roundtrip() {
+        for channels in [6, 8] {
+            let mut encoder = MultistreamEncoder::new(48000, channels, Application::Audio).unwrap();
+            assert_eq!(encoder.layout(), surround_layout(channels).unwrap());
+            let mut decoder = MultistreamDecoder::new(48000, channels).unwrap();
+
+            let channels = channels as usize;
+            // 每个声道使用不同频率的低频正弦波（LFE 声道只保留低频），检查每个声道都有输出
+            let pcm: Vec<f32> = (0..960 * channels)
+                .map(|i| {
+                    let (n, ch) = (i / channels, i % channels);
+                    (n as f32 * 0.003 * (ch + 1) as f32).sin() * 0.5
+                })
+                .collect();
+            let mut packet = [0u8; 4000 * 8];
+            let mut output = vec![0f32; 960 * channels];
+            let mut decoded = 0;
+            for _ in 0..5 {
+                let size = encoder.encode_float(&pcm, &mut packet).unwrap();
+                decoded = decoder
+                    .decode_float(&packet[..size], &mut output, false)
+                    .unwrap();
+            }
+            assert_eq!(decoded, 960);
+
+            let energy = |ch: usize| -> f32 {
+                output
+                    .iter()
+                    .skip(ch)
+                    .step_by(channels)
+                    .map(|s| s * s)
+                    .sum()
+            };
+            for ch in 0..channels {
+                assert!(energy(ch) > 1.0, "channel {ch} is silent");
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_frame_size() {
+        let mut encoder = MultistreamEncoder::new(48000, 6, Application::Audio).unwrap();
+        let mut packet = [0u8; 4000];
+        let err = encoder.encode(&[0i16; 961], &mut packet).unwrap_err();
+        assert_eq!(err.code, ErrorCode::BadArg as c_int);
+    }
+}
@@ -0,0 +1,272 @@
+//! Ogg Opus 容器（RFC 3533 / RFC 7845）的公共部分：页结构、CRC 校验和 Opus 头
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+pub mod reader;
+pub mod writer;
+
+/// Ogg Opus 的粒度位置（granule position）始终以 48kHz 计数
+pub(crate) const GRANULE_RATE: u32 = 48000;
+
+pub(crate) const PAGE_CAPTURE: &[u8; 4] = b"OggS";
+pub(crate) const OPUS_HEAD_MAGIC: &[u8; 8] = b"OpusHead";
+pub(crate) const OPUS_TAGS_MAGIC: &[u8; 8] = b"OpusTags";
+
+pub(crate) const FLAG_CONTINUED: u8 = 0x01;
+pub(crate) const FLAG_BOS: u8 = 0x02;
+pub(crate) const FLAG_EOS: u8 = 0x04;
+
+/// 页头固定部分的长度（不含分段表）
+pub(crate) const PAGE_HEADER_SIZE: usize = 27;
+const MAX_SEGMENTS: usize = 255;
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut r = (i as u32) << 24;
+        let mut j = 0;
+        while j < 8 {
+            r = if r & 0x8000_0000 != 0 {
+                (r << 1) ^ 0x04c1_1db7
+            } else {
+                r << 1
+            };
+            j += 1;
+        }
+        table[i] = r;
+        i += 1;
+    }
+    table
+};
+
+/// 计算 Ogg 页的 CRC32（多项式 0x04c11db7，初始值 0，不反转）
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    crc32_update(0, data)
+}
+
+fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    data.iter().fold(crc, |crc, &b| {
+        (crc << 8) ^ CRC_TABLE[((crc >> 24) as u8 ^ b) as usize]
+    })
+}
+
+/// 从流中读出的一个 Ogg 页
+pub(crate) struct Page {
+    /// 页在流中的起始字节偏移
+    pub(crate) offset: u64,
+    pub(crate) flags: u8,
+    pub(crate) granule: i64,
+    pub(crate) serial: u32,
+    pub(crate) sequence: u32,
+    pub(crate) lacing: Vec<u8>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Page {
+    /// 紧随本页之后的字节偏移
+    pub(crate) fn end(&self) -> u64 {
+        self.offset + (PAGE_HEADER_SIZE + self.lacing.len() + self.body.len()) as u64
+    }
+}
+
+/// 从当前位置读取下一个有效的 Ogg 页
+///
+/// 遇到非页数据、CRC 不匹配或版本不支持的页时，会向后逐字节查找下一个页起始标志重新同步。
+/// 到达流末尾（包括末尾被截断的页）时返回 `None`。
+pub(crate) fn read_page<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Page>> {
+    let mut window = [0u8; 4];
+    let mut filled = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        if reader.read(&mut byte)? == 0 {
+            return Ok(None);
+        }
+        window = [window[1], window[2], window[3], byte[0]];
+        filled += 1;
+        if filled < 4 || &window != PAGE_CAPTURE {
+            continue;
+        }
+
+        let start = reader.stream_position()? - 4;
+        match read_page_after_capture(reader, start) {
+            Ok(Some(page)) => return Ok(Some(page)),
+            Ok(None) => {
+                reader.seek(SeekFrom::Start(start + 1))?;
+                filled = 0;
+            }
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// 读取页起始标志之后的部分并校验，校验失败返回 `None`
+fn read_page_after_capture<R: Read>(reader: &mut R, offset: u64) -> io::Result<Option<Page>> {
+    let mut header = [0u8; PAGE_HEADER_SIZE];
+    header[..4].copy_from_slice(PAGE_CAPTURE);
+    reader.read_exact(&mut header[4..])?;
+    if header[4] != 0 {
+        return Ok(None);
+    }
+
+    let mut lacing = vec![0u8; header[26] as usize];
+    reader.read_exact(&mut lacing)?;
+    let mut body = vec![0u8; lacing.iter().map(|&l| l as usize).sum()];
+    reader.read_exact(&mut body)?;
+
+    let expected = u32::from_le_bytes(header[22..26].try_into().unwrap());
+    header[22..26].copy_from_slice(&[0; 4]);
+    let crc = [&header[..], &lacing, &body]
+        .iter()
+        .fold(0u32, |crc, data| crc32_update(crc, data));
+    if crc != expected {
+        return Ok(None);
+    }
+
+    Ok(Some(Page {
+        offset,
+        flags: header[5],
+        granule: i64::from_le_bytes(header[6..14].try_into().unwrap()),
+        serial: u32::from_le_bytes(header[14..18].try_into().unwrap()),
+        sequence: u32::from_le_bytes(header[18..22].try_into().unwrap()),
+        lacing,
+        body,
+    }))
+}
+
+/// 将数据包按 Ogg 分段规则组装成页
+pub(crate) struct PageWriter {
+    serial: u32,
+    sequence: u32,
+    segments: Vec<u8>,
+    body: Vec<u8>,
+    packets: usize,
+}
+
+impl PageWriter {
+    pub(crate) fn new(serial: u32) -> Self {
+        Self {
+            serial,
+            sequence: 0,
+            segments: Vec::with_capacity(MAX_SEGMENTS),
+            body: Vec::new(),
+            packets: 0,
+        }
+    }
+
+    /// 当前页中已完整放入的数据包数量
+    pub(crate) fn packets(&self) -> usize {
+        self.packets
+    }
+
+    /// 判断当前页是否还能完整容纳一个长度为 `len` 的数据包
+    pub(crate) fn fits(&self, len: usize) -> bool {
+        self.segments.len() + len / 255 < MAX_SEGMENTS
+    }
+
+    /// 向当前页追加一个完整的数据包，调用前需通过 `fits` 确认空间足够
+    pub(crate) fn push(&mut self, packet: &[u8]) {
+        let mut remaining = packet.len();
+        while remaining >= 255 {
+            self.segments.push(255);
+            remaining -= 255;
+        }
+        self.segments.push(remaining as u8);
+        self.body.extend_from_slice(packet);
+        self.packets += 1;
+    }
+
+    /// 输出当前页并开始新的一页
+    pub(crate) fn flush(&mut self, granule: i64, flags: u8) -> Vec<u8> {
+        let mut page = Vec::with_capacity(PAGE_HEADER_SIZE + self.segments.len() + self.body.len());
+        page.extend_from_slice(PAGE_CAPTURE);
+        page.push(0); // stream_structure_version
+        page.push(flags);
+        page.extend_from_slice(&granule.to_le_bytes());
+        page.extend_from_slice(&self.serial.to_le_bytes());
+        page.extend_from_slice(&self.sequence.to_le_bytes());
+        page.extend_from_slice(&[0; 4]); // CRC 占位
+        page.push(self.segments.len() as u8);
+        page.extend_from_slice(&self.segments);
+        page.extend_from_slice(&self.body);
+
+        let crc = crc32(&page);
+        page[22..26].copy_from_slice(&crc.to_le_bytes());
+
+        self.sequence += 1;
+        self.segments.clear();
+        self.body.clear();
+        self.packets = 0;
+
+        page
+    }
+}
+
+/// 构造 OpusHead 标识头（映射族 0，仅支持单声道和立体声）
+pub(crate) fn opus_head(
+    channels: u8,
+    pre_skip: u16,
+    input_sample_rate: u32,
+    output_gain: i16,
+) -> Vec<u8> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(OPUS_HEAD_MAGIC);
+    head.push(1); // version
+    head.push(channels);
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&input_sample_rate.to_le_bytes());
+    head.extend_from_slice(&output_gain.to_le_bytes());
+    head.push(0); // channel mapping family
+    head
+}
+
+/// 构造 OpusTags 注释头
+pub(crate) fn opus_tags(vendor: &str, comments: &[String]) -> Vec<u8> {
+    let mut tags = Vec::new();
+    tags.extend_from_slice(OPUS_TAGS_MAGIC);
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor.as_bytes());
+    tags.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+    for comment in comments {
+        tags.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+        tags.extend_from_slice(comment.as_bytes());
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_layout() {
+        let mut writer = PageWriter::new(0x1234_5678);
+        assert!(writer.fits(600));
+        writer.push(&[7u8; 600]);
+        writer.push(&[]);
+        assert_eq!(writer.packets(), 2);
+
+        let page = writer.flush(960, FLAG_BOS);
+        assert_eq!(&page[..4], PAGE_CAPTURE);
+        assert_eq!(page[5], FLAG_BOS);
+        assert_eq!(i64::from_le_bytes(page[6..14].try_into().unwrap()), 960);
+        assert_eq!(
+            u32::from_le_bytes(page[14..18].try_into().unwrap()),
+            0x1234_5678
+        );
+        // 600 = 255 + 255 + 90，空数据包占一个 0 分段
+        assert_eq!(&page[26..31], &[4, 255, 255, 90, 0]);
+        assert_eq!(page.len(), PAGE_HEADER_SIZE + 4 + 600);
+
+        let mut zeroed = page.clone();
+        zeroed[22..26].copy_from_slice(&[0; 4]);
+        assert_eq!(
+            u32::from_le_bytes(page[22..26].try_into().unwrap()),
+            crc32(&zeroed)
+        );
+
+        let next = writer.flush(960, FLAG_EOS);
+        assert_eq!(u32::from_le_bytes(next[18..22].try_into().unwrap()), 1);
+    }
+}
@@ -0,0 +1,1204 @@
+use std::{
+    collections::VecDeque,
+    ffi::{c_char, c_int, CStr, CString},
+    fs::File,
+    io::{BufReader, Cursor, Seek, SeekFrom},
+    path::Path,
+};
+
+use opus_rs::{Channels, Decoder};
+
+use super::{
+    read_page, Page, FLAG_BOS, FLAG_CONTINUED, FLAG_EOS, GRANULE_RATE, OPUS_HEAD_MAGIC,
+    OPUS_TAGS_MAGIC,
+};
+use crate::{
+    error::{format_error, OpusError},
+    handle,
+    utils::{buffer_too_small_error, ffi_exec, invalid_input, invalid_input_error, Source},
+};
+
+/// 单个 Opus 数据包解码后的最大每声道样本数（120ms @ 48kHz）
+const MAX_FRAME_SIZE: usize = 5760;
+/// 定位后在目标位置之前额外解码并丢弃的时长（80ms @ 48kHz），让解码器状态收敛
+const PRE_ROLL: i64 = 3840;
+/// 查找最后一页时每次从文件末尾向前读取的初始长度
+const END_SEARCH_CHUNK: u64 = 64 * 1024;
+
+/// OpusHead 标识头中的流参数
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OggOpusHead {
+    /// 声道数（1 或 2）
+    pub channels: u32,
+    /// 解码时需要丢弃的起始样本数（48kHz）
+    pub pre_skip: u32,
+    /// 编码前原始音频的采样率（Hz），仅供参考，0 表示未知
+    pub input_sample_rate: u32,
+    /// 输出增益（Q7.8 格式的 dB 值），读取时已自动应用
+    pub output_gain: i32,
+}
+
+impl OggOpusHead {
+    fn parse(data: &[u8]) -> Result<Self, OpusError> {
+        if data.len() < 19 || &data[..8] != OPUS_HEAD_MAGIC {
+            return Err(format_error("Invalid OpusHead packet"));
+        }
+        // 只支持主版本号 0（版本 0~15）
+        if data[8] & 0xf0 != 0 {
+            return Err(format_error("Unsupported OpusHead version"));
+        }
+        if data[18] != 0 {
+            return Err(format_error("Unsupported channel mapping family"));
+        }
+
+        let channels = data[9] as u32;
+        if !(1..=2).contains(&channels) {
+            return Err(format_error("Invalid channel count in OpusHead"));
+        }
+
+        Ok(Self {
+            channels,
+            pre_skip: u16::from_le_bytes([data[10], data[11]]) as u32,
+            input_sample_rate: u32::from_le_bytes(data[12..16].try_into().unwrap()),
+            output_gain: i16::from_le_bytes([data[16], data[17]]) as i32,
+        })
+    }
+}
+
+/// 解析 OpusTags 注释头，返回厂商字符串和注释列表
+fn parse_tags(data: &[u8]) -> Result<(String, Vec<String>), OpusError> {
+    fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], OpusError> {
+        if data.len() < len {
+            return Err(format_error("Truncated OpusTags packet"));
+        }
+        let (head, rest) = data.split_at(len);
+        *data = rest;
+        Ok(head)
+    }
+    fn take_string(data: &mut &[u8]) -> Result<String, OpusError> {
+        let len = u32::from_le_bytes(take(data, 4)?.try_into().unwrap());
+        Ok(String::from_utf8_lossy(take(data, len as usize)?).into_owned())
+    }
+
+    let mut data = data;
+    if take(&mut data, 8)? != OPUS_TAGS_MAGIC {
+        return Err(format_error("Invalid OpusTags packet"));
+    }
+    let vendor = take_string(&mut data)?;
+    let count = u32::from_le_bytes(take(&mut data, 4)?.try_into().unwrap());
+    let comments = (0..count)
+        .map(|_| take_string(&mut data))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((vendor, comments))
+}
+
+/// 从 Ogg 页中拆出的完整数据包
+struct Packet {
+    data: Vec<u8>,
+    // 数据包所在页（完成于该页）的粒度位置
+    granule: i64,
+    // 是否位于流的最后一页
+    last: bool,
+}
+
+/// Ogg Opus 读取器不透明指针类型
+pub struct OggOpusReader {
+    source: Source,
+    serial: u32,
+    head: OggOpusHead,
+    vendor: String,
+    comments: Vec<String>,
+    decoder: Decoder,
+    channels: usize,
+    sample_rate: u32,
+    // 数据包重组状态
+    packets: VecDeque<Packet>,
+    partial: Vec<u8>,
+    next_sequence: Option<u32>,
+    eos: bool,
+    // 第一个音频页的字节偏移
+    data_offset: u64,
+    // 以下粒度均以 48kHz 计
+    // 第一个音频数据包起始处的粒度位置
+    start: i64,
+    // 最后一页的粒度位置，首次查询时长时读取
+    end: Option<i64>,
+    // 下一个待解码数据包起始处的粒度位置
+    granule: i64,
+    // 剩余需丢弃的每声道样本数（输出采样率）
+    skip: usize,
+    // 已解码但尚未读出的交错 PCM 样本
+    pcm: Vec<f32>,
+    pcm_offset: usize,
+    // `read` 转换为 16 位 PCM 前使用的浮点缓冲区，在多次调用之间复用
+    scratch: Vec<f32>,
+}
+
+impl OggOpusReader {
+    /// 打开 Ogg Opus 文件，以 `sample_rate` 输出解码后的 PCM
+    pub fn open<P: AsRef<Path>>(path: P, sample_rate: u32) -> Result<Self, OpusError> {
+        let file = File::open(path)?;
+        Self::new(Source::File(BufReader::new(file)), sample_rate)
+    }
+
+    /// 从内存中的 Ogg Opus 数据创建读取器，以 `sample_rate` 输出解码后的 PCM
+    pub fn from_bytes(data: Vec<u8>, sample_rate: u32) -> Result<Self, OpusError> {
+        Self::new(Source::Memory(Cursor::new(data)), sample_rate)
+    }
+
+    fn new(mut source: Source, sample_rate: u32) -> Result<Self, OpusError> {
+        // 查找首个以 OpusHead 开头的 BOS 页，忽略复用在同一文件中的其他逻辑流
+        let first = loop {
+            match read_page(&mut source)? {
+                Some(page)
+                    if page.flags & FLAG_BOS != 0 && page.body.starts_with(OPUS_HEAD_MAGIC) =>
+                {
+                    break page
+                }
+                Some(_) => continue,
+                None => return Err(format_error("Not an Ogg Opus stream")),
+            }
+        };
+        if first.lacing.len() != 1 || first.lacing[0] == 255 {
+            return Err(format_error("OpusHead must be alone on the first page"));
+        }
+
+        let head = OggOpusHead::parse(&first.body)?;
+        let channels = if head.channels == 2 {
+            Channels::Stereo
+        } else {
+            Channels::Mono
+        };
+        let mut decoder = Decoder::new(sample_rate, channels)?;
+        decoder.set_gain(head.output_gain)?;
+
+        let mut reader = Self {
+            source,
+            serial: first.serial,
+            head,
+            vendor: String::new(),
+            comments: Vec::new(),
+            decoder,
+            channels: head.channels as usize,
+            sample_rate,
+            packets: VecDeque::new(),
+            partial: Vec::new(),
+            next_sequence: Some(first.sequence.wrapping_add(1)),
+            eos: first.flags & FLAG_EOS != 0,
+            data_offset: 0,
+            start: 0,
+            end: None,
+            granule: 0,
+            skip: 0,
+            pcm: Vec::new(),
+            pcm_offset: 0,
+            scratch: Vec::new(),
+        };
+
+        let tags = reader
+            .next_packet()?
+            .ok_or_else(|| format_error("Missing OpusTags packet"))?;
+        (reader.vendor, reader.comments) = parse_tags(&tags.data)?;
+
+        // 音频数据从 OpusTags 之后的新页开始
+        reader.data_offset = reader.source.stream_position()?;
+        reader.start = reader.resolve_granule(0)?;
+        reader.granule = reader.start;
+        reader.skip =
+            reader.samples_between(reader.start, reader.start + head.pre_skip as i64) as usize;
+
+        Ok(reader)
+    }
+
+    /// OpusHead 中的流参数
+    pub fn head(&self) -> &OggOpusHead {
+        &self.head
+    }
+
+    /// OpusTags 中的厂商字符串
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    /// OpusTags 中的用户注释，形如 `TITLE=...`
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// 流的总时长（每声道样本数，输出采样率），不含预跳过部分
+    pub fn duration(&mut self) -> Result<u64, OpusError> {
+        let end = match self.end {
+            Some(end) => end,
+            None => {
+                let end = self.find_end_granule()?;
+                self.end = Some(end);
+                end
+            }
+        };
+
+        Ok(self.samples_between(self.start + self.head.pre_skip as i64, end))
+    }
+
+    /// 流的总时长（毫秒）
+    pub fn duration_ms(&mut self) -> Result<u64, OpusError> {
+        Ok(self.duration()? * 1000 / self.sample_rate as u64)
+    }
+
+    /// 定位到指定的样本位置（每声道样本数，输出采样率），之后的读取从该位置开始
+    ///
+    /// 二分查找目标位置之前的页，重置解码器后从目标前至少 80ms 处开始解码，
+    /// 丢弃目标之前的样本，因此定位是样本精确的。超出流末尾时后续读取直接返回 0。
+    pub fn seek(&mut self, sample: u64) -> Result<(), OpusError> {
+        let target = self.start
+            + self.head.pre_skip as i64
+            + (sample * GRANULE_RATE as u64 / self.sample_rate as u64) as i64;
+        let (offset, granule) = self.bisect((target - PRE_ROLL).max(self.start))?;
+
+        self.source.seek(SeekFrom::Start(offset))?;
+        self.decoder.reset_state()?;
+        self.packets.clear();
+        self.partial.clear();
+        // 定位后第一页如果是续页，其中残缺的数据包会被丢弃
+        self.next_sequence = None;
+        self.eos = false;
+        self.pcm.clear();
+        self.pcm_offset = 0;
+
+        self.granule = self.resolve_granule(granule)?;
+        self.skip = self.samples_between(self.granule, target) as usize;
+
+        Ok(())
+    }
+
+    /// 定位到指定的时间位置（毫秒）
+    pub fn seek_ms(&mut self, ms: u64) -> Result<(), OpusError> {
+        self.seek(ms * self.sample_rate as u64 / 1000)
+    }
+
+    /// 读取交错的 16 位 PCM 样本，返回每声道样本数，0 表示已到达流末尾
+    pub fn read(&mut self, output: &mut [i16]) -> Result<usize, OpusError> {
+        let mut scratch = std::mem::take(&mut self.scratch);
+        scratch.resize(output.len(), 0.0);
+        let result = self.read_float(&mut scratch);
+        if let Ok(samples) = result {
+            for (out, &sample) in output.iter_mut().zip(&scratch[..samples * self.channels]) {
+                *out = (sample * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
+            }
+        }
+        self.scratch = scratch;
+
+        result
+    }
+
+    /// 读取交错的浮点 PCM 样本，返回每声道样本数，0 表示已到达流末尾
+    ///
+    /// 输出缓冲区容纳不下一个完整的多声道样本时返回 `BufferTooSmall`，以免与流末尾混淆
+    pub fn read_float(&mut self, output: &mut [f32]) -> Result<usize, OpusError> {
+        if output.len() < self.channels {
+            return Err(buffer_too_small_error(self.channels, output.len()));
+        }
+
+        let capacity = output.len() / self.channels * self.channels;
+        let mut written = 0;
+        while written < capacity {
+            if self.pcm_offset == self.pcm.len() && !self.decode_next()? {
+                break;
+            }
+            let count = (capacity - written).min(self.pcm.len() - self.pcm_offset);
+            output[written..written + count]
+                .copy_from_slice(&self.pcm[self.pcm_offset..self.pcm_offset + count]);
+            self.pcm_offset += count;
+            written += count;
+        }
+
+        Ok(written / self.channels)
+    }
+
+    /// 解码下一个音频数据包到内部缓冲区，应用预跳过和结束裁剪，没有更多数据包时返回 `false`
+    fn decode_next(&mut self) -> Result<bool, OpusError> {
+        let Some(packet) = self.next_packet()? else {
+            return Ok(false);
+        };
+
+        let mut buffer = vec![0f32; MAX_FRAME_SIZE * self.channels];
+        let decoded = self
+            .decoder
+            .decode_float(&packet.data, &mut buffer, false)?;
+        let mut samples = decoded;
+        if packet.last {
+            // 最后一页的粒度位置标记了流的实际结束位置，裁掉编码时补齐的部分
+            samples = samples.min(self.samples_between(self.granule, packet.granule) as usize);
+        }
+        self.granule += (decoded as u64 * GRANULE_RATE as u64 / self.sample_rate as u64) as i64;
+
+        let skipped = self.skip.min(samples);
+        self.skip -= skipped;
+        self.pcm.clear();
+        self.pcm
+            .extend_from_slice(&buffer[skipped * self.channels..samples * self.channels]);
+        self.pcm_offset = 0;
+
+        Ok(true)
+    }
+
+    /// 两个粒度位置之间的每声道样本数（输出采样率），`to` 不大于 `from` 时为 0
+    fn samples_between(&self, from: i64, to: i64) -> u64 {
+        (to - from).max(0) as u64 * self.sample_rate as u64 / GRANULE_RATE as u64
+    }
+
+    /// 确定下一个待解码数据包起始处的粒度位置
+    ///
+    /// 读入到第一个带粒度位置的页为止，用该页的粒度位置减去队列中所有数据包的时长。
+    /// 如果该页是最后一页（粒度位置可能经过结束裁剪，RFC 7845 约定此时无法反推），
+    /// 则使用调用者给出的下限 `floor`。
+    fn resolve_granule(&mut self, floor: i64) -> Result<i64, OpusError> {
+        while !self.eos && self.packets.back().is_none_or(|p| p.granule == -1) {
+            self.read_next_page()?;
+        }
+        let Some(last) = self.packets.back() else {
+            return Ok(floor);
+        };
+        if last.last {
+            return Ok(floor);
+        }
+
+        let mut duration = 0;
+        for packet in &self.packets {
+            duration += opus_rs::packet::get_nb_samples(&packet.data, GRANULE_RATE)? as i64;
+        }
+
+        Ok((last.granule - duration).max(floor))
+    }
+
+    /// 二分查找粒度位置不超过 `target` 的最后一个音频页，返回紧随其后的字节偏移和该页的粒度位置
+    fn bisect(&mut self, target: i64) -> Result<(u64, i64), OpusError> {
+        let mut best = (self.data_offset, self.start);
+        let mut low = self.data_offset;
+        let mut high = self.source.seek(SeekFrom::End(0))?;
+        while low < high {
+            let middle = low + (high - low) / 2;
+            self.source.seek(SeekFrom::Start(middle))?;
+            let page = loop {
+                match read_page(&mut self.source)? {
+                    Some(page) if page.offset >= high => break None,
+                    Some(page) if page.serial == self.serial && page.granule != -1 => {
+                        break Some(page)
+                    }
+                    Some(_) => continue,
+                    None => break None,
+                }
+            };
+            match page {
+                Some(page) if page.granule <= target => {
+                    best = (page.end(), page.granule);
+                    low = page.end();
+                }
+                _ => high = middle,
+            }
+        }
+
+        Ok(best)
+    }
+
+    /// 从文件末尾向前查找本逻辑流最后一页的粒度位置，不改变当前读取位置
+    fn find_end_granule(&mut self) -> Result<i64, OpusError> {
+        let position = self.source.stream_position()?;
+        let length = self.source.seek(SeekFrom::End(0))?;
+
+        let mut end = self.start;
+        let mut chunk = END_SEARCH_CHUNK;
+        loop {
+            let from = length.saturating_sub(chunk).max(self.data_offset);
+            self.source.seek(SeekFrom::Start(from))?;
+            let mut found = None;
+            while let Some(page) = read_page(&mut self.source)? {
+                if page.serial == self.serial && page.granule != -1 {
+                    found = Some(page.granule);
+                }
+            }
+            if let Some(granule) = found {
+                end = granule;
+                break;
+            }
+            if from == self.data_offset {
+                break;
+            }
+            chunk *= 2;
+        }
+
+        self.source.seek(SeekFrom::Start(position))?;
+        Ok(end)
+    }
+
+    /// 取出下一个完整的数据包，必要时继续读取页
+    fn next_packet(&mut self) -> Result<Option<Packet>, OpusError> {
+        while self.packets.is_empty() && !self.eos {
+            self.read_next_page()?;
+        }
+
+        Ok(self.packets.pop_front())
+    }
+
+    /// 读取本逻辑流的下一页，并把其中完成的数据包放入队列
+    fn read_next_page(&mut self) -> Result<(), OpusError> {
+        let page = loop {
+            match read_page(&mut self.source)? {
+                Some(page) if page.serial == self.serial => break page,
+                Some(_) => continue,
+                None => {
+                    // 流在没有 EOS 页的情况下结束（例如录音被中断），无法进行结束裁剪
+                    self.eos = true;
+                    return Ok(());
+                }
+            }
+        };
+        self.push_page(page);
+
+        Ok(())
+    }
+
+    fn push_page(&mut self, page: Page) {
+        // 页序号不连续说明中间有页丢失或损坏，跨页的数据包不完整，需要丢弃
+        let continuous = self.next_sequence == Some(page.sequence);
+        self.next_sequence = Some(page.sequence.wrapping_add(1));
+        let mut discard =
+            page.flags & FLAG_CONTINUED != 0 && (!continuous || self.partial.is_empty());
+        if page.flags & FLAG_CONTINUED == 0 {
+            self.partial.clear();
+        }
+
+        let mut offset = 0;
+        for &len in &page.lacing {
+            let segment = &page.body[offset..offset + len as usize];
+            offset += len as usize;
+            if !discard {
+                self.partial.extend_from_slice(segment);
+            }
+            if len < 255 {
+                let data = std::mem::take(&mut self.partial);
+                if !discard {
+                    self.packets.push_back(Packet {
+                        data,
+                        granule: page.granule,
+                        last: page.flags & FLAG_EOS != 0,
+                    });
+                }
+                discard = false;
+            }
+        }
+        if discard {
+            self.partial.clear();
+        }
+        if page.flags & FLAG_EOS != 0 {
+            self.eos = true;
+        }
+    }
+}
+
+/// 打开 Ogg Opus 文件用于解码
+///
+/// # 参数
+///
+/// * `path` - 输入文件路径（UTF-8 编码的 C 字符串）
+/// * `sample_rate` - 输出 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `result` - 输出参数，用于接收创建的读取器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 安全性
+///
+/// 调用者负责在不再需要时调用 `opus_ffi_free_ogg_reader` 释放返回的读取器。
+///
+/// # 注意事项
+///
+/// * 打开时会读取并校验 OpusHead 和 OpusTags，文件不是 Ogg Opus 格式时返回错误
+/// * 仅支持映射族 0（单声道和立体声）
+///
+/// # 示例
+///
+/// ```c
+/// OggOpusReader *reader = NULL;
+/// OpusError error = {0, NULL};
+/// int res = opus_ffi_ogg_reader_open_file("/tmp/voice.opus", 48000, &reader, &error);
+/// if (res == 0) {
+///     int16_t pcm[960 * 2];
+///     size_t samples = 0;
+///     while (opus_ffi_ogg_reader_read(reader, pcm, 960 * 2, &samples, &error) == 0 && samples > 0) {
+///         // 使用 pcm...
+///     }
+///     opus_ffi_free_ogg_reader(reader);
+/// }
+/// ```
+#[export_name = "opus_ffi_ogg_reader_open_file"]
+pub extern "C" fn ogg_reader_open_file(
+    path: *const c_char,
+    sample_rate: u32,
+    result: *mut *mut OggOpusReader,
+    error: *mut OpusError,
+) -> c_int {
+    if path.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return invalid_input(error),
+    };
+
+    ffi_exec(error, || {
+        let reader = OggOpusReader::open(path, sample_rate)?;
+        unsafe {
+            *result = handle::into_handle(reader);
+        }
+
+        Ok(())
+    })
+}
+
+/// 从内存中的 Ogg Opus 数据创建读取器
+///
+/// # 参数
+///
+/// * `data` - 指向 Ogg Opus 数据的指针
+/// * `data_size` - 数据长度（字节数）
+/// * `sample_rate` - 输出 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `result` - 输出参数，用于接收创建的读取器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 数据会被复制，调用返回后即可释放 `data`
+#[export_name = "opus_ffi_ogg_reader_open_memory"]
+pub extern "C" fn ogg_reader_open_memory(
+    data: *const u8,
+    data_size: usize,
+    sample_rate: u32,
+    result: *mut *mut OggOpusReader,
+    error: *mut OpusError,
+) -> c_int {
+    if data.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let data = unsafe { std::slice::from_raw_parts(data, data_size) }.to_vec();
+        let reader = OggOpusReader::from_bytes(data, sample_rate)?;
+        unsafe {
+            *result = handle::into_handle(reader);
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取 OpusHead 中的流参数
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `result` - 输出参数，用于接收流参数。`channels` 即 `opus_ffi_ogg_reader_read` 输出的声道数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_ogg_reader_get_head"]
+pub extern "C" fn ogg_reader_get_head(
+    reader: *mut OggOpusReader,
+    result: *mut OggOpusHead,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let reader = handle::get_mut(reader)?;
+        unsafe {
+            (*result) = *reader.head();
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取 OpusTags 中的厂商字符串
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `result` - 输出参数，用于接收厂商字符串，需使用 `opus_ffi_free_c_string` 释放
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_ogg_reader_get_vendor"]
+pub extern "C" fn ogg_reader_get_vendor(
+    reader: *mut OggOpusReader,
+    result: *mut *mut c_char,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let reader = handle::get_mut(reader)?;
+        into_c_string(reader.vendor(), result);
+
+        Ok(())
+    })
+}
+
+/// 获取 OpusTags 中用户注释的数量
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `result` - 输出参数，用于接收注释数量
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_ogg_reader_get_comment_count"]
+pub extern "C" fn ogg_reader_get_comment_count(
+    reader: *mut OggOpusReader,
+    result: *mut u32,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let reader = handle::get_mut(reader)?;
+        unsafe {
+            (*result) = reader.comments().len() as u32;
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取 OpusTags 中的一条用户注释
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `index` - 注释序号，范围为 `[0, opus_ffi_ogg_reader_get_comment_count)`
+/// * `result` - 输出参数，用于接收注释（形如 `TITLE=...`），需使用 `opus_ffi_free_c_string` 释放
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），序号越界时返回无效输入错误
+#[export_name = "opus_ffi_ogg_reader_get_comment"]
+pub extern "C" fn ogg_reader_get_comment(
+    reader: *mut OggOpusReader,
+    index: u32,
+    result: *mut *mut c_char,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let reader = handle::get_mut(reader)?;
+        let comment = reader
+            .comments()
+            .get(index as usize)
+            .ok_or_else(invalid_input_error)?;
+        into_c_string(comment, result);
+
+        Ok(())
+    })
+}
+
+fn into_c_string(value: &str, result: *mut *mut c_char) {
+    // 注释中可能含有 NUL 字符，截断到第一个 NUL 为止
+    let value = value.split('\0').next().unwrap_or_default();
+    let value = CString::new(value).unwrap_or_default();
+    unsafe {
+        (*result) = value.into_raw();
+    }
+}
+
+/// 从 Ogg Opus 读取器读取解码后的 PCM 样本（16 位整数）
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `output` - 指向输出缓冲区的指针，用于存储交错的 PCM 样本（16 位有符号整数）
+/// * `output_size` - 输出缓冲区的大小（样本总数，所有声道），至少为声道数，不必是声道数的整数倍
+/// * `samples_read` - 输出参数，实际读取的每声道样本数，`0` 表示已到达流末尾
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），`output_size` 小于声道数时返回 `OPUS_ERROR_CODE_BUFFER_TOO_SMALL`
+///
+/// # 注意事项
+///
+/// * 开头的预跳过样本和结尾补齐的样本已被裁掉，OpusHead 中的输出增益已被应用
+/// * 除非到达流末尾，否则总是填满输出缓冲区（按声道数向下取整）
+#[export_name = "opus_ffi_ogg_reader_read"]
+pub extern "C" fn ogg_reader_read(
+    reader: *mut OggOpusReader,
+    output: *mut i16,
+    output_size: u32,
+    samples_read: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || output.is_null() || samples_read.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut reader = handle::get_mut(reader)?;
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
+        let samples = reader.read(output)?;
+        unsafe {
+            (*samples_read) = samples;
+        }
+
+        Ok(())
+    })
+}
+
+/// 从 Ogg Opus 读取器读取解码后的 PCM 样本（32 位浮点数）
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `output` - 指向输出缓冲区的指针，用于存储交错的 PCM 样本（32 位浮点数）
+/// * `output_size` - 输出缓冲区的大小（样本总数，所有声道），至少为声道数，不必是声道数的整数倍
+/// * `samples_read` - 输出参数，实际读取的每声道样本数，`0` 表示已到达流末尾
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），`output_size` 小于声道数时返回 `OPUS_ERROR_CODE_BUFFER_TOO_SMALL`
+#[export_name = "opus_ffi_ogg_reader_read_float"]
+pub extern "C" fn ogg_reader_read_float(
+    reader: *mut OggOpusReader,
+    output: *mut f32,
+    output_size: u32,
+    samples_read: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || output.is_null() || samples_read.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut reader = handle::get_mut(reader)?;
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size as usize) };
+        let samples = reader.read_float(output)?;
+        unsafe {
+            (*samples_read) = samples;
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取 Ogg Opus 流的总时长（样本数）
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `result` - 输出参数，每声道样本数（输出采样率），不含开头的预跳过部分
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 时长由最后一页的粒度位置计算，首次调用时需要读取文件末尾，之后使用缓存的结果
+/// * 不影响当前的读取位置
+#[export_name = "opus_ffi_ogg_reader_get_duration"]
+pub extern "C" fn ogg_reader_get_duration(
+    reader: *mut OggOpusReader,
+    result: *mut u64,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut reader = handle::get_mut(reader)?;
+        let duration = reader.duration()?;
+        unsafe {
+            (*result) = duration;
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取 Ogg Opus 流的总时长（毫秒）
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `result` - 输出参数，总时长（毫秒）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_ogg_reader_get_duration_ms"]
+pub extern "C" fn ogg_reader_get_duration_ms(
+    reader: *mut OggOpusReader,
+    result: *mut u64,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut reader = handle::get_mut(reader)?;
+        let duration = reader.duration_ms()?;
+        unsafe {
+            (*result) = duration;
+        }
+
+        Ok(())
+    })
+}
+
+/// 定位到指定的样本位置
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `sample` - 目标位置，每声道样本数（输出采样率），0 表示流的开头
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 定位是样本精确的：解码器会被重置，并从目标位置前至少 80ms 处开始解码以消除重置带来的失真
+/// * 目标位置超出流末尾时不会报错，之后的 `opus_ffi_ogg_reader_read` 返回 0 个样本
+///
+/// # 示例
+///
+/// ```c
+/// uint64_t duration = 0;
+/// opus_ffi_ogg_reader_get_duration(reader, &duration, &error);
+/// // 跳到中间位置继续播放
+/// opus_ffi_ogg_reader_seek(reader, duration / 2, &error);
+/// opus_ffi_ogg_reader_read(reader, pcm, 960 * 2, &samples, &error);
+/// ```
+#[export_name = "opus_ffi_ogg_reader_seek"]
+pub extern "C" fn ogg_reader_seek(
+    reader: *mut OggOpusReader,
+    sample: u64,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut reader = handle::get_mut(reader)?;
+        reader.seek(sample)
+    })
+}
+
+/// 定位到指定的时间位置
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `ms` - 目标位置（毫秒），0 表示流的开头
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_ogg_reader_seek_ms"]
+pub extern "C" fn ogg_reader_seek_ms(
+    reader: *mut OggOpusReader,
+    ms: u64,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut reader = handle::get_mut(reader)?;
+        reader.seek_ms(ms)
+    })
+}
+
+/// 释放 Ogg Opus 读取器实例
+///
+/// # 参数
+///
+/// * `reader` - 通过 `opus_ffi_ogg_reader_open_file` 或 `opus_ffi_ogg_reader_open_memory` 创建的读取器指针
+///
+/// # 安全性
+///
+/// * 如果 `reader` 为 `NULL`，函数不会执行任何操作
+/// * 释放后，`reader` 指针将不再有效
+/// * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `reader` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `reader` 不是有效的读取器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+#[export_name = "opus_ffi_free_ogg_reader"]
+pub extern "C" fn free_ogg_reader(reader: *mut OggOpusReader) -> c_int {
+    match handle::release(reader) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::OpusErrorCode,
+        ogg::{opus_head, opus_tags, writer::OggOpusWriter},
+    };
+    use opus_rs::Application;
+    use std::ptr;
+
+    fn encode_sine(samples: usize) -> Vec<u8> {
+        let mut writer =
+            OggOpusWriter::in_memory(Channels::Mono, 16000, Application::Audio).unwrap();
+        let pcm: Vec<i16> = (0..samples)
+            .map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16)
+            .collect();
+        writer.write(&pcm).unwrap();
+        writer.finish().unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_null_checks_ogg_reader() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = ogg_reader_open_file(ptr::null(), 48000, ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+
+        let res = ogg_reader_read(
+            ptr::null_mut(),
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        let data = encode_sine(1600);
+        let mut reader = ptr::null_mut();
+        let res = ogg_reader_open_memory(
+            data.as_ptr(),
+            data.len(),
+            16000,
+            &mut reader,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(free_ogg_reader(reader), 0);
+
+        // 已释放的句柄
+        let mut output = [0i16; 320];
+        let mut samples = 0;
+        let res = ogg_reader_read(
+            reader,
+            output.as_mut_ptr(),
+            320,
+            &mut samples,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_ogg_reader(reader), invalid_handle);
+
+        // 把写入器当作读取器使用
+        let mut writer = ptr::null_mut();
+        let res =
+            crate::ogg::writer::ogg_writer_open_memory(1, 16000, 1, &mut writer, ptr::null_mut());
+        assert_eq!(res, 0);
+        let wrong_type = writer as *mut OggOpusReader;
+        assert_eq!(
+            ogg_reader_seek(wrong_type, 0, ptr::null_mut()),
+            invalid_handle
+        );
+        assert_eq!(free_ogg_reader(wrong_type), invalid_handle);
+        assert_eq!(crate::ogg::writer::free_ogg_writer(writer), 0);
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        let head = OggOpusHead::parse(&opus_head(2, 312, 44100, -256)).unwrap();
+        assert_eq!(
+            head,
+            OggOpusHead {
+                channels: 2,
+                pre_skip: 312,
+                input_sample_rate: 44100,
+                output_gain: -256,
+            }
+        );
+
+        let mut surround = opus_head(6, 312, 48000, 0);
+        surround[18] = 1;
+        assert!(OggOpusHead::parse(&surround).is_err());
+
+        let tags = opus_tags("vendor", &["TITLE=test".to_string()]);
+        let (vendor, comments) = parse_tags(&tags).unwrap();
+        assert_eq!(vendor, "vendor");
+        assert_eq!(comments, ["TITLE=test"]);
+        assert!(parse_tags(&tags[..tags.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_read_trims_to_input_length() {
+        let data = encode_sine(16000 + 123);
+        let mut reader = OggOpusReader::from_bytes(data, 16000).unwrap();
+        assert_eq!(reader.head().channels, 1);
+        assert_eq!(reader.head().input_sample_rate, 16000);
+        assert!(reader.vendor().starts_with("opus_ffi"));
+        assert_eq!(read_all(&mut reader), 16000 + 123);
+    }
+
+    #[test]
+    fn test_read_rejects_buffer_smaller_than_one_frame() {
+        let mut writer =
+            OggOpusWriter::in_memory(Channels::Stereo, 16000, Application::Audio).unwrap();
+        writer.write(&[1000i16; 16000 * 2]).unwrap();
+        let data = writer.finish().unwrap().unwrap();
+        let mut reader = OggOpusReader::from_bytes(data, 16000).unwrap();
+
+        // 容纳不下一个立体声样本的缓冲区报错，而不是返回 0（流末尾）
+        let err = reader.read_float(&mut [0f32; 1]).unwrap_err();
+        assert_eq!(err.code, OpusErrorCode::BufferTooSmall as c_int);
+        let err = reader.read(&mut [0i16; 1]).unwrap_err();
+        assert_eq!(err.code, OpusErrorCode::BufferTooSmall as c_int);
+
+        let reader = handle::into_handle(reader);
+        let mut output = [0i16; 1];
+        let mut samples = 0;
+        let res = ogg_reader_read(
+            reader,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            &mut samples,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, OpusErrorCode::BufferTooSmall as c_int);
+
+        // 出错后读取器仍可继续使用，奇数长度的缓冲区按声道数向下取整
+        let mut total = 0;
+        let mut output = [0i16; 101];
+        loop {
+            let res = ogg_reader_read(
+                reader,
+                output.as_mut_ptr(),
+                output.len() as u32,
+                &mut samples,
+                ptr::null_mut(),
+            );
+            assert_eq!(res, 0);
+            if samples == 0 {
+                break;
+            }
+            assert!(samples <= 50);
+            total += samples;
+        }
+        assert_eq!(free_ogg_reader(reader), 0);
+        assert_eq!(total, 16000);
+    }
+
+    #[test]
+    fn test_corrupted_page_is_skipped() {
+        let mut data = encode_sine(16000 * 3);
+        let clean = read_all(&mut OggOpusReader::from_bytes(data.clone(), 16000).unwrap());
+        assert_eq!(clean, 16000 * 3);
+
+        // 破坏中间某一页的正文，CRC 校验失败后该页被跳过
+        let len = data.len();
+        data[len / 2] ^= 0xff;
+        let samples = read_all(&mut OggOpusReader::from_bytes(data, 16000).unwrap());
+        assert!(samples > 0 && samples < clean);
+    }
+
+    #[test]
+    fn test_seek_is_sample_accurate() {
+        let data = encode_sine(16000 * 5);
+        let mut reader = OggOpusReader::from_bytes(data, 16000).unwrap();
+        assert_eq!(reader.duration().unwrap(), 16000 * 5);
+        assert_eq!(reader.duration_ms().unwrap(), 5000);
+
+        let mut reference = vec![0f32; 16000 * 5];
+        assert_eq!(reader.read_float(&mut reference).unwrap(), 16000 * 5);
+
+        for position in [0, 1, 12345, 16000 * 2 + 7, 16000 * 5 - 100] {
+            reader.seek(position).unwrap();
+            let mut buffer = [0f32; 100];
+            assert_eq!(reader.read_float(&mut buffer).unwrap(), 100);
+            let expected = &reference[position as usize..position as usize + 100];
+            let max_diff = buffer
+                .iter()
+                .zip(expected)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0f32, f32::max);
+            assert!(max_diff < 0.01, "position {position}: {max_diff}");
+        }
+
+        reader.seek_ms(6000).unwrap();
+        assert_eq!(read_all(&mut reader), 0);
+        reader.seek_ms(4000).unwrap();
+        assert_eq!(read_all(&mut reader), 16000);
+    }
+
+    fn read_all(reader: &mut OggOpusReader) -> usize {
+        let mut total = 0;
+        let mut buffer = [0i16; 1000];
+        loop {
+            let samples = reader.read(&mut buffer).unwrap();
+            if samples == 0 {
+                return total;
+            }
+            total += samples;
+        }
+    }
+}
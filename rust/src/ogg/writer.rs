@@ -0,0 +1,608 @@
+use std::{
+    ffi::{c_char, c_int, CStr},
+    fs::File,
+    io::BufWriter,
+    path::Path,
+    ptr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use opus_rs::{Application, Channels, ErrorCode};
+
+use super::{opus_head, opus_tags, PageWriter, FLAG_BOS, FLAG_EOS, GRANULE_RATE};
+use crate::{
+    encoder::Encoder,
+    error::OpusError,
+    handle,
+    utils::{application_from, channels_from, ffi_exec, into_raw_buffer, invalid_input, Sink},
+};
+
+/// 每个音频帧的时长（毫秒）
+const FRAME_DURATION_MS: usize = 20;
+/// 每页最多容纳的数据包数量（约 1 秒音频），控制页大小和定位粒度
+const PACKETS_PER_PAGE: usize = 50;
+/// 单个 Opus 数据包的最大长度
+const MAX_PACKET_SIZE: usize = 4000;
+
+/// Ogg Opus 写入器不透明指针类型
+pub struct OggOpusWriter {
+    encoder: Encoder,
+    // `opus_ffi_ogg_writer_get_encoder` 交给调用者的编码器句柄，尚未获取时为 NULL
+    encoder_handle: *mut Encoder,
+    sink: Sink,
+    pages: PageWriter,
+    channels: usize,
+    sample_rate: u32,
+    // 每声道样本数（输入采样率）
+    frame_size: usize,
+    // 以下粒度均以 48kHz 计
+    pre_skip: u64,
+    granule: u64,
+    // 尚未凑满一帧的交错 PCM 样本
+    pending: Vec<f32>,
+    // 已写入的每声道样本数（输入采样率）
+    input_samples: u64,
+    finished: bool,
+}
+
+impl OggOpusWriter {
+    /// 创建写入到文件的 Ogg Opus 写入器
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        channels: Channels,
+        sample_rate: u32,
+        application: Application,
+    ) -> Result<Self, OpusError> {
+        let file = File::create(path)?;
+        Self::new(
+            Sink::File(BufWriter::new(file)),
+            channels,
+            sample_rate,
+            application,
+        )
+    }
+
+    /// 创建写入到内存的 Ogg Opus 写入器，数据在 `finish` 时返回
+    pub fn in_memory(
+        channels: Channels,
+        sample_rate: u32,
+        application: Application,
+    ) -> Result<Self, OpusError> {
+        Self::new(Sink::Memory(Vec::new()), channels, sample_rate, application)
+    }
+
+    fn new(
+        sink: Sink,
+        channels: Channels,
+        sample_rate: u32,
+        application: Application,
+    ) -> Result<Self, OpusError> {
+        let mut encoder = Encoder::new(sample_rate, channels, application)?;
+        let lookahead = encoder.get_lookahead()? as u64;
+        let serial = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() ^ d.as_secs() as u32)
+            .unwrap_or_default();
+
+        let mut writer = Self {
+            encoder,
+            encoder_handle: ptr::null_mut(),
+            sink,
+            pages: PageWriter::new(serial),
+            channels: channels as usize,
+            sample_rate,
+            frame_size: sample_rate as usize * FRAME_DURATION_MS / 1000,
+            pre_skip: lookahead * GRANULE_RATE as u64 / sample_rate as u64,
+            granule: 0,
+            pending: Vec::new(),
+            input_samples: 0,
+            finished: false,
+        };
+        writer.write_headers()?;
+
+        Ok(writer)
+    }
+
+    /// 获取内部编码器，可用于在写入音频前调整比特率等参数
+    pub fn encoder_mut(&mut self) -> &mut Encoder {
+        &mut self.encoder
+    }
+
+    /// 写入交错的 16 位 PCM 样本，样本数可以是任意声道数的整数倍
+    pub fn write(&mut self, pcm: &[i16]) -> Result<(), OpusError> {
+        let pcm: Vec<f32> = pcm.iter().map(|&s| s as f32 / 32768.0).collect();
+        self.write_float(&pcm)
+    }
+
+    /// 写入交错的浮点 PCM 样本，样本数可以是任意声道数的整数倍
+    pub fn write_float(&mut self, pcm: &[f32]) -> Result<(), OpusError> {
+        self.check_not_finished()?;
+        if !pcm.len().is_multiple_of(self.channels) {
+            return Err(OpusError::new(
+                ErrorCode::BadArg as c_int,
+                "PCM sample count is not a multiple of the channel count",
+            ));
+        }
+
+        self.input_samples += (pcm.len() / self.channels) as u64;
+        self.pending.extend_from_slice(pcm);
+
+        let frame_len = self.frame_size * self.channels;
+        let mut offset = 0;
+        while self.pending.len() - offset >= frame_len {
+            let frame = self.pending[offset..offset + frame_len].to_vec();
+            self.encode_frame(&frame)?;
+            offset += frame_len;
+        }
+        self.pending.drain(..offset);
+
+        Ok(())
+    }
+
+    /// 结束写入：用静音补齐最后一帧和编码器延迟，写出带 EOS 标志的最后一页
+    ///
+    /// 对于内存写入器返回完整的 Ogg Opus 数据，文件写入器返回 `None`。
+    pub fn finish(&mut self) -> Result<Option<Vec<u8>>, OpusError> {
+        self.check_not_finished()?;
+
+        let total =
+            self.pre_skip + self.input_samples * GRANULE_RATE as u64 / self.sample_rate as u64;
+        let frame_len = self.frame_size * self.channels;
+        let mut frame = std::mem::take(&mut self.pending);
+        frame.resize(frame_len, 0.0);
+        // 至少输出一个数据包，并确保解码后的长度覆盖预跳过和全部输入
+        loop {
+            self.encode_frame(&frame)?;
+            if self.granule >= total {
+                break;
+            }
+            frame.fill(0.0);
+        }
+
+        let page = self.pages.flush(total as i64, FLAG_EOS);
+        self.sink.write(&page)?;
+        self.finished = true;
+
+        Ok(self.sink.finish()?)
+    }
+
+    fn check_not_finished(&self) -> Result<(), OpusError> {
+        if self.finished {
+            return Err(OpusError::new(
+                ErrorCode::InvalidState as c_int,
+                "Ogg Opus writer already finished",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn write_headers(&mut self) -> Result<(), OpusError> {
+        let head = opus_head(
+            self.channels as u8,
+            self.pre_skip as u16,
+            self.sample_rate,
+            0,
+        );
+        self.pages.push(&head);
+        let page = self.pages.flush(0, FLAG_BOS);
+        self.sink.write(&page)?;
+
+        let vendor = format!(
+            "opus_ffi {} ({})",
+            env!("CARGO_PKG_VERSION"),
+            opus_rs::version()
+        );
+        let tags = opus_tags(&vendor, &[]);
+        self.pages.push(&tags);
+        let page = self.pages.flush(0, 0);
+        self.sink.write(&page)?;
+
+        Ok(())
+    }
+
+    fn encode_frame(&mut self, frame: &[f32]) -> Result<(), OpusError> {
+        let mut packet = [0u8; MAX_PACKET_SIZE];
+        let size = self.encoder.encode_float(frame, &mut packet)?;
+
+        // 先输出已满的页，保证最后一页始终包含数据包，便于结束时裁剪
+        if self.pages.packets() >= PACKETS_PER_PAGE || !self.pages.fits(size) {
+            let page = self.pages.flush(self.granule as i64, 0);
+            self.sink.write(&page)?;
+        }
+        self.pages.push(&packet[..size]);
+        self.granule += (self.frame_size as u64) * GRANULE_RATE as u64 / self.sample_rate as u64;
+
+        Ok(())
+    }
+}
+
+/// 创建写入到文件的 Ogg Opus 写入器
+///
+/// # 参数
+///
+/// * `path` - 输出文件路径（UTF-8 编码的 C 字符串）。文件已存在时会被覆盖
+/// * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+///   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
+/// * `sample_rate` - 输入 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `application` - 应用模式，取值与 `opus_ffi_new_encoder` 相同
+/// * `result` - 输出参数，用于接收创建的写入器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 安全性
+///
+/// 调用者负责在不再需要时调用 `opus_ffi_free_ogg_writer` 释放返回的写入器。
+///
+/// # 示例
+///
+/// ```c
+/// OggOpusWriter *writer = NULL;
+/// OpusError error = {0, NULL};
+/// int res = opus_ffi_ogg_writer_open_file("/tmp/voice.opus", 1, 16000, 1, &writer, &error);
+/// if (res == 0) {
+///     opus_ffi_ogg_writer_write(writer, pcm_samples, sample_count, &error);
+///     opus_ffi_ogg_writer_finish(writer, NULL, NULL, &error);
+///     opus_ffi_free_ogg_writer(writer);
+/// }
+/// ```
+#[export_name = "opus_ffi_ogg_writer_open_file"]
+pub extern "C" fn ogg_writer_open_file(
+    path: *const c_char,
+    channels: u32,
+    sample_rate: u32,
+    application: u32,
+    result: *mut *mut OggOpusWriter,
+    error: *mut OpusError,
+) -> c_int {
+    if path.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return invalid_input(error),
+    };
+    ffi_exec(error, || {
+        let channels = channels_from(channels)?;
+        let application = application_from(application)?;
+        let writer = OggOpusWriter::create(path, channels, sample_rate, application)?;
+        unsafe {
+            *result = handle::into_handle(writer);
+        }
+
+        Ok(())
+    })
+}
+
+/// 创建写入到内存的 Ogg Opus 写入器
+///
+/// # 参数
+///
+/// * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+///   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
+/// * `sample_rate` - 输入 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `application` - 应用模式，取值与 `opus_ffi_new_encoder` 相同
+/// * `result` - 输出参数，用于接收创建的写入器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 编码后的数据保存在内存中，通过 `opus_ffi_ogg_writer_finish` 一次性取出
+#[export_name = "opus_ffi_ogg_writer_open_memory"]
+pub extern "C" fn ogg_writer_open_memory(
+    channels: u32,
+    sample_rate: u32,
+    application: u32,
+    result: *mut *mut OggOpusWriter,
+    error: *mut OpusError,
+) -> c_int {
+    if result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let channels = channels_from(channels)?;
+        let application = application_from(application)?;
+        let writer = OggOpusWriter::in_memory(channels, sample_rate, application)?;
+        unsafe {
+            *result = handle::into_handle(writer);
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取写入器内部使用的编码器
+///
+/// # 参数
+///
+/// * `writer` - 之前通过 `opus_ffi_ogg_writer_open_file` 或 `opus_ffi_ogg_writer_open_memory` 创建的写入器
+/// * `result` - 输出参数，编码器指针。可以传给 `opus_ffi_set_encoder_bitrate` 等函数调整编码参数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 安全性
+///
+/// * 返回的编码器归写入器所有，不能调用 `opus_ffi_free_encoder` 释放，也不能在 `opus_ffi_free_ogg_writer` 之后继续使用
+/// * 通过它的调用与写入器上的调用依次执行；写入器释放后它随之失效，使用时返回 `OPUS_ERROR_CODE_INVALID_HANDLE`
+/// * 不要直接用它调用 `opus_ffi_encode`，否则编码器状态与 Ogg 流不一致
+#[export_name = "opus_ffi_ogg_writer_get_encoder"]
+pub extern "C" fn ogg_writer_get_encoder(
+    writer: *mut OggOpusWriter,
+    result: *mut *mut Encoder,
+    error: *mut OpusError,
+) -> c_int {
+    if writer.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut writer = handle::get_mut(writer)?;
+        if writer.encoder_handle.is_null() {
+            let encoder: *mut Encoder = writer.encoder_mut();
+            writer.encoder_handle = handle::register_borrowed(&writer, encoder)?;
+        }
+        unsafe {
+            *result = writer.encoder_handle;
+        }
+
+        Ok(())
+    })
+}
+
+/// 向 Ogg Opus 写入器写入 PCM 样本（16 位整数）
+///
+/// # 参数
+///
+/// * `writer` - 之前创建的写入器实例
+/// * `input` - 指向交错 PCM 样本的指针（16 位有符号整数）
+/// * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍，长度不要求是整帧
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 写入器以 20ms 为一帧进行编码，不足一帧的样本会暂存到下一次写入或 `opus_ffi_ogg_writer_finish`
+#[export_name = "opus_ffi_ogg_writer_write"]
+pub extern "C" fn ogg_writer_write(
+    writer: *mut OggOpusWriter,
+    input: *const i16,
+    input_size: u32,
+    error: *mut OpusError,
+) -> c_int {
+    if writer.is_null() || input.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut writer = handle::get_mut(writer)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        writer.write(input)
+    })
+}
+
+/// 向 Ogg Opus 写入器写入 PCM 样本（32 位浮点数）
+///
+/// # 参数
+///
+/// * `writer` - 之前创建的写入器实例
+/// * `input` - 指向交错 PCM 样本的指针（32 位浮点数，范围通常在 [-1.0, 1.0] 之间）
+/// * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_ogg_writer_write_float"]
+pub extern "C" fn ogg_writer_write_float(
+    writer: *mut OggOpusWriter,
+    input: *const f32,
+    input_size: u32,
+    error: *mut OpusError,
+) -> c_int {
+    if writer.is_null() || input.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut writer = handle::get_mut(writer)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        writer.write_float(input)
+    })
+}
+
+/// 结束 Ogg Opus 写入
+///
+/// # 参数
+///
+/// * `writer` - 之前创建的写入器实例
+/// * `data` - 可选的输出参数。对于内存写入器，用于接收完整的 Ogg Opus 数据，需使用 `opus_ffi_free_buffer` 释放；
+///   对于文件写入器，将被设置为 `NULL`
+/// * `data_size` - 可选的输出参数，`data` 的长度（字节数）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 最后不足一帧的样本会用静音补齐，最后一页的粒度位置会标记实际长度，播放器据此裁掉补齐的部分
+/// * 结束后写入器不能再写入，但仍需调用 `opus_ffi_free_ogg_writer` 释放
+///
+/// # 示例
+///
+/// ```c
+/// uint8_t *data = NULL;
+/// size_t size = 0;
+/// int res = opus_ffi_ogg_writer_finish(writer, &data, &size, &error);
+/// if (res == 0) {
+///     // 使用 data...
+///     opus_ffi_free_buffer(data, size);
+/// }
+/// opus_ffi_free_ogg_writer(writer);
+/// ```
+#[export_name = "opus_ffi_ogg_writer_finish"]
+pub extern "C" fn ogg_writer_finish(
+    writer: *mut OggOpusWriter,
+    data: *mut *mut u8,
+    data_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if writer.is_null() || data.is_null() != data_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut writer = handle::get_mut(writer)?;
+        let output = writer.finish()?;
+        if !data.is_null() {
+            match output {
+                Some(output) => into_raw_buffer(output, data, data_size),
+                None => unsafe {
+                    (*data) = std::ptr::null_mut();
+                    (*data_size) = 0;
+                },
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// 释放 Ogg Opus 写入器实例
+///
+/// # 参数
+///
+/// * `writer` - 通过 `opus_ffi_ogg_writer_open_file` 或 `opus_ffi_ogg_writer_open_memory` 创建的写入器指针
+///
+/// # 安全性
+///
+/// * 如果 `writer` 为 `NULL`，函数不会执行任何操作
+/// * 未调用 `opus_ffi_ogg_writer_finish` 就释放时，文件写入器已写出的数据会保留，但文件不完整
+/// * 释放后，`writer` 指针以及通过 `opus_ffi_ogg_writer_get_encoder` 获取的编码器指针都将不再有效
+/// * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `writer` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `writer` 不是有效的写入器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+#[export_name = "opus_ffi_free_ogg_writer"]
+pub extern "C" fn free_ogg_writer(writer: *mut OggOpusWriter) -> c_int {
+    // 内部编码器的句柄与写入器在同一次加锁中注销，不会留下失效的句柄
+    match handle::release(writer) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::set_encoder_bitrate;
+    use std::ptr;
+
+    #[test]
+    fn test_null_checks_ogg_writer() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = ogg_writer_open_file(ptr::null(), 1, 16000, 1, ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+
+        let res = ogg_writer_write(ptr::null_mut(), ptr::null(), 0, ptr::null_mut());
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        let mut writer = ptr::null_mut();
+        assert_eq!(
+            ogg_writer_open_memory(1, 16000, 1, &mut writer, ptr::null_mut()),
+            0
+        );
+        assert_eq!(free_ogg_writer(writer), 0);
+
+        // 已释放的句柄
+        let pcm = [0i16; 320];
+        let res = ogg_writer_write(writer, pcm.as_ptr(), 320, ptr::null_mut());
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_ogg_writer(writer), invalid_handle);
+
+        // 把重组器当作写入器使用
+        let mut repacketizer = ptr::null_mut();
+        assert_eq!(
+            crate::repacketizer::new_repacketizer(&mut repacketizer, ptr::null_mut()),
+            0
+        );
+        let wrong_type = repacketizer as *mut OggOpusWriter;
+        let res = ogg_writer_finish(
+            wrong_type,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_ogg_writer(wrong_type), invalid_handle);
+        assert_eq!(crate::repacketizer::free_repacketizer(repacketizer), 0);
+    }
+
+    #[test]
+    fn test_encoder_handle_freed_with_writer() {
+        let mut writer = ptr::null_mut();
+        assert_eq!(
+            ogg_writer_open_memory(1, 16000, 1, &mut writer, ptr::null_mut()),
+            0
+        );
+        let mut encoder = ptr::null_mut();
+        assert_eq!(
+            ogg_writer_get_encoder(writer, &mut encoder, ptr::null_mut()),
+            0
+        );
+        assert_eq!(set_encoder_bitrate(encoder, 16000, ptr::null_mut()), 0);
+
+        // 同一个写入器总是返回同一个句柄，它不能单独释放
+        let mut again = ptr::null_mut();
+        assert_eq!(
+            ogg_writer_get_encoder(writer, &mut again, ptr::null_mut()),
+            0
+        );
+        assert_eq!(again, encoder);
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        assert_eq!(crate::encoder::free_encoder(encoder), invalid_handle);
+
+        assert_eq!(free_ogg_writer(writer), 0);
+        let res = set_encoder_bitrate(encoder, 16000, ptr::null_mut());
+        assert_eq!(res, invalid_handle);
+    }
+
+    #[test]
+    fn test_write_after_finish() {
+        let mut writer =
+            OggOpusWriter::in_memory(Channels::Mono, 16000, Application::Voip).unwrap();
+        writer.write(&[0i16; 100]).unwrap();
+        assert!(writer.finish().unwrap().is_some());
+
+        let err = writer.write(&[0i16; 100]).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidState as c_int);
+    }
+}
@@ -0,0 +1,282 @@
+//! 不解码的 Opus 数据包检查（RFC 6716 第 3 节）
+
+use std::ffi::c_int;
+
+use opus_rs::{packet, Bandwidth, Channels, ErrorCode};
+
+use crate::{
+    error::OpusError,
+    utils::{ffi_exec, invalid_input},
+};
+
+/// 数据包的编码模式
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpusMode {
+    /// 仅 SILK（语音，最高宽带）
+    SilkOnly = 1000,
+    /// SILK + CELT 混合
+    Hybrid = 1001,
+    /// 仅 CELT（音乐或低延迟）
+    CeltOnly = 1002,
+}
+
+/// 音频带宽，取值与 libopus 的 `OPUS_BANDWIDTH_*` 常量相同
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpusBandwidth {
+    /// 窄带，4kHz
+    Narrowband = 1101,
+    /// 中带，6kHz
+    Mediumband = 1102,
+    /// 宽带，8kHz
+    Wideband = 1103,
+    /// 超宽带，12kHz
+    Superwideband = 1104,
+    /// 全带，20kHz
+    Fullband = 1105,
+}
+
+impl OpusBandwidth {
+    /// 由 libopus 的 `OPUS_BANDWIDTH_*` 取值转换，其他取值返回 `None`
+    pub(crate) fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            1101 => Some(OpusBandwidth::Narrowband),
+            1102 => Some(OpusBandwidth::Mediumband),
+            1103 => Some(OpusBandwidth::Wideband),
+            1104 => Some(OpusBandwidth::Superwideband),
+            1105 => Some(OpusBandwidth::Fullband),
+            _ => None,
+        }
+    }
+}
+
+impl From<Bandwidth> for OpusBandwidth {
+    fn from(bandwidth: Bandwidth) -> Self {
+        match bandwidth {
+            Bandwidth::Narrowband => OpusBandwidth::Narrowband,
+            Bandwidth::Mediumband => OpusBandwidth::Mediumband,
+            Bandwidth::Wideband => OpusBandwidth::Wideband,
+            Bandwidth::Superwideband => OpusBandwidth::Superwideband,
+            _ => OpusBandwidth::Fullband,
+        }
+    }
+}
+
+/// 从 TOC 字节和帧数编码中解析出的数据包信息
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketInfo {
+    /// 编码模式
+    pub mode: OpusMode,
+    /// 音频带宽
+    pub bandwidth: OpusBandwidth,
+    /// 每帧时长（微秒）：2500, 5000, 10000, 20000, 40000 或 60000
+    pub frame_duration_us: u32,
+    /// 数据包中的帧数（1~48）
+    pub frame_count: u32,
+    /// 是否为立体声数据包
+    pub stereo: bool,
+    /// 按请求的采样率解码整个数据包得到的每声道样本数
+    pub samples_per_packet: u32,
+}
+
+/// 解析数据包的 TOC 字节和帧数编码，同时校验数据包结构是否合法
+///
+/// 合法的数据包才会返回信息，因此也可以在解码前用来过滤损坏的数据。
+pub fn packet_info(data: &[u8], sample_rate: u32) -> Result<PacketInfo, OpusError> {
+    if !matches!(sample_rate, 8000 | 12000 | 16000 | 24000 | 48000) {
+        return Err(OpusError::new(
+            ErrorCode::BadArg as c_int,
+            "Unsupported sample rate",
+        ));
+    }
+    if data.is_empty() {
+        return Err(OpusError::new(
+            ErrorCode::InvalidPacket as c_int,
+            "Empty packet",
+        ));
+    }
+
+    packet::parse(data)?;
+    let frame_count = packet::get_nb_frames(data)?;
+    let samples_per_packet = packet::get_nb_samples(data, sample_rate)?;
+    let frame_samples = packet::get_samples_per_frame(data, 48000)?;
+
+    let config = data[0] >> 3;
+    let mode = match config {
+        0..=11 => OpusMode::SilkOnly,
+        12..=15 => OpusMode::Hybrid,
+        _ => OpusMode::CeltOnly,
+    };
+
+    Ok(PacketInfo {
+        mode,
+        bandwidth: packet::get_bandwidth(data)?.into(),
+        frame_duration_us: (frame_samples * 1000 / 48) as u32,
+        frame_count: frame_count as u32,
+        stereo: packet::get_nb_channels(data)? == Channels::Stereo,
+        samples_per_packet: samples_per_packet as u32,
+    })
+}
+
+/// 解析 Opus 数据包的 TOC 信息（不解码）
+///
+/// # 参数
+///
+/// * `data` - 指向 Opus 数据包的指针
+/// * `data_size` - 数据包长度（字节数）
+/// * `sample_rate` - 计算 `samples_per_packet` 使用的采样率（Hz），通常与解码器的采样率相同，
+///   支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `result` - 输出参数，用于接收数据包信息
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），数据包结构不合法时返回 `OPUS_INVALID_PACKET`
+///
+/// # 注意事项
+///
+/// * 解码该数据包需要的输出缓冲区大小为 `samples_per_packet * 声道数`
+/// * 解码器的声道数与数据包的 `stereo` 标志无关，单声道解码器也可以解码立体声数据包
+///
+/// # 示例
+///
+/// ```c
+/// PacketInfo info;
+/// OpusError error = {0, NULL};
+/// if (opus_ffi_packet_get_info(packet, packet_size, 16000, &info, &error) == 0) {
+///     int16_t *pcm = malloc(info.samples_per_packet * channels * sizeof(int16_t));
+///     opus_ffi_decode(decoder, packet, packet_size, pcm, info.samples_per_packet * channels,
+///            false, &samples, &error);
+/// }
+/// ```
+#[export_name = "opus_ffi_packet_get_info"]
+pub extern "C" fn packet_get_info(
+    data: *const u8,
+    data_size: usize,
+    sample_rate: u32,
+    result: *mut PacketInfo,
+    error: *mut OpusError,
+) -> c_int {
+    if data.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let data = unsafe { std::slice::from_raw_parts(data, data_size) };
+        let info = packet_info(data, sample_rate)?;
+        unsafe {
+            (*result) = info;
+        }
+
+        Ok(())
+    })
+}
+
+/// 判断是否为 DTX（不连续传输）数据包
+///
+/// 开启 DTX 后，编码器在静音期间输出 1~2 字节的数据包，其中不包含音频数据，不需要发送。
+/// 空数据不是合法的数据包，不算作 DTX。
+pub fn is_dtx_packet(data: &[u8]) -> bool {
+    matches!(data.len(), 1 | 2)
+}
+
+/// 判断数据包是否为 DTX（不连续传输）数据包
+///
+/// # 参数
+///
+/// * `data` - 指向 Opus 数据包的指针
+/// * `data_size` - 数据包长度（字节数）
+///
+/// # 返回值
+///
+/// 数据包为 1~2 字节（只有 TOC，不含音频数据）时返回 `true`，`data` 为 `NULL` 或 `data_size` 为 0 时返回 `false`
+///
+/// # 注意事项
+///
+/// * DTX 数据包不需要发送或保存；已经收到的 DTX 数据包直接交给 `opus_ffi_decode` 解码，按数据包时长生成舒适噪声
+#[export_name = "opus_ffi_packet_is_dtx"]
+pub extern "C" fn packet_is_dtx(data: *const u8, data_size: usize) -> bool {
+    if data.is_null() {
+        return false;
+    }
+
+    is_dtx_packet(unsafe { std::slice::from_raw_parts(data, data_size) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opus_rs::{Application, Encoder};
+    use std::ptr;
+
+    #[test]
+    fn test_null_checks_packet() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = packet_get_info(ptr::null(), 0, 48000, ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_packet_is_dtx() {
+        let toc = [0x08u8];
+        assert!(packet_is_dtx(toc.as_ptr(), toc.len()));
+        let voice = [0xfcu8, 0xff, 0xfe];
+        assert!(!packet_is_dtx(voice.as_ptr(), voice.len()));
+        assert!(!packet_is_dtx(ptr::null(), 0));
+        // 空数据不是 DTX 数据包
+        assert!(!packet_is_dtx(toc.as_ptr(), 0));
+        assert!(!is_dtx_packet(&[]));
+    }
+
+    #[test]
+    fn test_packet_info() {
+        // 配置 1：SILK 窄带 20ms，单声道，编码 0（单帧），帧为空
+        let info = packet_info(&[0x08], 16000).unwrap();
+        assert_eq!(info.mode, OpusMode::SilkOnly);
+        assert_eq!(info.bandwidth, OpusBandwidth::Narrowband);
+        assert_eq!(info.frame_duration_us, 20000);
+        assert_eq!(info.frame_count, 1);
+        assert!(!info.stereo);
+        assert_eq!(info.samples_per_packet, 320);
+
+        // 配置 31：CELT 全带 20ms，立体声，编码 3（CBR，3 帧）
+        let info = packet_info(&[0xff, 0x03, 1, 2, 3], 48000).unwrap();
+        assert_eq!(info.mode, OpusMode::CeltOnly);
+        assert_eq!(info.bandwidth, OpusBandwidth::Fullband);
+        assert_eq!(info.frame_count, 3);
+        assert!(info.stereo);
+        assert_eq!(info.samples_per_packet, 2880);
+
+        // 配置 13：混合模式超宽带 20ms
+        let info = packet_info(&[13 << 3], 48000).unwrap();
+        assert_eq!(info.mode, OpusMode::Hybrid);
+        assert_eq!(info.bandwidth, OpusBandwidth::Superwideband);
+    }
+
+    #[test]
+    fn test_invalid_packet() {
+        let err = packet_info(&[], 48000).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidPacket as c_int);
+        // 编码 1 要求两帧长度相同，负载长度必须为偶数
+        assert!(packet_info(&[0x01, 1, 2, 3], 48000).is_err());
+        // 编码 3 的帧数不能为 0
+        assert!(packet_info(&[0x03, 0x00], 48000).is_err());
+        let err = packet_info(&[0x08], 44100).unwrap_err();
+        assert_eq!(err.code, ErrorCode::BadArg as c_int);
+    }
+
+    #[test]
+    fn test_encoded_packet_info() {
+        let mut encoder = Encoder::new(16000, Channels::Mono, Application::Voip).unwrap();
+        let mut packet = [0u8; 4000];
+        let size = encoder.encode(&[0i16; 320], &mut packet).unwrap();
+
+        let info = packet_info(&packet[..size], 16000).unwrap();
+        assert_eq!(info.samples_per_packet, 320);
+        assert_eq!(info.frame_duration_us * info.frame_count, 20000);
+        assert!(!info.stereo);
+    }
+}
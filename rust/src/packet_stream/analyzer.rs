@@ -0,0 +1,273 @@
+use std::{
+    ffi::{c_char, c_int, CStr},
+    fs::File,
+    io::Read,
+};
+
+use opus_rs::{packet, Bandwidth, Channels};
+
+use crate::{
+    error::OpusError,
+    utils::{ffi_exec, invalid_input},
+};
+
+/// CBR 数据包的最大长度（RFC 6716 中单帧的上限）
+const MAX_PACKET_SIZE: usize = 1275;
+/// 每个候选包长最多检查的数据包数量
+const MAX_PACKETS: usize = 512;
+/// 从文件读取用于分析的最大字节数，足够覆盖最大包长下的 `MAX_PACKETS` 个数据包
+const MAX_ANALYZE_SIZE: u64 = (MAX_PACKET_SIZE * MAX_PACKETS) as u64;
+
+/// 旧版固定包长录音的分析结果
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LegacyStreamInfo {
+    /// 推断出的数据包长度（字节数）
+    pub packet_size: u32,
+    /// 由编码带宽推断出的采样率（Hz）：窄带 8000，中带 12000，宽带 16000，超宽带 24000，全带 48000
+    pub sample_rate: u32,
+    /// 声道数（1 或 2）
+    pub channels: u32,
+    /// 每个数据包的时长（微秒）
+    pub frame_duration_us: u32,
+    /// 置信度，范围 [0, 1]。为 0 时表示无法识别，其他字段无意义
+    pub confidence: f32,
+}
+
+/// 分析没有头部的固定包长 Opus 录音，推断包长、采样率和声道数
+///
+/// 对每个候选包长，把数据按该长度切分，检查每段能否作为合法的 Opus 数据包解析，
+/// 以及各段 TOC 字节给出的时长和立体声标志是否一致。
+/// 正确的包长下，录音每个包的时长和声道都相同；错误的包长会落到包数据中间，TOC 近似随机。
+pub fn analyze_legacy(data: &[u8]) -> LegacyStreamInfo {
+    let mut best = LegacyStreamInfo::default();
+    let mut best_stride = 0;
+    let mut best_score = 0f32;
+    for stride in 1..=MAX_PACKET_SIZE.min(data.len()) {
+        let score = score_stride(data, stride);
+        // 包长的整数倍同样能得到一致的 TOC，但检查的数据包更少、得分更低，同分时取较小的包长
+        if score > best_score {
+            best_score = score;
+            best_stride = stride;
+        }
+    }
+    if best_stride == 0 {
+        return best;
+    }
+
+    let first = &data[..best_stride];
+    let (Ok(bandwidth), Ok(channels), Ok(samples)) = (
+        packet::get_bandwidth(first),
+        packet::get_nb_channels(first),
+        packet::get_nb_samples(first, 48000),
+    ) else {
+        return best;
+    };
+
+    best.packet_size = best_stride as u32;
+    best.sample_rate = match bandwidth {
+        Bandwidth::Narrowband => 8000,
+        Bandwidth::Mediumband => 12000,
+        Bandwidth::Wideband => 16000,
+        Bandwidth::Superwideband => 24000,
+        _ => 48000,
+    };
+    best.channels = match channels {
+        Channels::Mono => 1,
+        Channels::Stereo => 2,
+    };
+    best.frame_duration_us = (samples * 1000 / 48) as u32;
+    best.confidence = best_score;
+
+    best
+}
+
+/// 计算按 `stride` 切分时的得分，范围 [0, 1]
+fn score_stride(data: &[u8], stride: usize) -> f32 {
+    let count = (data.len() / stride).min(MAX_PACKETS);
+    if count < 2 {
+        return 0.0;
+    }
+
+    let mut valid = 0;
+    // 按（时长，立体声标志）统计，编码器在 CBR 下也可能切换模式和带宽，但帧长和声道不变
+    let mut kinds: Vec<((usize, u8), usize)> = Vec::new();
+    for chunk in data.chunks_exact(stride).take(count) {
+        let Ok(samples) = packet::get_nb_samples(chunk, 48000) else {
+            continue;
+        };
+        if samples == 0 || packet::parse(chunk).is_err() {
+            continue;
+        }
+        valid += 1;
+
+        let kind = (samples, chunk[0] & 0x04);
+        match kinds.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, n)) => *n += 1,
+            None => kinds.push((kind, 1)),
+        }
+    }
+    let same = kinds.iter().map(|&(_, n)| n).max().unwrap_or_default();
+
+    let mut score = (valid as f32 / count as f32) * (same as f32 / count as f32);
+    // 数据包越多，一致性越可信
+    score *= 1.0 - 1.0 / count as f32;
+    // 整个文件恰好是包长的整数倍时更可信（录音中断时末尾可能残留半个包）
+    if count < MAX_PACKETS && !data.len().is_multiple_of(stride) {
+        score *= 0.9;
+    }
+
+    score
+}
+
+/// 分析内存中没有头部的固定包长 Opus 录音
+///
+/// # 参数
+///
+/// * `data` - 指向录音数据的指针
+/// * `data_size` - 数据长度（字节数）。只分析开头的部分数据，传入整个文件或开头约 640KB 即可
+/// * `result` - 输出参数，用于接收分析结果
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功（包括无法识别的情况，此时 `result->confidence` 为 0）
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 采样率由数据包的编码带宽推断，可直接用于创建解码器；原始录音的采样率可能更高
+/// * 置信度较低（例如小于 0.5）时，数据很可能不是固定包长的 Opus 录音
+///
+/// # 示例
+///
+/// ```c
+/// LegacyStreamInfo info;
+/// OpusError error = {0, NULL};
+/// if (opus_ffi_analyze_legacy_file("/tmp/R20250728-151607.opus", &info, &error) == 0
+///     && info.confidence > 0.5) {
+///     PacketStreamReader *reader = NULL;
+///     opus_ffi_packet_reader_open_legacy_file("/tmp/R20250728-151607.opus", info.packet_size,
+///                                    info.sample_rate, info.channels, &reader, &error);
+/// }
+/// ```
+#[export_name = "opus_ffi_analyze_legacy_memory"]
+pub extern "C" fn analyze_legacy_memory(
+    data: *const u8,
+    data_size: usize,
+    result: *mut LegacyStreamInfo,
+    error: *mut OpusError,
+) -> c_int {
+    if data.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let data = unsafe { std::slice::from_raw_parts(data, data_size) };
+        let info = analyze_legacy(data);
+        unsafe {
+            (*result) = info;
+        }
+
+        Ok(())
+    })
+}
+
+/// 分析没有头部的固定包长 Opus 录音文件
+///
+/// # 参数
+///
+/// * `path` - 录音文件路径（UTF-8 编码的 C 字符串）
+/// * `result` - 输出参数，用于接收分析结果
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功（包括无法识别的情况，此时 `result->confidence` 为 0）
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 只读取文件开头的部分数据，大文件也能很快完成
+#[export_name = "opus_ffi_analyze_legacy_file"]
+pub extern "C" fn analyze_legacy_file(
+    path: *const c_char,
+    result: *mut LegacyStreamInfo,
+    error: *mut OpusError,
+) -> c_int {
+    if path.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return invalid_input(error),
+    };
+
+    ffi_exec(error, || {
+        let mut data = Vec::new();
+        File::open(path)?
+            .take(MAX_ANALYZE_SIZE)
+            .read_to_end(&mut data)?;
+        let info = analyze_legacy(&data);
+        unsafe {
+            (*result) = info;
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opus_rs::{Application, Bitrate, Encoder};
+    use std::ptr;
+
+    #[test]
+    fn test_null_checks_analyzer() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = analyze_legacy_memory(ptr::null(), 0, ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_detect_cbr_stream() {
+        let mut encoder = Encoder::new(16000, Channels::Mono, Application::Voip).unwrap();
+        encoder.set_bitrate(Bitrate::Bits(32000)).unwrap();
+        encoder.set_vbr(false).unwrap();
+
+        let mut data = Vec::new();
+        let mut packet = [0u8; 4000];
+        for frame in 0..100 {
+            let pcm: Vec<i16> = (0..320)
+                .map(|i| (((frame * 320 + i) as f32 * 0.07).sin() * 6000.0) as i16)
+                .collect();
+            let size = encoder.encode(&pcm, &mut packet).unwrap();
+            data.extend_from_slice(&packet[..size]);
+        }
+
+        let info = analyze_legacy(&data);
+        assert_eq!(info.packet_size, 80);
+        assert_eq!(info.channels, 1);
+        assert_eq!(info.frame_duration_us, 20000);
+        assert!(info.confidence > 0.9, "{info:?}");
+    }
+
+    #[test]
+    fn test_random_data_has_low_confidence() {
+        let mut state = 0x1234_5678u32;
+        let data: Vec<u8> = (0..20000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+
+        let info = analyze_legacy(&data);
+        assert!(info.confidence < 0.5, "{info:?}");
+        assert_eq!(analyze_legacy(&[]).confidence, 0.0);
+    }
+}
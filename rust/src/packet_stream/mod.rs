@@ -0,0 +1,139 @@
+//! 自描述的 Opus 数据包流格式，用于替代固定包长的裸 Opus 录音
+//!
+//! 文件布局（多字节整数均为小端序）：
+//!
+//! | 偏移 | 长度 | 内容                                      |
+//! |------|------|-------------------------------------------|
+//! | 0    | 4    | 魔数 `OPKT`                               |
+//! | 4    | 1    | 格式版本，当前为 1                        |
+//! | 5    | 1    | 声道数                                    |
+//! | 6    | 2    | 保留，写 0                                |
+//! | 8    | 4    | 采样率（Hz）                              |
+//! | 12   | 4    | 每帧时长（微秒），0 表示不固定            |
+//! | 16   | ...  | 数据包序列：LEB128 变长整数表示的包长 + 包数据 |
+
+use std::{ffi::c_int, io::Read};
+
+use opus_rs::ErrorCode;
+
+use crate::error::{format_error, OpusError};
+
+pub mod analyzer;
+pub mod reader;
+pub mod writer;
+
+pub(crate) const MAGIC: &[u8; 4] = b"OPKT";
+pub(crate) const VERSION: u8 = 1;
+pub(crate) const HEADER_SIZE: usize = 16;
+
+/// 单个数据包的最大长度，超过该长度的包长视为数据损坏
+pub(crate) const MAX_PACKET_SIZE: usize = u16::MAX as usize;
+
+/// 数据包流的头部信息
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PacketStreamHeader {
+    /// 编码时使用的采样率（Hz）
+    pub sample_rate: u32,
+    /// 声道数
+    pub channels: u32,
+    /// 每帧时长（微秒），0 表示帧长不固定或未知
+    pub frame_duration_us: u32,
+}
+
+impl PacketStreamHeader {
+    pub(crate) fn to_bytes(self) -> [u8; HEADER_SIZE] {
+        let mut header = [0u8; HEADER_SIZE];
+        header[..4].copy_from_slice(MAGIC);
+        header[4] = VERSION;
+        header[5] = self.channels as u8;
+        header[8..12].copy_from_slice(&self.sample_rate.to_le_bytes());
+        header[12..16].copy_from_slice(&self.frame_duration_us.to_le_bytes());
+        header
+    }
+
+    pub(crate) fn parse(header: &[u8; HEADER_SIZE]) -> Result<Self, OpusError> {
+        if &header[..4] != MAGIC {
+            return Err(format_error("Not an Opus packet stream"));
+        }
+        if header[4] != VERSION {
+            return Err(format_error("Unsupported packet stream version"));
+        }
+
+        let header = Self {
+            channels: header[5] as u32,
+            sample_rate: u32::from_le_bytes(header[8..12].try_into().unwrap()),
+            frame_duration_us: u32::from_le_bytes(header[12..16].try_into().unwrap()),
+        };
+        header.check().map_err(format_error)?;
+
+        Ok(header)
+    }
+
+    /// 校验调用者提供的头部参数
+    pub(crate) fn validate(&self) -> Result<(), OpusError> {
+        self.check()
+            .map_err(|message| OpusError::new(ErrorCode::BadArg as c_int, message))
+    }
+
+    fn check(&self) -> Result<(), &'static str> {
+        if !matches!(self.sample_rate, 8000 | 12000 | 16000 | 24000 | 48000) {
+            return Err("Invalid sample rate in packet stream header");
+        }
+        if self.channels == 0 || self.channels > u8::MAX as u32 {
+            return Err("Invalid channel count in packet stream header");
+        }
+
+        Ok(())
+    }
+}
+
+/// 将包长编码为 LEB128 变长整数
+pub(crate) fn write_varint(mut value: usize, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// 读取 LEB128 变长整数，流在第一个字节之前结束时返回 `None`
+pub(crate) fn read_varint<R: Read>(reader: &mut R) -> Result<Option<usize>, OpusError> {
+    let mut value = 0usize;
+    for i in 0..3 {
+        let mut byte = [0u8; 1];
+        if reader.read(&mut byte)? == 0 {
+            if i == 0 {
+                return Ok(None);
+            }
+            return Err(format_error("Truncated packet length"));
+        }
+        value |= ((byte[0] & 0x7f) as usize) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            if value > MAX_PACKET_SIZE {
+                break;
+            }
+            return Ok(Some(value));
+        }
+    }
+
+    Err(format_error("Packet length too large"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_roundtrip() {
+        for value in [0, 1, 127, 128, 300, 16383, 16384, MAX_PACKET_SIZE] {
+            let mut bytes = Vec::new();
+            write_varint(value, &mut bytes);
+            assert_eq!(read_varint(&mut bytes.as_slice()).unwrap(), Some(value));
+        }
+        assert_eq!(read_varint(&mut [].as_slice()).unwrap(), None);
+        assert!(read_varint(&mut [0x80].as_slice()).is_err());
+        assert!(read_varint(&mut [0xff, 0xff, 0xff, 0x01].as_slice()).is_err());
+        assert!(read_varint(&mut [0x80, 0x80, 0x04].as_slice()).is_err());
+    }
+}
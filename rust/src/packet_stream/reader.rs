@@ -0,0 +1,594 @@
+use std::{
+    ffi::{c_char, c_int, CStr},
+    fs::File,
+    io::{BufReader, Cursor, Read},
+    path::Path,
+};
+
+use opus_rs::ErrorCode;
+
+use super::{read_varint, PacketStreamHeader, HEADER_SIZE, MAX_PACKET_SIZE};
+use crate::{
+    error::{format_error, OpusError},
+    handle,
+    utils::{ffi_exec, invalid_input, Source},
+};
+
+/// 数据包的分帧方式
+enum Framing {
+    /// 带头部、每个包前有 LEB128 包长的数据包流
+    LengthPrefixed,
+    /// 旧版录音：没有头部，所有包都是相同的固定长度（CBR）
+    Fixed(usize),
+}
+
+/// 数据包流读取器不透明指针类型
+pub struct PacketStreamReader {
+    source: Source,
+    header: PacketStreamHeader,
+    framing: Framing,
+    // 因输出缓冲区不足而未取走的数据包
+    pending: Option<Vec<u8>>,
+}
+
+impl PacketStreamReader {
+    /// 打开数据包流文件并读取头部
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, OpusError> {
+        let file = File::open(path)?;
+        Self::new(Source::File(BufReader::new(file)))
+    }
+
+    /// 从内存中的数据包流创建读取器
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, OpusError> {
+        Self::new(Source::Memory(Cursor::new(data)))
+    }
+
+    /// 打开没有头部、固定包长的旧版录音文件，头部信息由调用者提供
+    pub fn open_legacy<P: AsRef<Path>>(
+        path: P,
+        packet_size: usize,
+        header: PacketStreamHeader,
+    ) -> Result<Self, OpusError> {
+        let file = File::open(path)?;
+        Self::new_legacy(Source::File(BufReader::new(file)), packet_size, header)
+    }
+
+    /// 从内存中没有头部、固定包长的旧版录音创建读取器
+    pub fn legacy_from_bytes(
+        data: Vec<u8>,
+        packet_size: usize,
+        header: PacketStreamHeader,
+    ) -> Result<Self, OpusError> {
+        Self::new_legacy(Source::Memory(Cursor::new(data)), packet_size, header)
+    }
+
+    fn new(mut source: Source) -> Result<Self, OpusError> {
+        let mut header = [0u8; HEADER_SIZE];
+        source
+            .read_exact(&mut header)
+            .map_err(|_| format_error("Not an Opus packet stream"))?;
+        let header = PacketStreamHeader::parse(&header)?;
+
+        Ok(Self {
+            source,
+            header,
+            framing: Framing::LengthPrefixed,
+            pending: None,
+        })
+    }
+
+    fn new_legacy(
+        source: Source,
+        packet_size: usize,
+        header: PacketStreamHeader,
+    ) -> Result<Self, OpusError> {
+        if packet_size == 0 || packet_size > MAX_PACKET_SIZE {
+            return Err(OpusError::new(
+                ErrorCode::BadArg as c_int,
+                &format!("Packet size must be between 1 and {MAX_PACKET_SIZE}"),
+            ));
+        }
+        header.validate()?;
+
+        Ok(Self {
+            source,
+            header,
+            framing: Framing::Fixed(packet_size),
+            pending: None,
+        })
+    }
+
+    /// 数据包流的头部信息
+    pub fn header(&self) -> &PacketStreamHeader {
+        &self.header
+    }
+
+    /// 读取下一个数据包，到达流末尾时返回 `None`
+    ///
+    /// 旧版录音末尾不足一个包长的数据会被忽略。
+    pub fn next_packet(&mut self) -> Result<Option<Vec<u8>>, OpusError> {
+        if let Some(packet) = self.pending.take() {
+            return Ok(Some(packet));
+        }
+
+        let size = match self.framing {
+            Framing::LengthPrefixed => match read_varint(&mut self.source)? {
+                Some(0) => return Err(format_error("Empty packet in packet stream")),
+                Some(size) => size,
+                None => return Ok(None),
+            },
+            Framing::Fixed(size) => size,
+        };
+
+        let mut packet = vec![0u8; size];
+        match self.source.read_exact(&mut packet) {
+            Ok(()) => Ok(Some(packet)),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => match self.framing {
+                Framing::LengthPrefixed => Err(format_error("Truncated packet in packet stream")),
+                Framing::Fixed(_) => Ok(None),
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 读取下一个数据包到 `output`，返回包长，0 表示已到达流末尾
+    ///
+    /// `output` 放不下时返回 `BufferTooSmall` 错误，数据包保留到下一次读取。
+    pub fn read_packet(&mut self, output: &mut [u8]) -> Result<usize, OpusError> {
+        let Some(packet) = self.next_packet()? else {
+            return Ok(0);
+        };
+        if packet.len() > output.len() {
+            let message = format!(
+                "Output buffer too small: {} bytes required, {} available",
+                packet.len(),
+                output.len()
+            );
+            self.pending = Some(packet);
+            return Err(OpusError::new(ErrorCode::BufferTooSmall as c_int, &message));
+        }
+
+        output[..packet.len()].copy_from_slice(&packet);
+        Ok(packet.len())
+    }
+}
+
+/// 打开数据包流文件
+///
+/// # 参数
+///
+/// * `path` - 输入文件路径（UTF-8 编码的 C 字符串）
+/// * `result` - 输出参数，用于接收创建的读取器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），文件不是数据包流格式时返回格式错误
+///
+/// # 安全性
+///
+/// 调用者负责在不再需要时调用 `opus_ffi_free_packet_reader` 释放返回的读取器。
+///
+/// # 示例
+///
+/// ```c
+/// PacketStreamReader *reader = NULL;
+/// OpusError error = {0, NULL};
+/// if (opus_ffi_packet_reader_open_file("/tmp/voice.opkt", &reader, &error) == 0) {
+///     PacketStreamHeader header;
+///     opus_ffi_packet_reader_get_header(reader, &header, &error);
+///     uint8_t packet[4000];
+///     size_t packet_size = 0;
+///     while (opus_ffi_packet_reader_next(reader, packet, sizeof(packet), &packet_size, &error) == 0
+///            && packet_size > 0) {
+///         opus_ffi_decode(decoder, packet, packet_size, pcm, pcm_size, false, &samples, &error);
+///     }
+///     opus_ffi_free_packet_reader(reader);
+/// }
+/// ```
+#[export_name = "opus_ffi_packet_reader_open_file"]
+pub extern "C" fn packet_reader_open_file(
+    path: *const c_char,
+    result: *mut *mut PacketStreamReader,
+    error: *mut OpusError,
+) -> c_int {
+    if path.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return invalid_input(error),
+    };
+
+    ffi_exec(error, || {
+        let reader = PacketStreamReader::open(path)?;
+        unsafe {
+            *result = handle::into_handle(reader);
+        }
+
+        Ok(())
+    })
+}
+
+/// 从内存中的数据包流创建读取器
+///
+/// # 参数
+///
+/// * `data` - 指向数据包流数据的指针
+/// * `data_size` - 数据长度（字节数）
+/// * `result` - 输出参数，用于接收创建的读取器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 数据会被复制，调用返回后即可释放 `data`
+#[export_name = "opus_ffi_packet_reader_open_memory"]
+pub extern "C" fn packet_reader_open_memory(
+    data: *const u8,
+    data_size: usize,
+    result: *mut *mut PacketStreamReader,
+    error: *mut OpusError,
+) -> c_int {
+    if data.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let data = unsafe { std::slice::from_raw_parts(data, data_size) }.to_vec();
+        let reader = PacketStreamReader::from_bytes(data)?;
+        unsafe {
+            *result = handle::into_handle(reader);
+        }
+
+        Ok(())
+    })
+}
+
+/// 打开没有头部、固定包长的旧版录音文件
+///
+/// # 参数
+///
+/// * `path` - 输入文件路径（UTF-8 编码的 C 字符串）
+/// * `packet_size` - 每个数据包的固定长度（字节数），例如现有录音使用的 80，范围 1 ~ 65535
+/// * `sample_rate` - 录音编码时使用的采样率（Hz）
+/// * `channels` - 录音的声道数
+/// * `result` - 输出参数，用于接收创建的读取器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 旧版录音只能用于 CBR 编码、包长固定的数据，文件末尾不足一个包长的数据会被忽略
+/// * `opus_ffi_packet_reader_get_header` 返回调用者传入的参数，`frame_duration_us` 为 0
+#[export_name = "opus_ffi_packet_reader_open_legacy_file"]
+pub extern "C" fn packet_reader_open_legacy_file(
+    path: *const c_char,
+    packet_size: usize,
+    sample_rate: u32,
+    channels: u32,
+    result: *mut *mut PacketStreamReader,
+    error: *mut OpusError,
+) -> c_int {
+    if path.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return invalid_input(error),
+    };
+    let header = PacketStreamHeader {
+        sample_rate,
+        channels,
+        frame_duration_us: 0,
+    };
+
+    ffi_exec(error, || {
+        let reader = PacketStreamReader::open_legacy(path, packet_size, header)?;
+        unsafe {
+            *result = handle::into_handle(reader);
+        }
+
+        Ok(())
+    })
+}
+
+/// 从内存中没有头部、固定包长的旧版录音创建读取器
+///
+/// # 参数
+///
+/// * `data` - 指向录音数据的指针
+/// * `data_size` - 数据长度（字节数）
+/// * `packet_size` - 每个数据包的固定长度（字节数），范围 1 ~ 65535
+/// * `sample_rate` - 录音编码时使用的采样率（Hz）
+/// * `channels` - 录音的声道数
+/// * `result` - 输出参数，用于接收创建的读取器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 数据会被复制，调用返回后即可释放 `data`
+#[export_name = "opus_ffi_packet_reader_open_legacy_memory"]
+pub extern "C" fn packet_reader_open_legacy_memory(
+    data: *const u8,
+    data_size: usize,
+    packet_size: usize,
+    sample_rate: u32,
+    channels: u32,
+    result: *mut *mut PacketStreamReader,
+    error: *mut OpusError,
+) -> c_int {
+    if data.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    let header = PacketStreamHeader {
+        sample_rate,
+        channels,
+        frame_duration_us: 0,
+    };
+
+    ffi_exec(error, || {
+        let data = unsafe { std::slice::from_raw_parts(data, data_size) }.to_vec();
+        let reader = PacketStreamReader::legacy_from_bytes(data, packet_size, header)?;
+        unsafe {
+            *result = handle::into_handle(reader);
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取数据包流的头部信息
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `result` - 输出参数，用于接收头部信息，可据此创建解码器
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_packet_reader_get_header"]
+pub extern "C" fn packet_reader_get_header(
+    reader: *mut PacketStreamReader,
+    result: *mut PacketStreamHeader,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let reader = handle::get_mut(reader)?;
+        unsafe {
+            (*result) = *reader.header();
+        }
+
+        Ok(())
+    })
+}
+
+/// 读取下一个数据包
+///
+/// # 参数
+///
+/// * `reader` - 之前创建的读取器实例
+/// * `output` - 指向输出缓冲区的指针，用于存储数据包
+/// * `output_size` - 输出缓冲区的大小（字节数），建议不小于 4000
+/// * `packet_size` - 输出参数，数据包长度（字节数），`0` 表示已到达流末尾
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 缓冲区放不下数据包时返回 `BufferTooSmall` 错误，数据包不会被丢弃，可以换用更大的缓冲区重新读取
+#[export_name = "opus_ffi_packet_reader_next"]
+pub extern "C" fn packet_reader_next(
+    reader: *mut PacketStreamReader,
+    output: *mut u8,
+    output_size: usize,
+    packet_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if reader.is_null() || output.is_null() || packet_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut reader = handle::get_mut(reader)?;
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size) };
+        let size = reader.read_packet(output)?;
+        unsafe {
+            (*packet_size) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 释放数据包流读取器实例
+///
+/// # 参数
+///
+/// * `reader` - 通过 `packet_reader_open_*` 系列函数创建的读取器指针
+///
+/// # 安全性
+///
+/// * 如果 `reader` 为 `NULL`，函数不会执行任何操作
+/// * 释放后，`reader` 指针将不再有效
+/// * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `reader` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `reader` 不是有效的读取器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+#[export_name = "opus_ffi_free_packet_reader"]
+pub extern "C" fn free_packet_reader(reader: *mut PacketStreamReader) -> c_int {
+    match handle::release(reader) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet_stream::writer::PacketStreamWriter;
+    use std::ptr;
+
+    const HEADER: PacketStreamHeader = PacketStreamHeader {
+        sample_rate: 16000,
+        channels: 1,
+        frame_duration_us: 20000,
+    };
+
+    #[test]
+    fn test_null_checks_packet_reader() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = packet_reader_open_file(ptr::null(), ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+
+        let res = packet_reader_next(
+            ptr::null_mut(),
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        let data = PacketStreamWriter::in_memory(HEADER)
+            .unwrap()
+            .finish()
+            .unwrap()
+            .unwrap();
+        let mut reader = ptr::null_mut();
+        let res =
+            packet_reader_open_memory(data.as_ptr(), data.len(), &mut reader, ptr::null_mut());
+        assert_eq!(res, 0);
+        assert_eq!(free_packet_reader(reader), 0);
+
+        // 已释放的句柄
+        let mut header = HEADER;
+        assert_eq!(
+            packet_reader_get_header(reader, &mut header, ptr::null_mut()),
+            invalid_handle
+        );
+        assert_eq!(free_packet_reader(reader), invalid_handle);
+
+        // 把写入器当作读取器使用
+        let mut writer = ptr::null_mut();
+        let res = crate::packet_stream::writer::packet_writer_open_memory(
+            16000,
+            1,
+            20000,
+            &mut writer,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        let wrong_type = writer as *mut PacketStreamReader;
+        let mut output = [0u8; 400];
+        let mut size = 0;
+        let res = packet_reader_next(
+            wrong_type,
+            output.as_mut_ptr(),
+            400,
+            &mut size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_packet_reader(wrong_type), invalid_handle);
+        assert_eq!(crate::packet_stream::writer::free_packet_writer(writer), 0);
+    }
+
+    #[test]
+    fn test_variable_size_roundtrip() {
+        let packets: Vec<Vec<u8>> = [1usize, 80, 127, 128, 300, 1275]
+            .iter()
+            .map(|&len| (0..len).map(|i| i as u8).collect())
+            .collect();
+
+        let mut writer = PacketStreamWriter::in_memory(HEADER).unwrap();
+        for packet in &packets {
+            writer.write_packet(packet).unwrap();
+        }
+        let data = writer.finish().unwrap().unwrap();
+
+        let mut reader = PacketStreamReader::from_bytes(data).unwrap();
+        assert_eq!(*reader.header(), HEADER);
+        for packet in &packets {
+            assert_eq!(reader.next_packet().unwrap().as_ref(), Some(packet));
+        }
+        assert_eq!(reader.next_packet().unwrap(), None);
+    }
+
+    #[test]
+    fn test_buffer_too_small_keeps_packet() {
+        let mut writer = PacketStreamWriter::in_memory(HEADER).unwrap();
+        writer.write_packet(&[7u8; 200]).unwrap();
+        let data = writer.finish().unwrap().unwrap();
+
+        let mut reader = PacketStreamReader::from_bytes(data).unwrap();
+        let mut small = [0u8; 100];
+        let err = reader.read_packet(&mut small).unwrap_err();
+        assert_eq!(err.code, ErrorCode::BufferTooSmall as c_int);
+
+        let mut large = [0u8; 400];
+        assert_eq!(reader.read_packet(&mut large).unwrap(), 200);
+        assert_eq!(reader.read_packet(&mut large).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_legacy_fixed_size() {
+        let mut data = vec![1u8; 80 * 3];
+        data.extend_from_slice(&[2u8; 10]);
+
+        let mut reader = PacketStreamReader::legacy_from_bytes(data.clone(), 80, HEADER).unwrap();
+        let mut count = 0;
+        while let Some(packet) = reader.next_packet().unwrap() {
+            assert_eq!(packet.len(), 80);
+            count += 1;
+        }
+        // 末尾不足一个包长的数据被忽略
+        assert_eq!(count, 3);
+
+        // 旧版录音没有头部，不能按新格式打开
+        assert!(PacketStreamReader::from_bytes(data).is_err());
+
+        // 包长与变长包长分帧使用相同的上限
+        for packet_size in [0, MAX_PACKET_SIZE + 1, usize::MAX] {
+            let err = PacketStreamReader::legacy_from_bytes(Vec::new(), packet_size, HEADER)
+                .err()
+                .unwrap();
+            assert_eq!(err.code, ErrorCode::BadArg as c_int);
+        }
+        assert!(PacketStreamReader::legacy_from_bytes(Vec::new(), MAX_PACKET_SIZE, HEADER).is_ok());
+    }
+}
@@ -0,0 +1,362 @@
+use std::{
+    ffi::{c_char, c_int, CStr},
+    fs::File,
+    io::BufWriter,
+    path::Path,
+};
+
+use opus_rs::ErrorCode;
+
+use super::{write_varint, PacketStreamHeader, MAX_PACKET_SIZE};
+use crate::{
+    error::OpusError,
+    handle,
+    utils::{ffi_exec, into_raw_buffer, invalid_input, Sink},
+};
+
+/// 数据包流写入器不透明指针类型
+pub struct PacketStreamWriter {
+    sink: Sink,
+    finished: bool,
+}
+
+impl PacketStreamWriter {
+    /// 创建写入到文件的数据包流写入器，并写出头部
+    pub fn create<P: AsRef<Path>>(path: P, header: PacketStreamHeader) -> Result<Self, OpusError> {
+        header.validate()?;
+        let file = File::create(path)?;
+        Self::new(Sink::File(BufWriter::new(file)), header)
+    }
+
+    /// 创建写入到内存的数据包流写入器，数据在 `finish` 时返回
+    pub fn in_memory(header: PacketStreamHeader) -> Result<Self, OpusError> {
+        header.validate()?;
+        Self::new(Sink::Memory(Vec::new()), header)
+    }
+
+    fn new(mut sink: Sink, header: PacketStreamHeader) -> Result<Self, OpusError> {
+        sink.write(&header.to_bytes())?;
+
+        Ok(Self {
+            sink,
+            finished: false,
+        })
+    }
+
+    /// 写入一个 Opus 数据包
+    pub fn write_packet(&mut self, packet: &[u8]) -> Result<(), OpusError> {
+        self.check_not_finished()?;
+        if packet.is_empty() || packet.len() > MAX_PACKET_SIZE {
+            return Err(OpusError::new(
+                ErrorCode::BadArg as c_int,
+                "Packet size must be between 1 and 65535 bytes",
+            ));
+        }
+
+        let mut record = Vec::with_capacity(packet.len() + 3);
+        write_varint(packet.len(), &mut record);
+        record.extend_from_slice(packet);
+        self.sink.write(&record)?;
+
+        Ok(())
+    }
+
+    /// 结束写入，对于内存写入器返回完整的数据，文件写入器返回 `None`
+    pub fn finish(&mut self) -> Result<Option<Vec<u8>>, OpusError> {
+        self.check_not_finished()?;
+        self.finished = true;
+
+        Ok(self.sink.finish()?)
+    }
+
+    fn check_not_finished(&self) -> Result<(), OpusError> {
+        if self.finished {
+            return Err(OpusError::new(
+                ErrorCode::InvalidState as c_int,
+                "Packet stream writer already finished",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// 创建写入到文件的数据包流写入器
+///
+/// # 参数
+///
+/// * `path` - 输出文件路径（UTF-8 编码的 C 字符串）。文件已存在时会被覆盖
+/// * `sample_rate` - 编码时使用的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `channels` - 声道数
+/// * `frame_duration_us` - 每帧时长（微秒），例如 20ms 帧为 20000；帧长不固定时传 0
+/// * `result` - 输出参数，用于接收创建的写入器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 安全性
+///
+/// 调用者负责在不再需要时调用 `opus_ffi_free_packet_writer` 释放返回的写入器。
+///
+/// # 示例
+///
+/// ```c
+/// PacketStreamWriter *writer = NULL;
+/// OpusError error = {0, NULL};
+/// int res = opus_ffi_packet_writer_open_file("/tmp/voice.opkt", 16000, 1, 20000, &writer, &error);
+/// if (res == 0) {
+///     // 每次编码得到的数据包长度可以不同
+///     opus_ffi_packet_writer_write(writer, packet, packet_size, &error);
+///     opus_ffi_packet_writer_finish(writer, NULL, NULL, &error);
+///     opus_ffi_free_packet_writer(writer);
+/// }
+/// ```
+#[export_name = "opus_ffi_packet_writer_open_file"]
+pub extern "C" fn packet_writer_open_file(
+    path: *const c_char,
+    sample_rate: u32,
+    channels: u32,
+    frame_duration_us: u32,
+    result: *mut *mut PacketStreamWriter,
+    error: *mut OpusError,
+) -> c_int {
+    if path.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(path) => path,
+        Err(_) => return invalid_input(error),
+    };
+    let header = PacketStreamHeader {
+        sample_rate,
+        channels,
+        frame_duration_us,
+    };
+
+    ffi_exec(error, || {
+        let writer = PacketStreamWriter::create(path, header)?;
+        unsafe {
+            *result = handle::into_handle(writer);
+        }
+
+        Ok(())
+    })
+}
+
+/// 创建写入到内存的数据包流写入器
+///
+/// # 参数
+///
+/// * `sample_rate` - 编码时使用的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `channels` - 声道数
+/// * `frame_duration_us` - 每帧时长（微秒），帧长不固定时传 0
+/// * `result` - 输出参数，用于接收创建的写入器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 数据保存在内存中，通过 `opus_ffi_packet_writer_finish` 一次性取出
+#[export_name = "opus_ffi_packet_writer_open_memory"]
+pub extern "C" fn packet_writer_open_memory(
+    sample_rate: u32,
+    channels: u32,
+    frame_duration_us: u32,
+    result: *mut *mut PacketStreamWriter,
+    error: *mut OpusError,
+) -> c_int {
+    if result.is_null() {
+        return invalid_input(error);
+    }
+
+    let header = PacketStreamHeader {
+        sample_rate,
+        channels,
+        frame_duration_us,
+    };
+
+    ffi_exec(error, || {
+        let writer = PacketStreamWriter::in_memory(header)?;
+        unsafe {
+            *result = handle::into_handle(writer);
+        }
+
+        Ok(())
+    })
+}
+
+/// 向数据包流写入一个 Opus 数据包
+///
+/// # 参数
+///
+/// * `writer` - 之前创建的写入器实例
+/// * `packet` - 指向 Opus 数据包的指针（通常是 `opus_ffi_encode` 的输出）
+/// * `packet_size` - 数据包长度（字节数），范围为 1~65535
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_packet_writer_write"]
+pub extern "C" fn packet_writer_write(
+    writer: *mut PacketStreamWriter,
+    packet: *const u8,
+    packet_size: usize,
+    error: *mut OpusError,
+) -> c_int {
+    if writer.is_null() || packet.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut writer = handle::get_mut(writer)?;
+        let packet = unsafe { std::slice::from_raw_parts(packet, packet_size) };
+        writer.write_packet(packet)
+    })
+}
+
+/// 结束数据包流写入
+///
+/// # 参数
+///
+/// * `writer` - 之前创建的写入器实例
+/// * `data` - 可选的输出参数。对于内存写入器，用于接收完整的数据，需使用 `opus_ffi_free_buffer` 释放；
+///   对于文件写入器，将被设置为 `NULL`
+/// * `data_size` - 可选的输出参数，`data` 的长度（字节数）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 结束后写入器不能再写入，但仍需调用 `opus_ffi_free_packet_writer` 释放
+#[export_name = "opus_ffi_packet_writer_finish"]
+pub extern "C" fn packet_writer_finish(
+    writer: *mut PacketStreamWriter,
+    data: *mut *mut u8,
+    data_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if writer.is_null() || data.is_null() != data_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut writer = handle::get_mut(writer)?;
+        let output = writer.finish()?;
+        if !data.is_null() {
+            match output {
+                Some(output) => into_raw_buffer(output, data, data_size),
+                None => unsafe {
+                    (*data) = std::ptr::null_mut();
+                    (*data_size) = 0;
+                },
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// 释放数据包流写入器实例
+///
+/// # 参数
+///
+/// * `writer` - 通过 `opus_ffi_packet_writer_open_file` 或 `opus_ffi_packet_writer_open_memory` 创建的写入器指针
+///
+/// # 安全性
+///
+/// * 如果 `writer` 为 `NULL`，函数不会执行任何操作
+/// * 释放后，`writer` 指针将不再有效
+/// * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `writer` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `writer` 不是有效的写入器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+#[export_name = "opus_ffi_free_packet_writer"]
+pub extern "C" fn free_packet_writer(writer: *mut PacketStreamWriter) -> c_int {
+    match handle::release(writer) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_null_checks_packet_writer() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = packet_writer_open_file(
+            ptr::null(),
+            16000,
+            1,
+            20000,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, expected_error);
+
+        let res = packet_writer_write(ptr::null_mut(), ptr::null(), 0, ptr::null_mut());
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        let mut writer = ptr::null_mut();
+        assert_eq!(
+            packet_writer_open_memory(16000, 1, 20000, &mut writer, ptr::null_mut()),
+            0
+        );
+        assert_eq!(free_packet_writer(writer), 0);
+
+        // 已释放的句柄
+        let packet = [0xf8u8];
+        let res = packet_writer_write(writer, packet.as_ptr(), 1, ptr::null_mut());
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_packet_writer(writer), invalid_handle);
+
+        // 把重组器当作写入器使用
+        let mut repacketizer = ptr::null_mut();
+        assert_eq!(
+            crate::repacketizer::new_repacketizer(&mut repacketizer, ptr::null_mut()),
+            0
+        );
+        let wrong_type = repacketizer as *mut PacketStreamWriter;
+        let res = packet_writer_finish(
+            wrong_type,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_packet_writer(wrong_type), invalid_handle);
+        assert_eq!(crate::repacketizer::free_repacketizer(repacketizer), 0);
+    }
+
+    #[test]
+    fn test_invalid_header_rejected() {
+        let header = PacketStreamHeader {
+            sample_rate: 44100,
+            channels: 1,
+            frame_duration_us: 20000,
+        };
+        assert!(PacketStreamWriter::in_memory(header).is_err());
+    }
+}
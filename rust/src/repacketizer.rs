@@ -0,0 +1,534 @@
+use std::ffi::c_int;
+
+use opus_rs::RepacketizerState;
+
+use crate::{
+    error::OpusError,
+    handle,
+    utils::{ffi_exec, invalid_input},
+};
+
+/// Opus 重组器不透明指针类型
+///
+/// 保存已加入数据包的副本，调用者传入的缓冲区在 `opus_ffi_repacketizer_cat` 返回后即可释放。
+pub struct Repacketizer {
+    inner: opus_rs::Repacketizer,
+    packets: Vec<Vec<u8>>,
+}
+
+impl Repacketizer {
+    pub fn new() -> Result<Self, OpusError> {
+        Ok(Self {
+            inner: opus_rs::Repacketizer::new()?,
+            packets: Vec::new(),
+        })
+    }
+
+    /// 加入一个数据包。所有数据包的 TOC 配置必须相同，总时长不能超过 120ms
+    pub fn cat(&mut self, packet: &[u8]) -> Result<(), OpusError> {
+        self.packets.push(packet.to_vec());
+        if let Err(e) = self.state() {
+            self.packets.pop();
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// 已加入的帧数
+    pub fn nb_frames(&mut self) -> Result<usize, OpusError> {
+        Ok(self.state()?.get_nb_frames())
+    }
+
+    /// 把全部帧合并为一个数据包，返回输出的字节数
+    pub fn out(&mut self, output: &mut [u8]) -> Result<usize, OpusError> {
+        Ok(self.state()?.out(output)?)
+    }
+
+    /// 把 `[begin, end)` 范围内的帧合并为一个数据包，返回输出的字节数
+    pub fn out_range(
+        &mut self,
+        begin: usize,
+        end: usize,
+        output: &mut [u8],
+    ) -> Result<usize, OpusError> {
+        Ok(self.state()?.out_range(begin, end, output)?)
+    }
+
+    /// 清空已加入的数据包
+    pub fn reset(&mut self) {
+        self.packets.clear();
+    }
+
+    /// 用保存的数据包重建底层重组器的状态
+    fn state(&mut self) -> Result<RepacketizerState<'_, '_>, OpusError> {
+        let Self { inner, packets } = self;
+        let mut state = inner.begin();
+        for packet in packets.iter() {
+            state.cat(packet)?;
+        }
+
+        Ok(state)
+    }
+}
+
+/// 创建新的 Opus 重组器
+///
+/// # 参数
+///
+/// * `result` - 输出参数，用于接收创建的重组器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 安全性
+///
+/// 调用者负责在不再需要时调用 `opus_ffi_free_repacketizer` 释放返回的重组器。
+///
+/// # 示例
+///
+/// ```c
+/// // 把 3 个 20ms 数据包合并为一个 60ms 数据包
+/// Repacketizer *rp = NULL;
+/// OpusError error = {0, NULL};
+/// if (opus_ffi_new_repacketizer(&rp, &error) == 0) {
+///     for (int i = 0; i < 3; i++) {
+///         opus_ffi_repacketizer_cat(rp, packets[i], packet_sizes[i], &error);
+///     }
+///     uint8_t merged[4000];
+///     size_t merged_size = 0;
+///     opus_ffi_repacketizer_out(rp, merged, sizeof(merged), &merged_size, &error);
+///     opus_ffi_free_repacketizer(rp);
+/// }
+/// ```
+#[export_name = "opus_ffi_new_repacketizer"]
+pub extern "C" fn new_repacketizer(result: *mut *mut Repacketizer, error: *mut OpusError) -> c_int {
+    if result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let repacketizer = Repacketizer::new()?;
+        unsafe {
+            *result = handle::into_handle(repacketizer);
+        }
+
+        Ok(())
+    })
+}
+
+/// 向重组器加入一个数据包
+///
+/// # 参数
+///
+/// * `repacketizer` - 之前创建的重组器实例
+/// * `data` - 指向 Opus 数据包的指针，数据会被复制
+/// * `data_size` - 数据包长度（字节数）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 加入的所有数据包必须具有相同的 TOC 配置（模式、带宽、帧长、声道），
+///   否则返回 `OPUS_INVALID_PACKET`，此时重组器中已有的数据包不受影响
+/// * 总时长超过 120ms 时同样返回 `OPUS_INVALID_PACKET`
+#[export_name = "opus_ffi_repacketizer_cat"]
+pub extern "C" fn repacketizer_cat(
+    repacketizer: *mut Repacketizer,
+    data: *const u8,
+    data_size: usize,
+    error: *mut OpusError,
+) -> c_int {
+    if repacketizer.is_null() || data.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut repacketizer = handle::get_mut(repacketizer)?;
+        let data = unsafe { std::slice::from_raw_parts(data, data_size) };
+        repacketizer.cat(data)
+    })
+}
+
+/// 获取重组器中已加入的帧数
+///
+/// # 参数
+///
+/// * `repacketizer` - 之前创建的重组器实例
+/// * `result` - 输出参数，用于接收帧数。拆分数据包时可据此逐帧调用 `opus_ffi_repacketizer_out_range`
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_repacketizer_get_nb_frames"]
+pub extern "C" fn repacketizer_get_nb_frames(
+    repacketizer: *mut Repacketizer,
+    result: *mut u32,
+    error: *mut OpusError,
+) -> c_int {
+    if repacketizer.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut repacketizer = handle::get_mut(repacketizer)?;
+        let frames = repacketizer.nb_frames()?;
+        unsafe {
+            (*result) = frames as u32;
+        }
+
+        Ok(())
+    })
+}
+
+/// 把重组器中的全部帧输出为一个数据包
+///
+/// # 参数
+///
+/// * `repacketizer` - 之前创建的重组器实例
+/// * `output` - 指向输出缓冲区的指针
+/// * `output_size` - 输出缓冲区的大小（字节数）
+/// * `result_size` - 输出参数，输出数据包的长度（字节数）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），缓冲区不足时返回 `OPUS_BUFFER_TOO_SMALL`
+///
+/// # 注意事项
+///
+/// * 输出后重组器中的数据包仍然保留，需要调用 `opus_ffi_repacketizer_reset` 才能开始下一轮合并
+#[export_name = "opus_ffi_repacketizer_out"]
+pub extern "C" fn repacketizer_out(
+    repacketizer: *mut Repacketizer,
+    output: *mut u8,
+    output_size: usize,
+    result_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if repacketizer.is_null() || output.is_null() || result_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut repacketizer = handle::get_mut(repacketizer)?;
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size) };
+        let size = repacketizer.out(output)?;
+        unsafe {
+            (*result_size) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 把重组器中指定范围的帧输出为一个数据包
+///
+/// # 参数
+///
+/// * `repacketizer` - 之前创建的重组器实例
+/// * `begin` - 第一帧的序号（从 0 开始）
+/// * `end` - 最后一帧之后的序号，范围 `[begin, end)` 必须非空且不超过已加入的帧数
+/// * `output` - 指向输出缓冲区的指针
+/// * `output_size` - 输出缓冲区的大小（字节数）
+/// * `result_size` - 输出参数，输出数据包的长度（字节数）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），范围无效时返回 `OPUS_BAD_ARG`
+///
+/// # 示例
+///
+/// ```c
+/// // 把一个 60ms 数据包拆分为 3 个 20ms 数据包
+/// opus_ffi_repacketizer_cat(rp, packet, packet_size, &error);
+/// uint32_t frames = 0;
+/// opus_ffi_repacketizer_get_nb_frames(rp, &frames, &error);
+/// for (uint32_t i = 0; i < frames; i++) {
+///     opus_ffi_repacketizer_out_range(rp, i, i + 1, out, sizeof(out), &out_size, &error);
+/// }
+/// opus_ffi_repacketizer_reset(rp, &error);
+/// ```
+#[export_name = "opus_ffi_repacketizer_out_range"]
+pub extern "C" fn repacketizer_out_range(
+    repacketizer: *mut Repacketizer,
+    begin: u32,
+    end: u32,
+    output: *mut u8,
+    output_size: usize,
+    result_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if repacketizer.is_null() || output.is_null() || result_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut repacketizer = handle::get_mut(repacketizer)?;
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size) };
+        let size = repacketizer.out_range(begin as usize, end as usize, output)?;
+        unsafe {
+            (*result_size) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 清空重组器中已加入的数据包
+///
+/// # 参数
+///
+/// * `repacketizer` - 之前创建的重组器实例
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_repacketizer_reset"]
+pub extern "C" fn repacketizer_reset(
+    repacketizer: *mut Repacketizer,
+    error: *mut OpusError,
+) -> c_int {
+    if repacketizer.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut repacketizer = handle::get_mut(repacketizer)?;
+        repacketizer.reset();
+
+        Ok(())
+    })
+}
+
+/// 释放 Opus 重组器实例
+///
+/// # 参数
+///
+/// * `repacketizer` - 通过 `opus_ffi_new_repacketizer` 创建的重组器指针
+///
+/// # 安全性
+///
+/// * 如果 `repacketizer` 为 `NULL`，函数不会执行任何操作
+/// * 释放后，`repacketizer` 指针将不再有效
+/// * 重复释放、传入已释放或其他类型的指针时不会释放任何内存，而是返回错误
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `repacketizer` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `repacketizer` 不是有效的重组器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+#[export_name = "opus_ffi_free_repacketizer"]
+pub extern "C" fn free_repacketizer(repacketizer: *mut Repacketizer) -> c_int {
+    match handle::release(repacketizer) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
+    }
+}
+
+/// 把数据包填充到指定长度（不改变解码结果）
+///
+/// # 参数
+///
+/// * `data` - 指向数据包缓冲区的指针，缓冲区容量至少为 `new_size` 字节
+/// * `data_size` - 数据包当前的长度（字节数）
+/// * `new_size` - 填充后的长度（字节数），不能小于 `data_size`
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功，数据包长度变为 `new_size`
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 常用于在 VBR 下把数据包补齐到固定长度，以便按固定包长传输
+#[export_name = "opus_ffi_packet_pad"]
+pub extern "C" fn packet_pad(
+    data: *mut u8,
+    data_size: usize,
+    new_size: usize,
+    error: *mut OpusError,
+) -> c_int {
+    if data.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let data = unsafe { std::slice::from_raw_parts_mut(data, new_size) };
+        opus_rs::packet::pad(data, data_size)?;
+
+        Ok(())
+    })
+}
+
+/// 去除数据包中的填充
+///
+/// # 参数
+///
+/// * `data` - 指向数据包的指针，结果原地写回
+/// * `data_size` - 数据包的长度（字节数）
+/// * `result_size` - 输出参数，去除填充后的长度（字节数）
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中），数据包不合法时返回 `OPUS_INVALID_PACKET`
+#[export_name = "opus_ffi_packet_unpad"]
+pub extern "C" fn packet_unpad(
+    data: *mut u8,
+    data_size: usize,
+    result_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if data.is_null() || result_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let data = unsafe { std::slice::from_raw_parts_mut(data, data_size) };
+        let size = opus_rs::packet::unpad(data)?;
+        unsafe {
+            (*result_size) = size;
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::packet_info;
+    use opus_rs::{Application, Channels, Encoder, ErrorCode};
+    use std::ptr;
+
+    fn encode_frames(count: usize) -> Vec<Vec<u8>> {
+        let mut encoder = Encoder::new(16000, Channels::Mono, Application::Voip).unwrap();
+        let mut packet = [0u8; 4000];
+        (0..count)
+            .map(|frame| {
+                let pcm: Vec<i16> = (0..320)
+                    .map(|i| (((frame * 320 + i) as f32 * 0.05).sin() * 6000.0) as i16)
+                    .collect();
+                let size = encoder.encode(&pcm, &mut packet).unwrap();
+                packet[..size].to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_null_checks_repacketizer() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = new_repacketizer(ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+
+        let res = repacketizer_cat(ptr::null_mut(), ptr::null(), 0, ptr::null_mut());
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        let mut repacketizer = ptr::null_mut();
+        assert_eq!(new_repacketizer(&mut repacketizer, ptr::null_mut()), 0);
+        assert_eq!(free_repacketizer(repacketizer), 0);
+
+        // 已释放的句柄
+        assert_eq!(
+            repacketizer_reset(repacketizer, ptr::null_mut()),
+            invalid_handle
+        );
+        assert_eq!(free_repacketizer(repacketizer), invalid_handle);
+
+        // 把解码器当作重组器使用
+        let mut decoder = ptr::null_mut();
+        assert_eq!(
+            crate::decoder::new_decoder(1, 16000, &mut decoder, ptr::null_mut()),
+            0
+        );
+        let wrong_type = decoder as *mut Repacketizer;
+        let mut frames = 0;
+        let res = repacketizer_get_nb_frames(wrong_type, &mut frames, ptr::null_mut());
+        assert_eq!(res, invalid_handle);
+        assert_eq!(free_repacketizer(wrong_type), invalid_handle);
+        assert_eq!(crate::decoder::free_decoder(decoder), 0);
+    }
+
+    #[test]
+    fn test_merge_and_split() {
+        let frames = encode_frames(3);
+        let mut repacketizer = Repacketizer::new().unwrap();
+        for frame in &frames {
+            repacketizer.cat(frame).unwrap();
+        }
+        assert_eq!(repacketizer.nb_frames().unwrap(), 3);
+
+        let mut merged = [0u8; 4000];
+        let size = repacketizer.out(&mut merged).unwrap();
+        let info = packet_info(&merged[..size], 16000).unwrap();
+        assert_eq!(info.frame_count, 3);
+        assert_eq!(info.samples_per_packet, 960);
+
+        repacketizer.reset();
+        repacketizer.cat(&merged[..size]).unwrap();
+        let mut split = [0u8; 4000];
+        for (i, frame) in frames.iter().enumerate() {
+            let size = repacketizer.out_range(i, i + 1, &mut split).unwrap();
+            assert_eq!(&split[..size], frame.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_cat_rejects_mismatched_packet() {
+        let frames = encode_frames(1);
+        let mut repacketizer = Repacketizer::new().unwrap();
+        repacketizer.cat(&frames[0]).unwrap();
+
+        // 配置 31：CELT 全带 20ms，与 SILK 数据包的 TOC 不同
+        let err = repacketizer.cat(&[0xf8]).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidPacket as c_int);
+        assert_eq!(repacketizer.nb_frames().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_pad_unpad() {
+        let frames = encode_frames(1);
+        let original = &frames[0];
+        let mut buffer = original.clone();
+        buffer.resize(original.len() + 50, 0);
+
+        let res = packet_pad(
+            buffer.as_mut_ptr(),
+            original.len(),
+            buffer.len(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(packet_info(&buffer, 16000).unwrap().samples_per_packet, 320);
+
+        let mut size = 0;
+        let res = packet_unpad(
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, 0);
+        assert_eq!(&buffer[..size], original.as_slice());
+    }
+}
@@ -0,0 +1,737 @@
+//! 流式编码：接受任意长度的 PCM，按固定帧长切分后编码
+//!
+//! 平台录音器每次回调的样本数通常不是整帧，`StreamEncoder` 把不足一帧的样本暂存到下一次写入，
+//! 编码出的数据包按顺序排队，由调用者逐个取出。
+
+use std::{collections::VecDeque, ffi::c_int, ptr};
+
+use opus_rs::{Application, Channels, ErrorCode};
+
+use crate::{
+    encoder::Encoder,
+    error::OpusError,
+    handle,
+    utils::{application_from, channels_from, ffi_exec, invalid_input},
+};
+
+/// 单个 Opus 数据包的最大长度
+const MAX_PACKET_SIZE: usize = 4000;
+
+/// 流式编码器不透明指针类型
+pub struct StreamEncoder {
+    encoder: Encoder,
+    // `opus_ffi_stream_encoder_get_encoder` 交给调用者的编码器句柄，尚未获取时为 NULL
+    encoder_handle: *mut Encoder,
+    channels: usize,
+    // 每声道样本数
+    frame_size: usize,
+    // 尚未凑满一帧的交错 PCM 样本，按写入时的格式暂存，两者最多只有一个非空
+    pending: Vec<i16>,
+    pending_float: Vec<f32>,
+    // 已编码、等待取出的数据包
+    packets: VecDeque<Vec<u8>>,
+}
+
+impl StreamEncoder {
+    /// 创建流式编码器，`frame_duration_us` 为每个数据包的时长（微秒）
+    pub fn new(
+        sample_rate: u32,
+        channels: Channels,
+        application: Application,
+        frame_duration_us: u32,
+    ) -> Result<Self, OpusError> {
+        if !matches!(
+            frame_duration_us,
+            2500 | 5000 | 10000 | 20000 | 40000 | 60000
+        ) {
+            return Err(OpusError::new(
+                ErrorCode::BadArg as c_int,
+                &format!("Invalid frame duration: {frame_duration_us}us"),
+            ));
+        }
+
+        let encoder = Encoder::new(sample_rate, channels, application)?;
+
+        Ok(Self {
+            encoder,
+            encoder_handle: ptr::null_mut(),
+            channels: channels as usize,
+            frame_size: (sample_rate as u64 * frame_duration_us as u64 / 1_000_000) as usize,
+            pending: Vec::new(),
+            pending_float: Vec::new(),
+            packets: VecDeque::new(),
+        })
+    }
+
+    /// 获取内部编码器，可用于调整比特率等参数
+    pub fn encoder_mut(&mut self) -> &mut Encoder {
+        &mut self.encoder
+    }
+
+    /// 写入交错的 16 位 PCM 样本，样本数可以是任意声道数的整数倍，返回新产生的数据包数量
+    ///
+    /// 整帧按 `Encoder::encode` 编码，结果与对同样的 PCM 调用 `opus_ffi_encode` 相同。
+    pub fn push(&mut self, pcm: &[i16]) -> Result<usize, OpusError> {
+        self.check_len(pcm.len())?;
+        // 之前写入的浮点样本不足一帧时，转换为整数后与新样本拼成一帧
+        if !self.pending_float.is_empty() {
+            let pending = self
+                .pending_float
+                .drain(..)
+                .map(|s| (s * 32768.0).round().clamp(-32768.0, 32767.0) as i16);
+            self.pending.extend(pending);
+        }
+
+        let encoder = &mut self.encoder;
+        let packets = &mut self.packets;
+        push_frames(
+            &mut self.pending,
+            pcm,
+            self.frame_size * self.channels,
+            |frame| encode_packet(packets, |packet| encoder.encode(frame, packet)),
+        )
+    }
+
+    /// 写入交错的浮点 PCM 样本，样本数可以是任意声道数的整数倍，返回新产生的数据包数量
+    pub fn push_float(&mut self, pcm: &[f32]) -> Result<usize, OpusError> {
+        self.check_len(pcm.len())?;
+        // 之前写入的整数样本不足一帧时，转换为浮点后与新样本拼成一帧
+        if !self.pending.is_empty() {
+            let pending = self.pending.drain(..).map(|s| s as f32 / 32768.0);
+            self.pending_float.extend(pending);
+        }
+
+        let encoder = &mut self.encoder;
+        let packets = &mut self.packets;
+        push_frames(
+            &mut self.pending_float,
+            pcm,
+            self.frame_size * self.channels,
+            |frame| encode_packet(packets, |packet| encoder.encode_float(frame, packet)),
+        )
+    }
+
+    /// 用静音补齐暂存的不足一帧的样本并编码，没有暂存样本时不做任何操作，返回新产生的数据包数量
+    pub fn flush(&mut self) -> Result<usize, OpusError> {
+        let frame_len = self.frame_size * self.channels;
+        let encoder = &mut self.encoder;
+        let packets = &mut self.packets;
+        if !self.pending.is_empty() {
+            self.pending.resize(frame_len, 0);
+            encode_packet(packets, |packet| encoder.encode(&self.pending, packet))?;
+            self.pending.clear();
+        } else if !self.pending_float.is_empty() {
+            self.pending_float.resize(frame_len, 0.0);
+            encode_packet(packets, |packet| {
+                encoder.encode_float(&self.pending_float, packet)
+            })?;
+            self.pending_float.clear();
+        } else {
+            return Ok(0);
+        }
+
+        Ok(1)
+    }
+
+    /// 等待取出的数据包数量
+    pub fn packet_count(&self) -> usize {
+        self.packets.len()
+    }
+
+    /// 取出下一个数据包，返回其长度，没有数据包时返回 0
+    ///
+    /// 缓冲区放不下时返回 `BufferTooSmall`，数据包保留在队列中。
+    pub fn next_packet(&mut self, output: &mut [u8]) -> Result<usize, OpusError> {
+        let Some(packet) = self.packets.front() else {
+            return Ok(0);
+        };
+        if packet.len() > output.len() {
+            return Err(OpusError::new(
+                ErrorCode::BufferTooSmall as c_int,
+                &format!(
+                    "Output buffer too small: {} bytes required, {} available",
+                    packet.len(),
+                    output.len()
+                ),
+            ));
+        }
+
+        let size = packet.len();
+        output[..size].copy_from_slice(packet);
+        self.packets.pop_front();
+
+        Ok(size)
+    }
+
+    fn check_len(&self, len: usize) -> Result<(), OpusError> {
+        if !len.is_multiple_of(self.channels) {
+            return Err(OpusError::new(
+                ErrorCode::BadArg as c_int,
+                "PCM sample count is not a multiple of the channel count",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// 把 `pcm` 接在暂存的样本之后按 `frame_len` 切分，对每个整帧调用 `encode`，不足一帧的部分留在 `pending`
+///
+/// 没有暂存样本时整帧直接从 `pcm` 编码，不会复制。返回编码的帧数。
+fn push_frames<T: Copy>(
+    pending: &mut Vec<T>,
+    mut pcm: &[T],
+    frame_len: usize,
+    mut encode: impl FnMut(&[T]) -> Result<(), OpusError>,
+) -> Result<usize, OpusError> {
+    let mut frames = 0;
+    if !pending.is_empty() {
+        let take = (frame_len - pending.len()).min(pcm.len());
+        pending.extend_from_slice(&pcm[..take]);
+        pcm = &pcm[take..];
+        if pending.len() < frame_len {
+            return Ok(0);
+        }
+        encode(pending)?;
+        pending.clear();
+        frames += 1;
+    }
+
+    let mut chunks = pcm.chunks_exact(frame_len);
+    for frame in &mut chunks {
+        encode(frame)?;
+        frames += 1;
+    }
+    pending.extend_from_slice(chunks.remainder());
+
+    Ok(frames)
+}
+
+/// 编码一个数据包并放入队列
+fn encode_packet(
+    packets: &mut VecDeque<Vec<u8>>,
+    encode: impl FnOnce(&mut [u8]) -> Result<usize, OpusError>,
+) -> Result<(), OpusError> {
+    let mut packet = [0u8; MAX_PACKET_SIZE];
+    let size = encode(&mut packet)?;
+    packets.push_back(packet[..size].to_vec());
+
+    Ok(())
+}
+
+/// 把队列中的数据包数量写入可选的输出参数
+fn write_packet_count(stream: &StreamEncoder, packet_count: *mut usize) {
+    if !packet_count.is_null() {
+        unsafe {
+            (*packet_count) = stream.packet_count();
+        }
+    }
+}
+
+/// 创建新的流式编码器
+///
+/// # 参数
+///
+/// * `channels` - 声道数：1 表示单声道（Mono），2 表示立体声（Stereo）。
+///   其他值返回错误（宽松模式下默认为单声道，见 `opus_ffi_set_lenient_mode`）
+/// * `sample_rate` - 输入 PCM 的采样率（Hz），支持的采样率：8000, 12000, 16000, 24000, 48000
+/// * `application` - 应用模式，取值与 `opus_ffi_new_encoder` 相同
+/// * `frame_duration_us` - 每个数据包的时长（微秒）：2500, 5000, 10000, 20000, 40000 或 60000
+/// * `result` - 输出参数，用于接收创建的流式编码器指针
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）。帧时长不合法时返回 `OPUS_BAD_ARG`
+///
+/// # 安全性
+///
+/// 调用者负责在不再需要时调用 `opus_ffi_free_stream_encoder` 释放返回的编码器。
+///
+/// # 示例
+///
+/// ```c
+/// StreamEncoder *stream = NULL;
+/// OpusError error = {0, NULL};
+/// int res = opus_ffi_new_stream_encoder(1, 16000, 1, 20000, &stream, &error);
+/// if (res == 0) {
+///     size_t count = 0;
+///     opus_ffi_stream_encoder_push(stream, mic_samples, mic_sample_count, &count, &error);
+///     for (size_t i = 0; i < count; i++) {
+///         uint8_t packet[4000];
+///         size_t packet_size = 0;
+///         opus_ffi_stream_encoder_next_packet(stream, packet, sizeof(packet), &packet_size, &error);
+///         send_packet(packet, packet_size);
+///     }
+///     opus_ffi_free_stream_encoder(stream);
+/// }
+/// ```
+#[export_name = "opus_ffi_new_stream_encoder"]
+pub extern "C" fn new_stream_encoder(
+    channels: u32,
+    sample_rate: u32,
+    application: u32,
+    frame_duration_us: u32,
+    result: *mut *mut StreamEncoder,
+    error: *mut OpusError,
+) -> c_int {
+    if result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let channels = channels_from(channels)?;
+        let application = application_from(application)?;
+        let stream = StreamEncoder::new(sample_rate, channels, application, frame_duration_us)?;
+        unsafe {
+            *result = handle::into_handle(stream);
+        }
+
+        Ok(())
+    })
+}
+
+/// 获取流式编码器内部使用的编码器
+///
+/// # 参数
+///
+/// * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+/// * `result` - 输出参数，编码器指针。可以传给 `opus_ffi_set_encoder_bitrate`、`opus_ffi_set_encoder_dtx` 等函数调整编码参数
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 安全性
+///
+/// * 返回的编码器归流式编码器所有，不能调用 `opus_ffi_free_encoder` 释放，也不能在 `opus_ffi_free_stream_encoder` 之后继续使用
+/// * 通过它的调用与流式编码器上的调用依次执行；流式编码器释放后它随之失效，使用时返回 `OPUS_ERROR_CODE_INVALID_HANDLE`
+/// * 不要直接用它调用 `opus_ffi_encode`，否则编码器状态与数据包队列不一致
+#[export_name = "opus_ffi_stream_encoder_get_encoder"]
+pub extern "C" fn stream_encoder_get_encoder(
+    stream: *mut StreamEncoder,
+    result: *mut *mut Encoder,
+    error: *mut OpusError,
+) -> c_int {
+    if stream.is_null() || result.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut stream = handle::get_mut(stream)?;
+        if stream.encoder_handle.is_null() {
+            let encoder: *mut Encoder = stream.encoder_mut();
+            stream.encoder_handle = handle::register_borrowed(&stream, encoder)?;
+        }
+        unsafe {
+            *result = stream.encoder_handle;
+        }
+
+        Ok(())
+    })
+}
+
+/// 向流式编码器写入 PCM 样本（16 位整数）
+///
+/// # 参数
+///
+/// * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+/// * `input` - 指向交错 PCM 样本的指针（16 位有符号整数）
+/// * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍，长度不要求是整帧
+/// * `packet_count` - 可选的输出参数，写入后队列中等待取出的数据包数量
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 每凑满一帧就编码一个数据包放入队列，不足一帧的样本暂存到下一次写入或 `opus_ffi_stream_encoder_flush`
+/// * 数据包通过 `opus_ffi_stream_encoder_next_packet` 按顺序取出
+/// * 整帧按 16 位整数编码，得到的数据包与对同样的 PCM 逐帧调用 `opus_ffi_encode` 相同
+#[export_name = "opus_ffi_stream_encoder_push"]
+pub extern "C" fn stream_encoder_push(
+    stream: *mut StreamEncoder,
+    input: *const i16,
+    input_size: u32,
+    packet_count: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if stream.is_null() || input.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut stream = handle::get_mut(stream)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        stream.push(input)?;
+        write_packet_count(&stream, packet_count);
+
+        Ok(())
+    })
+}
+
+/// 向流式编码器写入 PCM 样本（32 位浮点数）
+///
+/// # 参数
+///
+/// * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+/// * `input` - 指向交错 PCM 样本的指针（32 位浮点数，范围通常在 [-1.0, 1.0] 之间）
+/// * `input_size` - 输入样本的总数（所有声道），必须是声道数的整数倍
+/// * `packet_count` - 可选的输出参数，写入后队列中等待取出的数据包数量
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+#[export_name = "opus_ffi_stream_encoder_push_float"]
+pub extern "C" fn stream_encoder_push_float(
+    stream: *mut StreamEncoder,
+    input: *const f32,
+    input_size: u32,
+    packet_count: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if stream.is_null() || input.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut stream = handle::get_mut(stream)?;
+        let input = unsafe { std::slice::from_raw_parts(input, input_size as usize) };
+        stream.push_float(input)?;
+        write_packet_count(&stream, packet_count);
+
+        Ok(())
+    })
+}
+
+/// 用静音补齐暂存的样本并编码为最后一个数据包
+///
+/// # 参数
+///
+/// * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+/// * `packet_count` - 可选的输出参数，补齐后队列中等待取出的数据包数量
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 没有暂存样本时不产生数据包
+/// * 补齐后可以继续写入，例如在一段录音结束后开始下一段
+#[export_name = "opus_ffi_stream_encoder_flush"]
+pub extern "C" fn stream_encoder_flush(
+    stream: *mut StreamEncoder,
+    packet_count: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if stream.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut stream = handle::get_mut(stream)?;
+        stream.flush()?;
+        write_packet_count(&stream, packet_count);
+
+        Ok(())
+    })
+}
+
+/// 取出下一个已编码的数据包
+///
+/// # 参数
+///
+/// * `stream` - 之前通过 `opus_ffi_new_stream_encoder` 创建的流式编码器
+/// * `output` - 指向输出缓冲区的指针，用于存储数据包
+/// * `output_size` - 输出缓冲区的大小（字节数），建议不小于 4000
+/// * `packet_size` - 输出参数，数据包长度（字节数），`0` 表示队列中没有数据包
+/// * `error` - 可选的错误输出参数
+///
+/// # 返回值
+///
+/// * `0` - 成功
+/// * 负数 - 错误代码（错误详情在 `error` 参数中）
+///
+/// # 注意事项
+///
+/// * 缓冲区放不下数据包时返回 `BufferTooSmall` 错误，数据包不会被丢弃，可以换用更大的缓冲区重新读取
+#[export_name = "opus_ffi_stream_encoder_next_packet"]
+pub extern "C" fn stream_encoder_next_packet(
+    stream: *mut StreamEncoder,
+    output: *mut u8,
+    output_size: usize,
+    packet_size: *mut usize,
+    error: *mut OpusError,
+) -> c_int {
+    if stream.is_null() || output.is_null() || packet_size.is_null() {
+        return invalid_input(error);
+    }
+
+    ffi_exec(error, || {
+        let mut stream = handle::get_mut(stream)?;
+        let output = unsafe { std::slice::from_raw_parts_mut(output, output_size) };
+        let size = stream.next_packet(output)?;
+        unsafe {
+            (*packet_size) = size;
+        }
+
+        Ok(())
+    })
+}
+
+/// 释放流式编码器实例
+///
+/// # 参数
+///
+/// * `stream` - 通过 `opus_ffi_new_stream_encoder` 创建的流式编码器指针
+///
+/// # 安全性
+///
+/// * 如果 `stream` 为 `NULL`，函数不会执行任何操作
+/// * 队列中尚未取出的数据包和暂存的样本会被丢弃，需要时先调用 `opus_ffi_stream_encoder_flush` 并取出全部数据包
+/// * 释放后，`stream` 指针以及通过 `opus_ffi_stream_encoder_get_encoder` 获取的编码器指针都将不再有效
+///
+/// # 返回值
+///
+/// * `0` - 成功，或 `stream` 为 `NULL`
+/// * `OPUS_ERROR_CODE_INVALID_HANDLE` - `stream` 不是有效的流式编码器句柄（错误详情可通过 `opus_ffi_last_error_message` 获取）
+#[export_name = "opus_ffi_free_stream_encoder"]
+pub extern "C" fn free_stream_encoder(stream: *mut StreamEncoder) -> c_int {
+    // 内部编码器的句柄与流式编码器在同一次加锁中注销，不会留下失效的句柄
+    match handle::release(stream) {
+        Ok(()) => 0,
+        Err(e) => OpusError::fill(std::ptr::null_mut(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::set_encoder_bitrate;
+    use std::ptr;
+
+    #[test]
+    fn test_null_checks_stream_encoder() {
+        let expected_error = invalid_input(ptr::null_mut());
+        let res = new_stream_encoder(1, 16000, 1, 20000, ptr::null_mut(), ptr::null_mut());
+        assert_eq!(res, expected_error);
+
+        let res = stream_encoder_push(
+            ptr::null_mut(),
+            ptr::null(),
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, expected_error);
+
+        let res = stream_encoder_next_packet(
+            ptr::null_mut(),
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(res, expected_error);
+        assert_eq!(free_stream_encoder(ptr::null_mut()), 0);
+    }
+
+    #[test]
+    fn test_invalid_frame_duration() {
+        let mut stream = ptr::null_mut();
+        let res = new_stream_encoder(1, 16000, 1, 15000, &mut stream, ptr::null_mut());
+        assert_eq!(res, ErrorCode::BadArg as c_int);
+        assert!(stream.is_null());
+    }
+
+    #[test]
+    fn test_stream_encoder_chunks() {
+        let mut stream = ptr::null_mut();
+        assert_eq!(
+            new_stream_encoder(1, 16000, 1, 20000, &mut stream, ptr::null_mut()),
+            0
+        );
+        let mut encoder = ptr::null_mut();
+        assert_eq!(
+            stream_encoder_get_encoder(stream, &mut encoder, ptr::null_mut()),
+            0
+        );
+        assert_eq!(set_encoder_bitrate(encoder, 16000, ptr::null_mut()), 0);
+
+        // 1010 个样本分成大小不一的块写入：3 个整帧（960 个样本），剩余 50 个样本
+        let pcm: Vec<i16> = (0..1010)
+            .map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16)
+            .collect();
+        let mut count = 0;
+        for chunk in pcm.chunks(101) {
+            let res = stream_encoder_push(
+                stream,
+                chunk.as_ptr(),
+                chunk.len() as u32,
+                &mut count,
+                ptr::null_mut(),
+            );
+            assert_eq!(res, 0);
+        }
+        assert_eq!(count, 3);
+
+        // 补齐最后 50 个样本
+        assert_eq!(stream_encoder_flush(stream, &mut count, ptr::null_mut()), 0);
+        assert_eq!(count, 4);
+        assert_eq!(stream_encoder_flush(stream, &mut count, ptr::null_mut()), 0);
+        assert_eq!(count, 4);
+
+        let mut small = [0u8; 1];
+        let mut packet_size = 0;
+        let res = stream_encoder_next_packet(
+            stream,
+            small.as_mut_ptr(),
+            small.len(),
+            &mut packet_size,
+            ptr::null_mut(),
+        );
+        assert_eq!(res, ErrorCode::BufferTooSmall as c_int);
+
+        let mut decoder = opus_rs::Decoder::new(16000, Channels::Mono).unwrap();
+        let mut packet = [0u8; MAX_PACKET_SIZE];
+        let mut output = [0i16; 320];
+        let mut packets = 0;
+        loop {
+            let res = stream_encoder_next_packet(
+                stream,
+                packet.as_mut_ptr(),
+                packet.len(),
+                &mut packet_size,
+                ptr::null_mut(),
+            );
+            assert_eq!(res, 0);
+            if packet_size == 0 {
+                break;
+            }
+            let samples = decoder
+                .decode(&packet[..packet_size], &mut output, false)
+                .unwrap();
+            assert_eq!(samples, 320);
+            packets += 1;
+        }
+        assert_eq!(packets, 4);
+
+        assert_eq!(free_stream_encoder(stream), 0);
+        // 内部编码器的句柄随流式编码器一起注销
+        let res = set_encoder_bitrate(encoder, 16000, ptr::null_mut());
+        assert_eq!(res, crate::error::OpusErrorCode::InvalidHandle as c_int);
+    }
+
+    #[test]
+    fn test_stream_encoder_matches_encode() {
+        let mut stream = ptr::null_mut();
+        assert_eq!(
+            new_stream_encoder(1, 16000, 1, 20000, &mut stream, ptr::null_mut()),
+            0
+        );
+        let mut encoder = ptr::null_mut();
+        assert_eq!(
+            crate::encoder::new_encoder(1, 16000, 1, &mut encoder, ptr::null_mut()),
+            0
+        );
+
+        // 内部编码器的句柄与流式编码器的句柄互不影响
+        let mut inner = ptr::null_mut();
+        assert_eq!(
+            stream_encoder_get_encoder(stream, &mut inner, ptr::null_mut()),
+            0
+        );
+        assert_ne!(inner as usize, stream as usize);
+        assert_eq!(set_encoder_bitrate(inner, 24000, ptr::null_mut()), 0);
+        assert_eq!(set_encoder_bitrate(encoder, 24000, ptr::null_mut()), 0);
+
+        let pcm: Vec<i16> = (0..320 * 5)
+            .map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16)
+            .collect();
+        let mut count = 0;
+        for chunk in pcm.chunks(150) {
+            let res = stream_encoder_push(
+                stream,
+                chunk.as_ptr(),
+                chunk.len() as u32,
+                &mut count,
+                ptr::null_mut(),
+            );
+            assert_eq!(res, 0);
+        }
+        assert_eq!(count, 5);
+
+        // 与对同样的 PCM 逐帧调用 opus_ffi_encode 的结果完全相同
+        for frame in pcm.chunks(320) {
+            let mut expected = [0u8; MAX_PACKET_SIZE];
+            let mut expected_size = 0;
+            let res = crate::encoder::encode(
+                encoder,
+                frame.as_ptr(),
+                frame.len() as u32,
+                expected.as_mut_ptr(),
+                expected.len() as u32,
+                &mut expected_size,
+                ptr::null_mut(),
+            );
+            assert_eq!(res, 0);
+
+            let mut packet = [0u8; MAX_PACKET_SIZE];
+            let mut packet_size = 0;
+            let res = stream_encoder_next_packet(
+                stream,
+                packet.as_mut_ptr(),
+                packet.len(),
+                &mut packet_size,
+                ptr::null_mut(),
+            );
+            assert_eq!(res, 0);
+            assert_eq!(&packet[..packet_size], &expected[..expected_size]);
+        }
+
+        // 内部编码器不能单独释放，随流式编码器一起失效
+        let invalid_handle = crate::error::OpusErrorCode::InvalidHandle as c_int;
+        assert_eq!(crate::encoder::free_encoder(inner), invalid_handle);
+        assert_eq!(free_stream_encoder(stream), 0);
+        assert_eq!(
+            set_encoder_bitrate(inner, 24000, ptr::null_mut()),
+            invalid_handle
+        );
+        assert_eq!(crate::encoder::free_encoder(encoder), 0);
+    }
+
+    #[test]
+    fn test_stream_encoder_mixed_formats() {
+        let mut stream =
+            StreamEncoder::new(16000, Channels::Mono, Application::Voip, 20000).unwrap();
+        assert_eq!(stream.push(&[0i16; 100]).unwrap(), 0);
+        // 暂存的整数样本与之后写入的浮点样本拼成一帧
+        assert_eq!(stream.push_float(&[0.0; 220]).unwrap(), 1);
+        assert_eq!(stream.push_float(&[0.0; 100]).unwrap(), 0);
+        assert_eq!(stream.push(&[0i16; 540]).unwrap(), 2);
+        assert_eq!(stream.flush().unwrap(), 0);
+        assert_eq!(stream.packet_count(), 3);
+    }
+
+    #[test]
+    fn test_stream_encoder_stereo() {
+        let mut stream =
+            StreamEncoder::new(48000, Channels::Stereo, Application::Audio, 10000).unwrap();
+        // 奇数个样本不是整数个立体声采样点
+        assert!(stream.push(&[0i16; 3]).is_err());
+        assert_eq!(stream.push_float(&[0.0; 480 * 2 * 2 + 2]).unwrap(), 2);
+        assert_eq!(stream.flush().unwrap(), 1);
+        assert_eq!(stream.packet_count(), 3);
+    }
+}
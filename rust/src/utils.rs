@@ -1,7 +1,14 @@
-use opus_rs::{Error, ErrorCode};
+use opus_rs::{Application, Channels, ErrorCode};
 
-use crate::error::OpusError;
-use std::ffi::{c_char, c_int, CString};
+use crate::error::{panic_error, set_last_error, unsupported_error, OpusError, OpusErrorCode};
+use std::any::Any;
+use std::cell::Cell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
 
 /// 释放由 Rust 分配的 C 字符串
 ///
@@ -20,10 +27,10 @@ use std::ffi::{c_char, c_int, CString};
 /// ```c
 /// char *message = NULL;
 /// // ... 从 Rust 函数获取字符串 ...
-/// free_c_string(&message);
+/// opus_ffi_free_c_string(&message);
 /// // message 现在为 NULL
 /// ```
-#[no_mangle]
+#[export_name = "opus_ffi_free_c_string"]
 pub extern "C" fn free_c_string(p: *mut *mut c_char) {
     unsafe {
         if !p.is_null() {
@@ -58,9 +65,9 @@ pub extern "C" fn free_c_string(p: *mut *mut c_char) {
 /// ```c
 /// OpusError *error = malloc(sizeof(OpusError));
 /// // ... 使用 error ...
-/// free_opus_error(error);
+/// opus_ffi_free_opus_error(error);
 /// ```
-#[no_mangle]
+#[export_name = "opus_ffi_free_opus_error"]
 pub extern "C" fn free_opus_error(e: *mut OpusError) {
     if !e.is_null() {
         unsafe {
@@ -72,41 +79,329 @@ pub extern "C" fn free_opus_error(e: *mut OpusError) {
     }
 }
 
-pub(crate) fn invalid_input(error: *mut OpusError) -> c_int {
-    let code = ErrorCode::Unknown as c_int - 1;
+/// 释放由 Rust 分配的字节缓冲区
+///
+/// # 参数
+///
+/// * `data` - 由 Rust 函数返回的缓冲区指针（例如 `opus_ffi_ogg_writer_finish` 输出的数据）
+/// * `size` - 缓冲区的长度（字节数），必须与返回时的长度一致
+///
+/// # 安全性
+///
+/// * 如果 `data` 为 `NULL`，函数不会执行任何操作
+/// * 只能释放由 Rust 代码分配并返回的缓冲区，且每个缓冲区只能释放一次
+///
+/// # 示例
+///
+/// ```c
+/// uint8_t *data = NULL;
+/// size_t size = 0;
+/// // ... 从 Rust 函数获取缓冲区 ...
+/// opus_ffi_free_buffer(data, size);
+/// ```
+#[export_name = "opus_ffi_free_buffer"]
+pub extern "C" fn free_buffer(data: *mut u8, size: usize) {
+    if !data.is_null() {
+        unsafe {
+            let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(data, size));
+        }
+    }
+}
+
+/// 将 Rust 缓冲区的所有权转交给调用者，调用者需使用 `opus_ffi_free_buffer` 释放
+pub(crate) fn into_raw_buffer(data: Vec<u8>, out: *mut *mut u8, out_size: *mut usize) {
+    let size = data.len();
+    let data = Box::into_raw(data.into_boxed_slice()) as *mut u8;
+    unsafe {
+        (*out) = data;
+        (*out_size) = size;
+    }
+}
+
+/// 文件或内存输出目标
+pub(crate) enum Sink {
+    File(BufWriter<File>),
+    Memory(Vec<u8>),
+}
+
+impl Sink {
+    pub(crate) fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        match self {
+            Sink::File(file) => file.write_all(data),
+            Sink::Memory(buffer) => {
+                buffer.extend_from_slice(data);
+                Ok(())
+            }
+        }
+    }
+
+    /// 结束输出：文件刷新到磁盘并返回 `None`，内存目标返回全部数据
+    pub(crate) fn finish(&mut self) -> io::Result<Option<Vec<u8>>> {
+        match self {
+            Sink::File(file) => {
+                file.flush()?;
+                Ok(None)
+            }
+            Sink::Memory(buffer) => Ok(Some(std::mem::take(buffer))),
+        }
+    }
+}
+
+/// 文件或内存输入来源
+pub(crate) enum Source {
+    File(BufReader<File>),
+    Memory(Cursor<Vec<u8>>),
+}
+
+impl Read for Source {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Source::File(file) => file.read(buf),
+            Source::Memory(cursor) => cursor.read(buf),
+        }
+    }
+}
+
+impl Seek for Source {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Source::File(file) => file.seek(pos),
+            Source::Memory(cursor) => cursor.seek(pos),
+        }
+    }
+}
+
+/// 宽松模式开关，默认关闭
+static LENIENT_MODE: AtomicBool = AtomicBool::new(false);
+
+/// 设置宽松模式
+///
+/// # 参数
+///
+/// * `enabled` - `true` 表示开启宽松模式：不支持的声道数按单声道处理，不支持的应用模式按 Voip 处理（旧版行为）；
+///   `false` 表示关闭（默认），此时这些值会返回错误
+///
+/// # 注意事项
+///
+/// * 该设置是全局的，对之后所有线程创建的编码器、解码器和 Ogg 写入器生效
+/// * 宽松模式只用于兼容依赖旧版行为的调用者，新代码应传入正确的参数
+///
+/// # 示例
+///
+/// ```c
+/// opus_ffi_set_lenient_mode(true);
+/// // 声道数 6 会被当作单声道，不再返回错误
+/// opus_ffi_new_encoder(6, 16000, 1, &encoder, &error);
+/// ```
+#[export_name = "opus_ffi_set_lenient_mode"]
+pub extern "C" fn set_lenient_mode(enabled: bool) {
+    LENIENT_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// 获取当前是否开启了宽松模式
+#[export_name = "opus_ffi_get_lenient_mode"]
+pub extern "C" fn get_lenient_mode() -> bool {
+    LENIENT_MODE.load(Ordering::Relaxed)
+}
+
+/// 将 C 接口传入的声道数转换为 `Channels`
+///
+/// 不支持的值返回错误，宽松模式下默认为单声道。
+pub(crate) fn channels_from(channels: u32) -> Result<Channels, OpusError> {
+    match channels {
+        1 => Ok(Channels::Mono),
+        2 => Ok(Channels::Stereo),
+        _ if get_lenient_mode() => Ok(Channels::Mono),
+        _ => Err(unsupported_error(&format!(
+            "Unsupported channel count: {channels}, expected 1 or 2"
+        ))),
+    }
+}
+
+/// 将 C 接口传入的应用模式转换为 `Application`
+///
+/// 不支持的值返回错误，宽松模式下默认为 Voip。
+pub(crate) fn application_from(application: u32) -> Result<Application, OpusError> {
+    match application {
+        1 => Ok(Application::Voip),
+        2 => Ok(Application::Audio),
+        3 => Ok(Application::LowDelay),
+        _ if get_lenient_mode() => Ok(Application::Voip),
+        _ => Err(unsupported_error(&format!(
+            "Unsupported application: {application}, expected 1 (Voip), 2 (Audio) or 3 (LowDelay)"
+        ))),
+    }
+}
+
+pub(crate) fn fill_error(error: *mut OpusError, code: c_int, message: &str) -> c_int {
+    set_last_error(code, message);
     if !error.is_null() {
         unsafe {
             (*error).code = code;
-            (*error).message = CString::new("Invalid input").unwrap().into_raw();
+            (*error).message = CString::new(message).unwrap().into_raw();
         }
     }
 
     code
 }
 
-pub(crate) fn ffi_exec<F: FnOnce() -> Result<(), Error> + std::panic::UnwindSafe>(
+pub(crate) fn invalid_input(error: *mut OpusError) -> c_int {
+    fill_error(error, OpusErrorCode::InvalidInput as c_int, "Invalid input")
+}
+
+pub(crate) fn invalid_input_error() -> OpusError {
+    OpusError::new(OpusErrorCode::InvalidInput as c_int, "Invalid input")
+}
+
+pub(crate) fn buffer_too_small_error(required: usize, capacity: usize) -> OpusError {
+    OpusError::new(
+        ErrorCode::BufferTooSmall as c_int,
+        &format!("Output buffer too small: {required} samples required, {capacity} available"),
+    )
+}
+
+/// 检查 libopus 函数的返回值，负数转换为 `OpusError`
+pub(crate) fn check(function: &str, code: c_int) -> Result<usize, OpusError> {
+    if code < 0 {
+        let description = unsafe { CStr::from_ptr(audiopus_sys::opus_strerror(code)) };
+        return Err(OpusError::new(
+            code,
+            &format!("{function}: {}", description.to_string_lossy()),
+        ));
+    }
+
+    Ok(code as usize)
+}
+
+/// 按声道数把交错的样本总数换算为每声道样本数（帧大小）
+pub(crate) fn frame_size(samples: usize, channels: usize) -> Result<usize, OpusError> {
+    if !samples.is_multiple_of(channels) {
+        return Err(OpusError::new(
+            ErrorCode::BadArg as c_int,
+            "Sample count must be a multiple of the channel count",
+        ));
+    }
+
+    Ok(samples / channels)
+}
+
+thread_local! {
+    /// 本线程最近一次 panic 的位置，由 panic hook 记录
+    static PANIC_LOCATION: Cell<Option<String>> = const { Cell::new(None) };
+}
+
+static PANIC_HOOK: Once = Once::new();
+
+/// 安装记录 panic 位置的 hook，之前的 hook（默认输出到 stderr）仍会被调用
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if let Some(location) = info.location() {
+                PANIC_LOCATION.with(|last| last.set(Some(location.to_string())));
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// 取出 panic 信息，`panic!` 的参数为 `&str` 或 `String`
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message;
+    }
+
+    payload
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .unwrap_or("non-string panic payload")
+}
+
+/// 执行接口函数的主体，把错误和 panic 转换为错误代码
+///
+/// panic 时错误描述包含 panic 信息、发生位置以及调用 `ffi_exec` 的接口函数位置。
+#[track_caller]
+pub(crate) fn ffi_exec<F: FnOnce() -> Result<(), OpusError> + std::panic::UnwindSafe>(
     error: *mut OpusError,
     f: F,
 ) -> c_int {
+    let entry = Location::caller();
+    install_panic_hook();
+
     let result = std::panic::catch_unwind(f);
     match result {
         Ok(Ok(())) => 0,
         Ok(Err(e)) => OpusError::fill(error, e),
-        Err(_) => {
-            // Panic occurred
-            // We can't easily get the panic message here safely across FFI without more work,
-            // so we just return a generic error code for now.
-            // In a real app we might want to log this or try to set the error message.
-            let code = ErrorCode::Unknown as c_int - 2;
-            if !error.is_null() {
-                unsafe {
-                    (*error).code = code; // Generic error
-                    let msg = CString::new("Rust panic occurred").unwrap();
-                    (*error).message = msg.into_raw();
-                }
-            }
+        Err(payload) => {
+            let location = PANIC_LOCATION
+                .with(Cell::take)
+                .unwrap_or_else(|| "unknown location".to_string());
+            let message = format!(
+                "Rust panic in FFI call at {entry}: {} (panicked at {location})",
+                panic_message(payload.as_ref())
+            );
 
-            code
+            OpusError::fill(error, panic_error(&message))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decoder::new_decoder, encoder::new_encoder};
+    use std::ptr;
+
+    // 宽松模式是全局设置，相关断言放在同一个测试中，避免与其他测试并发修改
+    #[test]
+    fn test_unsupported_arguments() {
+        let expected_error = OpusErrorCode::Unsupported as c_int;
+
+        let mut encoder = ptr::null_mut();
+        let mut error = OpusError {
+            code: 0,
+            message: ptr::null_mut(),
+        };
+        let res = new_encoder(6, 16000, 1, &mut encoder, &mut error);
+        assert_eq!(res, expected_error);
+        assert!(error.message().contains("channel"));
+        assert!(encoder.is_null());
+
+        let res = new_encoder(1, 16000, 7, &mut encoder, ptr::null_mut());
+        assert_eq!(res, expected_error);
+        let mut decoder = ptr::null_mut();
+        let res = new_decoder(0, 16000, &mut decoder, ptr::null_mut());
+        assert_eq!(res, expected_error);
+
+        set_lenient_mode(true);
+        assert!(get_lenient_mode());
+        assert_eq!(channels_from(6).unwrap(), Channels::Mono);
+        assert_eq!(application_from(7).unwrap(), Application::Voip);
+        set_lenient_mode(false);
+        assert!(channels_from(6).is_err());
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let mut error = OpusError {
+            code: 0,
+            message: ptr::null_mut(),
+        };
+        let res = ffi_exec(&mut error, || -> Result<(), OpusError> {
+            panic!("decoder state is {}", "broken")
+        });
+        assert_eq!(res, OpusErrorCode::Panic as c_int);
+        assert_eq!(error.code, res);
+        assert!(error.message().contains("decoder state is broken"));
+        // 接口函数位置和 panic 位置都指向本文件
+        assert_eq!(error.message().matches("utils.rs").count(), 2);
+
+        let res = ffi_exec(ptr::null_mut(), || -> Result<(), OpusError> {
+            panic!("static message")
+        });
+        assert_eq!(res, OpusErrorCode::Panic as c_int);
+        let message =
+            unsafe { std::ffi::CStr::from_ptr(crate::error::opus_ffi_last_error_message()) };
+        assert!(message.to_str().unwrap().contains("static message"));
+    }
+}
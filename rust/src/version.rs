@@ -0,0 +1,168 @@
+//! 版本与能力查询
+//!
+//! 调用方可以在加载动态库后先检查版本和能力，及早发现残留在 `jniLibs` 或 `Libraries` 中的旧版本库文件。
+
+use std::ffi::{c_char, CString};
+use std::sync::OnceLock;
+
+use crate::multistream::MAX_SURROUND_CHANNELS;
+
+/// 支持浮点 PCM 接口（`opus_ffi_encode_float`、`opus_ffi_decode_float` 等）
+pub const OPUS_FFI_CAP_FLOAT_API: u32 = 1 << 0;
+/// 支持多流（环绕声）编解码
+pub const OPUS_FFI_CAP_MULTISTREAM: u32 = 1 << 1;
+/// 支持 Ogg Opus 容器的读写
+pub const OPUS_FFI_CAP_OGG: u32 = 1 << 2;
+/// 支持数据包重组
+pub const OPUS_FFI_CAP_REPACKETIZER: u32 = 1 << 3;
+/// 启用了 `legacy-symbols` feature，同时导出不带 `opus_ffi_` 前缀的旧符号名
+pub const OPUS_FFI_CAP_LEGACY_SYMBOLS: u32 = 1 << 4;
+
+/// 编解码器支持的采样率（Hz），按从小到大排列
+pub(crate) const SUPPORTED_SAMPLE_RATES: [u32; 5] = [8000, 12000, 16000, 24000, 48000];
+
+/// 库的能力描述
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpusFfiCapabilities {
+    /// 本库的主版本号
+    pub version_major: u32,
+    /// 本库的次版本号
+    pub version_minor: u32,
+    /// 本库的修订号
+    pub version_patch: u32,
+    /// 支持的采样率（Hz），只有前 `sample_rate_count` 项有效
+    pub sample_rates: [u32; 8],
+    /// `sample_rates` 中有效项的数量
+    pub sample_rate_count: u32,
+    /// 普通编解码器支持的最大声道数
+    pub max_channels: u32,
+    /// 多流编解码器支持的最大声道数
+    pub max_surround_channels: u32,
+    /// `OPUS_FFI_CAP_*` 标志的组合
+    pub flags: u32,
+}
+
+impl OpusFfiCapabilities {
+    /// 当前构建的能力
+    pub fn current() -> Self {
+        let version = |s: &str| s.parse().unwrap_or(0);
+        let mut sample_rates = [0u32; 8];
+        sample_rates[..SUPPORTED_SAMPLE_RATES.len()].copy_from_slice(&SUPPORTED_SAMPLE_RATES);
+
+        let mut flags = OPUS_FFI_CAP_FLOAT_API
+            | OPUS_FFI_CAP_MULTISTREAM
+            | OPUS_FFI_CAP_OGG
+            | OPUS_FFI_CAP_REPACKETIZER;
+        if cfg!(feature = "legacy-symbols") {
+            flags |= OPUS_FFI_CAP_LEGACY_SYMBOLS;
+        }
+
+        Self {
+            version_major: version(env!("CARGO_PKG_VERSION_MAJOR")),
+            version_minor: version(env!("CARGO_PKG_VERSION_MINOR")),
+            version_patch: version(env!("CARGO_PKG_VERSION_PATCH")),
+            sample_rates,
+            sample_rate_count: SUPPORTED_SAMPLE_RATES.len() as u32,
+            max_channels: 2,
+            max_surround_channels: MAX_SURROUND_CHANNELS,
+            flags,
+        }
+    }
+}
+
+/// 获取版本字符串
+///
+/// # 返回值
+///
+/// 形如 `"opus_ffi 0.1.0 (libopus 1.3.1)"` 的 C 字符串，包含本库的版本号和底层 libopus 的版本，不会返回 `NULL`。
+///
+/// # 安全性
+///
+/// 返回的是静态字符串，调用者不能释放。
+///
+/// # 示例
+///
+/// ```c
+/// printf("%s\n", opus_ffi_version());
+/// ```
+#[no_mangle]
+pub extern "C" fn opus_ffi_version() -> *const c_char {
+    static VERSION: OnceLock<CString> = OnceLock::new();
+
+    VERSION
+        .get_or_init(|| {
+            let version = format!(
+                "opus_ffi {} ({})",
+                env!("CARGO_PKG_VERSION"),
+                opus_rs::version()
+            );
+            CString::new(version).unwrap_or_default()
+        })
+        .as_ptr()
+}
+
+/// 获取库的能力描述
+///
+/// # 参数
+///
+/// * `result` - 输出参数，用于接收能力描述，为 `NULL` 时不做任何操作
+///
+/// # 示例
+///
+/// ```c
+/// OpusFfiCapabilities caps;
+/// opus_ffi_get_capabilities(&caps);
+/// if (caps.version_major != 0 || !(caps.flags & OPUS_FFI_CAP_MULTISTREAM)) {
+///     // 加载到了不匹配的库文件
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn opus_ffi_get_capabilities(result: *mut OpusFfiCapabilities) {
+    if result.is_null() {
+        return;
+    }
+
+    unsafe {
+        (*result) = OpusFfiCapabilities::current();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn test_version() {
+        let version = unsafe { CStr::from_ptr(opus_ffi_version()) }
+            .to_str()
+            .unwrap();
+        assert!(version.starts_with(&format!("opus_ffi {} (", env!("CARGO_PKG_VERSION"))));
+        assert!(version.contains("libopus"));
+        // 每次返回同一个静态字符串
+        assert_eq!(opus_ffi_version(), opus_ffi_version());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let mut caps = std::mem::MaybeUninit::<OpusFfiCapabilities>::zeroed();
+        opus_ffi_get_capabilities(caps.as_mut_ptr());
+        let caps = unsafe { caps.assume_init() };
+
+        assert_eq!(caps, OpusFfiCapabilities::current());
+        assert_eq!(
+            &caps.sample_rates[..caps.sample_rate_count as usize],
+            &SUPPORTED_SAMPLE_RATES
+        );
+        assert_eq!(caps.max_channels, 2);
+        assert_eq!(caps.max_surround_channels, 8);
+        assert_ne!(caps.flags & OPUS_FFI_CAP_FLOAT_API, 0);
+        assert_eq!(
+            caps.flags & OPUS_FFI_CAP_LEGACY_SYMBOLS != 0,
+            cfg!(feature = "legacy-symbols")
+        );
+
+        opus_ffi_get_capabilities(std::ptr::null_mut());
+    }
+}
@@ -1,12 +1,37 @@
+use std::ffi::CString;
 use std::fs::File;
 use std::io::{Read as _, Write as _};
 use std::ptr;
 
 use anyhow::{anyhow, Ok};
-use opus_ffi::decoder::{decode, free_decoder, new_decoder};
-use opus_ffi::encoder::{encode, free_encoder, new_encoder};
+use opus_ffi::decoder::{
+    decode, decode_with_frame_size, free_decoder, get_decoder_final_range, new_decoder, Decoder,
+};
+use opus_ffi::encoder::{
+    encode, encode_with_result, free_encoder, get_encoder_final_range, get_encoder_inband_fec,
+    new_encoder, set_encoder_dtx, set_encoder_inband_fec, set_encoder_packet_loss_perc,
+    EncodeResult, Encoder,
+};
 use opus_ffi::error::OpusError;
-use opus_rs::{Decoder, Encoder};
+use opus_ffi::ogg::reader::{
+    free_ogg_reader, ogg_reader_get_head, ogg_reader_open_memory, ogg_reader_read, OggOpusHead,
+    OggOpusReader,
+};
+use opus_ffi::ogg::writer::{
+    free_ogg_writer, ogg_writer_finish, ogg_writer_open_file, ogg_writer_open_memory,
+    ogg_writer_write, OggOpusWriter,
+};
+use opus_ffi::packet_stream::analyzer::{analyze_legacy_file, LegacyStreamInfo};
+use opus_ffi::packet_stream::reader::{
+    free_packet_reader, packet_reader_get_header, packet_reader_next,
+    packet_reader_open_legacy_file, packet_reader_open_memory, PacketStreamReader,
+};
+use opus_ffi::packet_stream::writer::{
+    free_packet_writer, packet_writer_finish, packet_writer_open_memory, packet_writer_write,
+    PacketStreamWriter,
+};
+use opus_ffi::packet_stream::PacketStreamHeader;
+use opus_ffi::utils::free_buffer;
 
 const FRAME_SIZE: usize = 80;
 const CHANNELS: u32 = 1;
@@ -32,8 +57,8 @@ fn test_decode() -> anyhow::Result<()> {
     assert!(!decoder.is_null());
 
     let mut buffer = [0u8; FRAME_SIZE]; // 80 bytes input chunk
-                                                // Max frame size for Opus is 120ms. At 16kHz, 120ms = 1920 samples.
-                                                // We allocate enough space to avoid OPUS_BUFFER_TOO_SMALL if the packet contains more than 20ms.
+                                        // Max frame size for Opus is 120ms. At 16kHz, 120ms = 1920 samples.
+                                        // We allocate enough space to avoid OPUS_BUFFER_TOO_SMALL if the packet contains more than 20ms.
     let mut output_buffer = [0i16; FRAME_SIZE * 4];
     let mut decoded_samples: usize = 0;
     let mut total_decoded_samples = 0;
@@ -101,6 +126,8 @@ fn test_encode() -> anyhow::Result<()> {
     let res = new_encoder(CHANNELS, SAMPLE_RATE, APPLICATION, &mut encoder, &mut error);
     assert_eq!(res, 0);
     assert!(!encoder.is_null());
+    // Silent stretches produce DTX packets that don't need to be written.
+    assert_eq!(set_encoder_dtx(encoder, true, &mut error), 0);
 
     // 20ms frame at 16kHz = 80 samples.
     // 16-bit PCM = 2 bytes per sample.
@@ -108,7 +135,7 @@ fn test_encode() -> anyhow::Result<()> {
     const PCM_FRAME_SIZE: usize = FRAME_SIZE * 4;
     let mut pcm_buffer = [0u8; PCM_FRAME_SIZE];
     let mut output_buffer = [0u8; 1024]; // Max Opus packet is smaller than this
-    let mut encoded_len: usize = 0;
+    let mut encoded = EncodeResult::default();
 
     loop {
         // Read PCM data
@@ -125,13 +152,13 @@ fn test_encode() -> anyhow::Result<()> {
         let input_ptr = pcm_buffer.as_ptr() as *const i16;
         let input_samples = PCM_FRAME_SIZE / 2; // Convert bytes to samples (i16 = 2 bytes)
 
-        let res = encode(
+        let res = encode_with_result(
             encoder,
             input_ptr,
             input_samples as u32,
             output_buffer.as_mut_ptr(),
             output_buffer.len() as u32,
-            &mut encoded_len,
+            &mut encoded,
             &mut error,
         );
 
@@ -139,13 +166,16 @@ fn test_encode() -> anyhow::Result<()> {
             return Err(anyhow!("Encode error: {}", res));
         }
 
-        println!("Encoded size: {}", encoded_len);
+        println!("Encoded size: {}, dtx: {}", encoded.size, encoded.dtx);
+        if encoded.dtx {
+            continue;
+        }
 
         // Write encoded data to file
         // Note: Raw Opus file usually needs framing (like Ogg) to be playable.
         // Here we just write raw packets which might not be directly playable but verifies the encoder.
         // For a proper test we might want to write length-delimited packets or just verify it produces data.
-        output_file.write_all(&output_buffer[..encoded_len])?;
+        output_file.write_all(&output_buffer[..encoded.size])?;
     }
 
     // Cleanup
@@ -153,3 +183,562 @@ fn test_encode() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_fec_recovery() -> anyhow::Result<()> {
+    // 20ms frame at 16kHz = 320 samples.
+    const PCM_FRAME_SIZE: usize = 320;
+    const FRAMES: usize = 50;
+    // Every 5th packet is treated as lost.
+    const LOSS_INTERVAL: usize = 5;
+
+    // The decoded signal lags the input by the encoder lookahead (2.5ms + 4ms at 16kHz).
+    const DELAY: usize = 104;
+
+    // A voiced-like signal (two harmonics) so that SILK produces LBRR data. The pitch swings
+    // 220±120Hz three times a second like speech intonation, so concealment cannot simply
+    // repeat the last pitch period.
+    let signal = |n: usize| {
+        use std::f32::consts::PI;
+        let t = n as f32 / SAMPLE_RATE as f32;
+        let phase = 2.0 * PI * 220.0 * t - 120.0 / 3.0 * (2.0 * PI * 3.0 * t).cos();
+        let v = phase.sin() * 0.4 + (2.0 * phase).sin() * 0.2;
+        (v * i16::MAX as f32) as i16
+    };
+
+    let mut encoder: *mut Encoder = ptr::null_mut();
+    let mut decoder: *mut Decoder = ptr::null_mut();
+    // Takes the same packets but conceals the gaps without FEC, as a baseline.
+    let mut plc_decoder: *mut Decoder = ptr::null_mut();
+    let mut error = OpusError {
+        code: 0,
+        message: ptr::null_mut(),
+    };
+
+    let res = new_encoder(CHANNELS, SAMPLE_RATE, APPLICATION, &mut encoder, &mut error);
+    assert_eq!(res, 0);
+    let res = new_decoder(CHANNELS, SAMPLE_RATE, &mut decoder, &mut error);
+    assert_eq!(res, 0);
+    let res = new_decoder(CHANNELS, SAMPLE_RATE, &mut plc_decoder, &mut error);
+    assert_eq!(res, 0);
+
+    assert_eq!(set_encoder_inband_fec(encoder, true, &mut error), 0);
+    assert_eq!(set_encoder_packet_loss_perc(encoder, 20, &mut error), 0);
+    let mut fec_enabled = false;
+    assert_eq!(
+        get_encoder_inband_fec(encoder, &mut fec_enabled, &mut error),
+        0
+    );
+    assert!(fec_enabled);
+
+    let mut packets = Vec::with_capacity(FRAMES);
+    let mut output_buffer = [0u8; 1024];
+    let mut encoded_len: usize = 0;
+    for frame in 0..FRAMES {
+        let pcm: Vec<i16> = (0..PCM_FRAME_SIZE)
+            .map(|i| signal(frame * PCM_FRAME_SIZE + i))
+            .collect();
+
+        let res = encode(
+            encoder,
+            pcm.as_ptr(),
+            pcm.len() as u32,
+            output_buffer.as_mut_ptr(),
+            output_buffer.len() as u32,
+            &mut encoded_len,
+            &mut error,
+        );
+        if res < 0 {
+            return Err(anyhow!("Encode error: {}", res));
+        }
+        packets.push(output_buffer[..encoded_len].to_vec());
+    }
+
+    // Squared error of a decoded frame against the original PCM it should reproduce.
+    let distance = |frame: usize, pcm: &[i16]| -> f64 {
+        pcm.iter()
+            .enumerate()
+            .map(|(i, &s)| {
+                let n = (frame * PCM_FRAME_SIZE + i).saturating_sub(DELAY);
+                let diff = s as f64 - signal(n) as f64;
+                diff * diff
+            })
+            .sum()
+    };
+
+    // Large enough for a 120ms packet, the frame size is passed explicitly for FEC recovery.
+    let mut pcm_buffer = [0i16; PCM_FRAME_SIZE * 6];
+    let mut plc_buffer = [0i16; PCM_FRAME_SIZE * 6];
+    let mut decoded_samples: usize = 0;
+    let mut recovered = 0;
+    let mut fec_error = 0.0;
+    let mut plc_error = 0.0;
+    for (index, packet) in packets.iter().enumerate() {
+        if index % LOSS_INTERVAL == LOSS_INTERVAL - 1 {
+            // Packet lost: nothing to decode until the next one arrives.
+            continue;
+        }
+
+        if index > 0 && (index - 1) % LOSS_INTERVAL == LOSS_INTERVAL - 1 {
+            // The previous packet was lost, recover it from the FEC data carried by this one.
+            // The frame size must match the duration of the lost packet exactly.
+            let res = decode_with_frame_size(
+                decoder,
+                packet.as_ptr(),
+                packet.len() as u32,
+                pcm_buffer.as_mut_ptr(),
+                pcm_buffer.len() as u32,
+                PCM_FRAME_SIZE as u32,
+                true,
+                &mut decoded_samples,
+                &mut error,
+            );
+            if res < 0 {
+                return Err(anyhow!("FEC decode error: {}", res));
+            }
+            assert_eq!(decoded_samples, PCM_FRAME_SIZE);
+
+            // The same gap without FEC falls back to packet loss concealment.
+            let res = decode_with_frame_size(
+                plc_decoder,
+                packet.as_ptr(),
+                0,
+                plc_buffer.as_mut_ptr(),
+                plc_buffer.len() as u32,
+                PCM_FRAME_SIZE as u32,
+                false,
+                &mut decoded_samples,
+                &mut error,
+            );
+            if res < 0 {
+                return Err(anyhow!("PLC decode error: {}", res));
+            }
+            assert_eq!(decoded_samples, PCM_FRAME_SIZE);
+
+            let fec = &pcm_buffer[..PCM_FRAME_SIZE];
+            let plc = &plc_buffer[..PCM_FRAME_SIZE];
+            assert_ne!(fec, plc);
+            fec_error += distance(index - 1, fec);
+            plc_error += distance(index - 1, plc);
+            recovered += 1;
+        }
+
+        for (decoder, buffer) in [(decoder, &mut pcm_buffer), (plc_decoder, &mut plc_buffer)] {
+            let res = decode(
+                decoder,
+                packet.as_ptr(),
+                packet.len() as u32,
+                buffer.as_mut_ptr(),
+                buffer.len() as u32,
+                false,
+                &mut decoded_samples,
+                &mut error,
+            );
+            if res < 0 {
+                return Err(anyhow!("Decode error: {}", res));
+            }
+            assert_eq!(decoded_samples, PCM_FRAME_SIZE);
+        }
+    }
+
+    assert_eq!(recovered, FRAMES / LOSS_INTERVAL - 1);
+    // The recovered frames follow the original much more closely than concealment alone.
+    assert!(
+        fec_error < plc_error / 2.0,
+        "FEC error {fec_error} is not below half the PLC error {plc_error}"
+    );
+
+    free_encoder(encoder);
+    free_decoder(decoder);
+    free_decoder(plc_decoder);
+
+    Ok(())
+}
+
+#[test]
+fn test_final_range() -> anyhow::Result<()> {
+    const PCM_FRAME_SIZE: usize = 320;
+
+    let mut encoder: *mut Encoder = ptr::null_mut();
+    let mut decoder: *mut Decoder = ptr::null_mut();
+    let mut error = OpusError {
+        code: 0,
+        message: ptr::null_mut(),
+    };
+    assert_eq!(
+        new_encoder(CHANNELS, SAMPLE_RATE, APPLICATION, &mut encoder, &mut error),
+        0
+    );
+    assert_eq!(
+        new_decoder(CHANNELS, SAMPLE_RATE, &mut decoder, &mut error),
+        0
+    );
+
+    // Every decoded packet must leave the decoder in the same range coder state as the encoder.
+    let mut packet = [0u8; 1024];
+    let mut pcm_buffer = [0i16; PCM_FRAME_SIZE];
+    for frame in 0..20 {
+        let pcm: Vec<i16> = (0..PCM_FRAME_SIZE)
+            .map(|i| {
+                let t = (frame * PCM_FRAME_SIZE + i) as f32 / SAMPLE_RATE as f32;
+                ((2.0 * std::f32::consts::PI * 330.0 * t).sin() * 0.3 * i16::MAX as f32) as i16
+            })
+            .collect();
+
+        let mut encoded_len = 0;
+        let res = encode(
+            encoder,
+            pcm.as_ptr(),
+            pcm.len() as u32,
+            packet.as_mut_ptr(),
+            packet.len() as u32,
+            &mut encoded_len,
+            &mut error,
+        );
+        assert_eq!(res, 0);
+        let mut encoder_range = 0;
+        assert_eq!(
+            get_encoder_final_range(encoder, &mut encoder_range, &mut error),
+            0
+        );
+
+        let mut decoded_samples = 0;
+        let res = decode(
+            decoder,
+            packet.as_ptr(),
+            encoded_len as u32,
+            pcm_buffer.as_mut_ptr(),
+            pcm_buffer.len() as u32,
+            false,
+            &mut decoded_samples,
+            &mut error,
+        );
+        assert_eq!(res, 0);
+        let mut decoder_range = 0;
+        assert_eq!(
+            get_decoder_final_range(decoder, &mut decoder_range, &mut error),
+            0
+        );
+
+        assert_eq!(encoder_range, decoder_range, "frame {frame}");
+    }
+
+    free_encoder(encoder);
+    free_decoder(decoder);
+
+    Ok(())
+}
+
+#[test]
+fn test_encode_ogg() -> anyhow::Result<()> {
+    // 1.01s of audio, deliberately not a whole number of 20ms frames.
+    const SAMPLES: usize = 16160;
+
+    let pcm: Vec<i16> = (0..SAMPLES)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            ((2.0 * std::f32::consts::PI * 440.0 * t).sin() * 0.5 * i16::MAX as f32) as i16
+        })
+        .collect();
+
+    let mut writer: *mut OggOpusWriter = ptr::null_mut();
+    let mut error = OpusError {
+        code: 0,
+        message: ptr::null_mut(),
+    };
+
+    let res = ogg_writer_open_memory(CHANNELS, SAMPLE_RATE, APPLICATION, &mut writer, &mut error);
+    assert_eq!(res, 0);
+
+    // Push the PCM in uneven chunks, the writer buffers partial frames internally.
+    for chunk in pcm.chunks(1000) {
+        let res = ogg_writer_write(writer, chunk.as_ptr(), chunk.len() as u32, &mut error);
+        if res < 0 {
+            return Err(anyhow!("Ogg write error: {}", res));
+        }
+    }
+
+    let mut data: *mut u8 = ptr::null_mut();
+    let mut data_size: usize = 0;
+    let res = ogg_writer_finish(writer, &mut data, &mut data_size, &mut error);
+    assert_eq!(res, 0);
+    free_ogg_writer(writer);
+
+    let bytes = unsafe { std::slice::from_raw_parts(data, data_size) }.to_vec();
+    free_buffer(data, data_size);
+
+    // Walk the pages: OpusHead on the first page, OpusTags on the second, EOS on the last.
+    let mut offset = 0;
+    let mut pages = Vec::new();
+    while offset < bytes.len() {
+        assert_eq!(&bytes[offset..offset + 4], b"OggS");
+        let flags = bytes[offset + 5];
+        let granule = i64::from_le_bytes(bytes[offset + 6..offset + 14].try_into()?);
+        let segments = bytes[offset + 26] as usize;
+        let body_size: usize = bytes[offset + 27..offset + 27 + segments]
+            .iter()
+            .map(|&s| s as usize)
+            .sum();
+        let body_start = offset + 27 + segments;
+        pages.push((flags, granule, &bytes[body_start..body_start + body_size]));
+        offset = body_start + body_size;
+    }
+
+    assert!(pages.len() >= 3);
+    let (flags, _, head) = pages[0];
+    assert_eq!(flags, 0x02);
+    assert_eq!(&head[..8], b"OpusHead");
+    assert_eq!(head[9], CHANNELS as u8);
+    let pre_skip = u16::from_le_bytes(head[10..12].try_into()?) as i64;
+    assert_eq!(u32::from_le_bytes(head[12..16].try_into()?), SAMPLE_RATE);
+    assert_eq!(&pages[1].2[..8], b"OpusTags");
+
+    let (flags, granule, _) = pages[pages.len() - 1];
+    assert_eq!(flags, 0x04);
+    // Granule positions are always counted at 48kHz.
+    assert_eq!(
+        granule,
+        pre_skip + (SAMPLES * 48000 / SAMPLE_RATE as usize) as i64
+    );
+
+    // The same stream can be written straight to a file.
+    let output_path = CString::new("data/R20250728-151607(encode).ogg")?;
+    let res = ogg_writer_open_file(
+        output_path.as_ptr(),
+        CHANNELS,
+        SAMPLE_RATE,
+        APPLICATION,
+        &mut writer,
+        &mut error,
+    );
+    assert_eq!(res, 0);
+    let res = ogg_writer_write(writer, pcm.as_ptr(), pcm.len() as u32, &mut error);
+    assert_eq!(res, 0);
+    let res = ogg_writer_finish(writer, ptr::null_mut(), ptr::null_mut(), &mut error);
+    assert_eq!(res, 0);
+    free_ogg_writer(writer);
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_ogg() -> anyhow::Result<()> {
+    // 0.5s of audio plus a partial frame, decoded back at 48kHz.
+    const SAMPLES: usize = 8050;
+    const OUTPUT_RATE: u32 = 48000;
+
+    let pcm: Vec<i16> = (0..SAMPLES)
+        .map(|i| ((i as f32 * 0.1).sin() * 8000.0) as i16)
+        .collect();
+
+    let mut writer: *mut OggOpusWriter = ptr::null_mut();
+    let mut error = OpusError {
+        code: 0,
+        message: ptr::null_mut(),
+    };
+    let res = ogg_writer_open_memory(CHANNELS, SAMPLE_RATE, APPLICATION, &mut writer, &mut error);
+    assert_eq!(res, 0);
+    let res = ogg_writer_write(writer, pcm.as_ptr(), pcm.len() as u32, &mut error);
+    assert_eq!(res, 0);
+    let mut data: *mut u8 = ptr::null_mut();
+    let mut data_size: usize = 0;
+    let res = ogg_writer_finish(writer, &mut data, &mut data_size, &mut error);
+    assert_eq!(res, 0);
+    free_ogg_writer(writer);
+
+    let mut reader: *mut OggOpusReader = ptr::null_mut();
+    let res = ogg_reader_open_memory(data, data_size, OUTPUT_RATE, &mut reader, &mut error);
+    free_buffer(data, data_size);
+    assert_eq!(res, 0);
+
+    let mut head = OggOpusHead::default();
+    let res = ogg_reader_get_head(reader, &mut head, &mut error);
+    assert_eq!(res, 0);
+    assert_eq!(head.channels, CHANNELS);
+    assert_eq!(head.input_sample_rate, SAMPLE_RATE);
+
+    // Pre-skip and the padding of the last frame are trimmed away.
+    let mut output = vec![0i16; 1234];
+    let mut total = 0;
+    loop {
+        let mut samples_read = 0;
+        let res = ogg_reader_read(
+            reader,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            &mut samples_read,
+            &mut error,
+        );
+        if res < 0 {
+            return Err(anyhow!("Ogg read error: {}", res));
+        }
+        if samples_read == 0 {
+            break;
+        }
+        total += samples_read;
+    }
+    free_ogg_reader(reader);
+
+    assert_eq!(total, SAMPLES * (OUTPUT_RATE / SAMPLE_RATE) as usize);
+
+    // Garbage is rejected with an error instead of being decoded.
+    let garbage = [0u8; 64];
+    let res = ogg_reader_open_memory(
+        garbage.as_ptr(),
+        garbage.len(),
+        OUTPUT_RATE,
+        &mut reader,
+        &mut error,
+    );
+    assert!(res < 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_packet_stream() -> anyhow::Result<()> {
+    let mut error = OpusError {
+        code: 0,
+        message: ptr::null_mut(),
+    };
+
+    // The existing recording has no header and fixed 80 byte packets, which the analyzer can detect.
+    let path = CString::new("data/R20250728-151607.opus")?;
+    let mut info = LegacyStreamInfo::default();
+    let res = analyze_legacy_file(path.as_ptr(), &mut info, &mut error);
+    assert_eq!(res, 0);
+    // Detected stride and sample rate must match how the recording was made.
+    assert_eq!(info.packet_size as usize, FRAME_SIZE);
+    assert_eq!(info.sample_rate, SAMPLE_RATE);
+    assert_eq!(info.channels, CHANNELS);
+    assert_eq!(info.frame_duration_us, 20000);
+    assert!(info.confidence > 0.5);
+
+    let mut reader: *mut PacketStreamReader = ptr::null_mut();
+    let res = packet_reader_open_legacy_file(
+        path.as_ptr(),
+        info.packet_size as usize,
+        info.sample_rate,
+        info.channels,
+        &mut reader,
+        &mut error,
+    );
+    assert_eq!(res, 0);
+
+    let mut packets = Vec::new();
+    let mut packet = [0u8; 4000];
+    loop {
+        let mut packet_size = 0;
+        let res = packet_reader_next(
+            reader,
+            packet.as_mut_ptr(),
+            packet.len(),
+            &mut packet_size,
+            &mut error,
+        );
+        assert_eq!(res, 0);
+        if packet_size == 0 {
+            break;
+        }
+        assert_eq!(packet_size, FRAME_SIZE);
+        packets.push(packet[..packet_size].to_vec());
+    }
+    free_packet_reader(reader);
+    assert!(!packets.is_empty());
+
+    // Re-encode a signal with VBR so packet sizes vary, and store it in the new format.
+    let mut encoder: *mut Encoder = ptr::null_mut();
+    let res = new_encoder(CHANNELS, SAMPLE_RATE, APPLICATION, &mut encoder, &mut error);
+    assert_eq!(res, 0);
+
+    let mut writer: *mut PacketStreamWriter = ptr::null_mut();
+    let res = packet_writer_open_memory(SAMPLE_RATE, CHANNELS, 20000, &mut writer, &mut error);
+    assert_eq!(res, 0);
+
+    let frame_samples = (SAMPLE_RATE / 50) as usize;
+    let mut sizes = Vec::new();
+    for frame in 0..50 {
+        let pcm: Vec<i16> = (0..frame_samples)
+            .map(|i| {
+                let t = (frame * frame_samples + i) as f32 / SAMPLE_RATE as f32;
+                // Alternate between silence and a tone to force different packet sizes.
+                let amplitude = if frame % 10 < 5 { 0.0 } else { 8000.0 };
+                ((2.0 * std::f32::consts::PI * 440.0 * t).sin() * amplitude) as i16
+            })
+            .collect();
+        let mut encoded_len = 0;
+        let res = encode(
+            encoder,
+            pcm.as_ptr(),
+            pcm.len() as u32,
+            packet.as_mut_ptr(),
+            packet.len() as u32,
+            &mut encoded_len,
+            &mut error,
+        );
+        assert_eq!(res, 0);
+        let res = packet_writer_write(writer, packet.as_ptr(), encoded_len, &mut error);
+        assert_eq!(res, 0);
+        sizes.push(encoded_len);
+    }
+    free_encoder(encoder);
+    assert!(sizes.iter().any(|&size| size != sizes[0]));
+
+    let mut data: *mut u8 = ptr::null_mut();
+    let mut data_size: usize = 0;
+    let res = packet_writer_finish(writer, &mut data, &mut data_size, &mut error);
+    assert_eq!(res, 0);
+    free_packet_writer(writer);
+
+    let res = packet_reader_open_memory(data, data_size, &mut reader, &mut error);
+    free_buffer(data, data_size);
+    assert_eq!(res, 0);
+
+    let mut header = PacketStreamHeader::default();
+    let res = packet_reader_get_header(reader, &mut header, &mut error);
+    assert_eq!(res, 0);
+    assert_eq!(header.sample_rate, SAMPLE_RATE);
+    assert_eq!(header.channels, CHANNELS);
+    assert_eq!(header.frame_duration_us, 20000);
+
+    let mut decoder: *mut Decoder = ptr::null_mut();
+    let res = new_decoder(CHANNELS, SAMPLE_RATE, &mut decoder, &mut error);
+    assert_eq!(res, 0);
+
+    let mut output = vec![0i16; frame_samples * 6];
+    let mut read_sizes = Vec::new();
+    loop {
+        let mut packet_size = 0;
+        let res = packet_reader_next(
+            reader,
+            packet.as_mut_ptr(),
+            packet.len(),
+            &mut packet_size,
+            &mut error,
+        );
+        assert_eq!(res, 0);
+        if packet_size == 0 {
+            break;
+        }
+        read_sizes.push(packet_size);
+
+        let mut decoded_samples = 0;
+        let res = decode(
+            decoder,
+            packet.as_ptr(),
+            packet_size as u32,
+            output.as_mut_ptr(),
+            output.len() as u32,
+            false,
+            &mut decoded_samples,
+            &mut error,
+        );
+        assert_eq!(res, 0);
+        assert_eq!(decoded_samples, frame_samples);
+    }
+    free_packet_reader(reader);
+    free_decoder(decoder);
+    assert_eq!(read_sizes, sizes);
+
+    Ok(())
+}